@@ -0,0 +1,378 @@
+//! Alert rules: conditions checked on every RPC tick, with notifications
+//! sent to a webhook and / or a user script.
+//!
+//! Each rule fires once when its condition has held long enough and sends
+//! a matching "resolved" notification when it clears, so a flapping or
+//! persistent problem never repeats the same alert on every tick.  Reorgs
+//! are one-off events and have no recovery.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncWriteExt, process::Command, time::timeout};
+
+/// Time allowed for the webhook request and for the script to finish.
+const ACTION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A freshly started node has no peers; give it this long to find some.
+const PEERS_GRACE_SECS: u64 = 5 * 60;
+
+const GIB: u64 = 1 << 30;
+
+/// A configured condition (stored in `config.json` under `alerts.rules`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Rule {
+    /// bitcoind exited without being asked to.
+    BitcoindDown,
+    /// electrs exited without being asked to.
+    ElectrsDown,
+    /// bitcoind has been running but not synced for this long.
+    NotSynced { minutes: u64 },
+    /// The tip has not moved for this long (outside initial block download).
+    NoNewBlock { minutes: u64 },
+    /// A data volume has less than this much free space.
+    DiskBelow { gib: u64 },
+    /// bitcoind has fewer connections than this.
+    PeersBelow { peers: u64 },
+    /// A reorg replaced more blocks than this.
+    ReorgDeeper { depth: u64 },
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BitcoindDown => f.write_str("bitcoind down"),
+            Self::ElectrsDown => f.write_str("electrs down"),
+            Self::NotSynced { minutes } => write!(f, "not synced for {minutes} min"),
+            Self::NoNewBlock { minutes } => write!(f, "no new block for {minutes} min"),
+            Self::DiskBelow { gib } => write!(f, "disk below {gib} GiB"),
+            Self::PeersBelow { peers } => write!(f, "fewer than {peers} peers"),
+            Self::ReorgDeeper { depth } => write!(f, "reorg deeper than {depth}"),
+        }
+    }
+}
+
+impl Rule {
+    /// Each kind with a typical threshold, for the "add rule" picker.
+    pub const KINDS: [Rule; 7] = [
+        Rule::BitcoindDown,
+        Rule::ElectrsDown,
+        Rule::NotSynced { minutes: 60 },
+        Rule::NoNewBlock { minutes: 90 },
+        Rule::DiskBelow { gib: 20 },
+        Rule::PeersBelow { peers: 4 },
+        Rule::ReorgDeeper { depth: 1 },
+    ];
+
+    /// The rule's threshold, if it takes one.
+    pub fn threshold(self) -> Option<u64> {
+        match self {
+            Self::BitcoindDown | Self::ElectrsDown => None,
+            Self::NotSynced { minutes } | Self::NoNewBlock { minutes } => Some(minutes),
+            Self::DiskBelow { gib } => Some(gib),
+            Self::PeersBelow { peers } => Some(peers),
+            Self::ReorgDeeper { depth } => Some(depth),
+        }
+    }
+
+    /// The same kind of rule with a different threshold.
+    #[must_use]
+    pub fn with_threshold(self, value: u64) -> Self {
+        match self {
+            Self::BitcoindDown | Self::ElectrsDown => self,
+            Self::NotSynced { .. } => Self::NotSynced { minutes: value },
+            Self::NoNewBlock { .. } => Self::NoNewBlock { minutes: value },
+            Self::DiskBelow { .. } => Self::DiskBelow { gib: value },
+            Self::PeersBelow { .. } => Self::PeersBelow { peers: value },
+            Self::ReorgDeeper { .. } => Self::ReorgDeeper { depth: value },
+        }
+    }
+
+    /// The kind's name in the picker, without the threshold.
+    pub fn kind_label(self) -> &'static str {
+        match self {
+            Self::BitcoindDown => "bitcoind down",
+            Self::ElectrsDown => "electrs down",
+            Self::NotSynced { .. } => "Not synced for (min)",
+            Self::NoNewBlock { .. } => "No new block for (min)",
+            Self::DiskBelow { .. } => "Disk free below (GiB)",
+            Self::PeersBelow { .. } => "Peers below",
+            Self::ReorgDeeper { .. } => "Reorg deeper than (blocks)",
+        }
+    }
+
+    /// Whether the condition holds: how long it must hold before firing,
+    /// and what to report.
+    fn check(self, s: &Snapshot) -> Option<(u64, String)> {
+        match self {
+            Self::BitcoindDown => s
+                .bitcoind_exited
+                .then(|| (0, "bitcoind exited unexpectedly".to_owned())),
+            Self::ElectrsDown => s
+                .electrs_exited
+                .then(|| (0, "electrs exited unexpectedly".to_owned())),
+            Self::NotSynced { minutes } => (s.bitcoind_running && !s.synced).then(|| {
+                (
+                    minutes * 60,
+                    format!("bitcoind not synced for {minutes} min"),
+                )
+            }),
+            Self::NoNewBlock { minutes } => {
+                let age = s.tip_age.filter(|&age| age >= minutes * 60)?;
+                Some((0, format!("no new block for {} min", age / 60)))
+            }
+            Self::DiskBelow { gib } => s.disks.iter().find_map(|&(label, free)| {
+                let free = free.filter(|&free| free < gib * GIB)?;
+                #[allow(clippy::cast_precision_loss)]
+                let free_gib = free as f64 / GIB as f64;
+                Some((0, format!("{label} volume has {free_gib:.1} GiB free")))
+            }),
+            Self::PeersBelow { peers } => {
+                let n = s.peers.filter(|&n| s.bitcoind_running && n < peers)?;
+                Some((PEERS_GRACE_SECS, format!("{n} peers connected")))
+            }
+            Self::ReorgDeeper { .. } => None,
+        }
+    }
+}
+
+/// What the app knows on a tick.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Default)]
+pub struct Snapshot {
+    /// Unix seconds.
+    pub now: u64,
+    pub bitcoind_running: bool,
+    /// The process stopped on its own and has not been relaunched.
+    pub bitcoind_exited: bool,
+    pub electrs_exited: bool,
+    pub synced: bool,
+    /// Seconds the tip has stood; `None` when unknown or during IBD.
+    pub tip_age: Option<u64>,
+    pub peers: Option<u64>,
+    /// Free bytes per data volume.
+    pub disks: Vec<(&'static str, Option<u64>)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum State {
+    Firing,
+    Resolved,
+}
+
+/// One notification; also the webhook's JSON body.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Alert {
+    pub rule: String,
+    pub state: State,
+    /// Short heading (the `title` field understood by gotify and others).
+    pub title: String,
+    pub message: String,
+    /// Unix seconds.
+    pub time: u64,
+}
+
+impl Alert {
+    fn new(rule: Rule, state: State, message: String, time: u64) -> Self {
+        let title = match state {
+            State::Firing => format!("Bitcoin node alert: {rule}"),
+            State::Resolved => format!("Bitcoin node resolved: {rule}"),
+        };
+        Self {
+            rule: rule.to_string(),
+            state,
+            title,
+            message,
+            time,
+        }
+    }
+
+    /// One line for the terminal and the event log.
+    pub fn summary(&self) -> String {
+        let state = match self.state {
+            State::Firing => "FIRING",
+            State::Resolved => "RESOLVED",
+        };
+        format!("{state} {}: {}", self.rule, self.message)
+    }
+
+    /// A made-up alert for the "Send test" button.
+    pub fn test(time: u64) -> Self {
+        Self {
+            rule: "test".into(),
+            state: State::Firing,
+            title: "Bitcoin node alert: test".into(),
+            message: "Test notification from Bitcoin Node Manager".into(),
+            time,
+        }
+    }
+}
+
+/// Which rules are firing, and since when each condition has held.
+#[derive(Debug, Default)]
+pub struct Engine {
+    since: HashMap<Rule, u64>,
+    firing: HashSet<Rule>,
+}
+
+impl Engine {
+    /// Check every rule against `s`; returns the alerts to send.
+    pub fn evaluate(&mut self, rules: &[Rule], s: &Snapshot) -> Vec<Alert> {
+        // A removed rule neither fires nor resolves.
+        self.since.retain(|r, _| rules.contains(r));
+        self.firing.retain(|r| rules.contains(r));
+
+        let mut alerts = Vec::new();
+        for &rule in rules {
+            if let Some((hold, message)) = rule.check(s) {
+                let since = *self.since.entry(rule).or_insert(s.now);
+                if s.now.saturating_sub(since) >= hold && self.firing.insert(rule) {
+                    alerts.push(Alert::new(rule, State::Firing, message, s.now));
+                }
+            } else {
+                self.since.remove(&rule);
+                if self.firing.remove(&rule) {
+                    alerts.push(Alert::new(
+                        rule,
+                        State::Resolved,
+                        "condition cleared".into(),
+                        s.now,
+                    ));
+                }
+            }
+        }
+        alerts
+    }
+
+    /// The alert for a reorg of `depth` blocks, if a rule asks for one.
+    pub fn reorg(rules: &[Rule], depth: u64, message: String, now: u64) -> Option<Alert> {
+        let rule = *rules
+            .iter()
+            .find(|r| matches!(r, Rule::ReorgDeeper { depth: n } if depth > *n))?;
+        Some(Alert::new(rule, State::Firing, message, now))
+    }
+}
+
+/// POST `alert` as JSON to `webhook_url` and / or run `command` with it;
+/// empty strings skip that action.  Both are attempted even if one fails.
+pub async fn deliver(alert: Alert, webhook_url: String, command: String) -> Result<()> {
+    let mut errors = Vec::new();
+    if !webhook_url.is_empty() {
+        if let Err(e) = post_webhook(&webhook_url, &alert).await {
+            errors.push(format!("webhook: {e:#}"));
+        }
+    }
+    if !command.is_empty() {
+        if let Err(e) = run_command(&command, &alert).await {
+            errors.push(format!("command: {e:#}"));
+        }
+    }
+    if !errors.is_empty() {
+        bail!("{}", errors.join("; "));
+    }
+    Ok(())
+}
+
+async fn post_webhook(url: &str, alert: &Alert) -> Result<()> {
+    reqwest::Client::builder()
+        .timeout(ACTION_TIMEOUT)
+        .build()
+        .context("build HTTP client")?
+        .post(url)
+        .json(alert)
+        .send()
+        .await
+        .context("send")?
+        .error_for_status()?;
+    Ok(())
+}
+
+/// Run the script with the alert in `BNM_ALERT_*` variables and as JSON on
+/// stdin.
+async fn run_command(command: &str, alert: &Alert) -> Result<()> {
+    let state = match alert.state {
+        State::Firing => "firing",
+        State::Resolved => "resolved",
+    };
+    let mut child = Command::new(command)
+        .env("BNM_ALERT_RULE", &alert.rule)
+        .env("BNM_ALERT_STATE", state)
+        .env("BNM_ALERT_TITLE", &alert.title)
+        .env("BNM_ALERT_MESSAGE", &alert.message)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("start {command}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let json = serde_json::to_vec(alert).context("serialise alert")?;
+        // A script that ignores stdin may exit before reading it.
+        let _ = stdin.write_all(&json).await;
+    }
+    let status = timeout(ACTION_TIMEOUT, child.wait())
+        .await
+        .context("timed out")?
+        .context("wait")?;
+    if !status.success() {
+        bail!("exited with {status}");
+    }
+    Ok(())
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fires_once_after_hold_then_resolves() {
+        let rules = [Rule::NotSynced { minutes: 10 }, Rule::BitcoindDown];
+        let mut engine = Engine::default();
+        let mut s = Snapshot {
+            now: 1_000,
+            bitcoind_running: true,
+            ..Snapshot::default()
+        };
+        assert!(engine.evaluate(&rules, &s).is_empty());
+        s.now += 599;
+        assert!(engine.evaluate(&rules, &s).is_empty());
+
+        s.now += 1;
+        let fired = engine.evaluate(&rules, &s);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].state, State::Firing);
+        assert_eq!(fired[0].rule, "not synced for 10 min");
+        s.now += 5;
+        assert!(engine.evaluate(&rules, &s).is_empty());
+
+        s.synced = true;
+        let resolved = engine.evaluate(&rules, &s);
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].state, State::Resolved);
+        assert!(engine.evaluate(&rules, &s).is_empty());
+    }
+
+    #[test]
+    fn reorg_rule_needs_greater_depth() {
+        let rules = [Rule::ReorgDeeper { depth: 2 }];
+        assert!(Engine::reorg(&rules, 2, String::new(), 0).is_none());
+        assert!(Engine::reorg(&rules, 3, String::new(), 0).is_some());
+    }
+
+    #[test]
+    fn rules_round_trip_through_json() {
+        let json = serde_json::to_string(&Rule::DiskBelow { gib: 50 }).unwrap();
+        assert_eq!(json, r#"{"kind":"disk_below","gib":50}"#);
+        let back: Rule = serde_json::from_str(r#"{"kind":"bitcoind_down"}"#).unwrap();
+        assert_eq!(back, Rule::BitcoindDown);
+    }
+}
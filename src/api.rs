@@ -0,0 +1,417 @@
+//! Optional local HTTP API for scripts: status, recent log lines, launch /
+//! shutdown and binary updates as JSON.
+//!
+//! A minimal HTTP/1.1 server (one request per connection) bound to
+//! `127.0.0.1` only.  Every request must carry `Authorization: Bearer
+//! <token>`.  Authenticated requests are handed to `App::update` as
+//! messages, so the API drives exactly the same state and actions as the
+//! buttons; the reply comes back over a oneshot channel.
+
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use iced::futures::{channel::mpsc, SinkExt, Stream};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::oneshot,
+    time::timeout,
+};
+
+/// Time allowed to send the request head.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Time the app has to answer; an update check may read slow mirrors.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Largest request head accepted.
+const MAX_HEAD: usize = 16 * 1024;
+
+/// Largest request body read (and discarded); no route takes a body.
+const MAX_BODY: usize = 64 * 1024;
+
+/// Log lines returned when `?lines=` is not given.
+const DEFAULT_LOG_LINES: usize = 100;
+
+/// A fresh random API token (64 hex digits).
+pub fn new_token() -> Result<String> {
+    let mut bytes = [0u8; 32];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| anyhow!("system random number generator failed"))?;
+    Ok(hex::encode(bytes))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Process {
+    Bitcoind,
+    Electrs,
+}
+
+/// What a request asks for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Route {
+    Status,
+    Logs {
+        process: Process,
+        lines: usize,
+    },
+    Launch(Process),
+    /// Stopping bitcoind also stops electrs, as the UI's button does.
+    Shutdown(Process),
+    UpdateCheck,
+    UpdateApply,
+    /// Prometheus scrape (see `metrics`).
+    Metrics,
+}
+
+/// Map a method and request target to a route.
+pub fn route(method: &str, target: &str) -> Result<Route, Response> {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let process = |name: &str| match name {
+        "bitcoind" => Some(Process::Bitcoind),
+        "electrs" => Some(Process::Electrs),
+        _ => None,
+    };
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let (expected, route) = match segments.as_slice() {
+        ["status"] => ("GET", Route::Status),
+        ["logs", name] if process(name).is_some() => {
+            let lines = query
+                .split('&')
+                .find_map(|kv| kv.strip_prefix("lines="))
+                .map_or(Ok(DEFAULT_LOG_LINES), str::parse)
+                .map_err(|_| Response::error(400, "lines must be a number"))?;
+            (
+                "GET",
+                Route::Logs {
+                    process: process(name).unwrap_or(Process::Bitcoind),
+                    lines,
+                },
+            )
+        }
+        [name, action] if process(name).is_some() => {
+            let p = process(name).unwrap_or(Process::Bitcoind);
+            match *action {
+                "launch" => ("POST", Route::Launch(p)),
+                "shutdown" => ("POST", Route::Shutdown(p)),
+                _ => return Err(Response::error(404, "no such endpoint")),
+            }
+        }
+        ["update", "check"] => ("POST", Route::UpdateCheck),
+        ["update", "apply"] => ("POST", Route::UpdateApply),
+        ["metrics"] => ("GET", Route::Metrics),
+        _ => return Err(Response::error(404, "no such endpoint")),
+    };
+    if method == expected {
+        Ok(route)
+    } else {
+        Err(Response::error(405, &format!("use {expected}")))
+    }
+}
+
+/// An HTTP response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    pub fn json(status: u16, value: &Value) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: value.to_string(),
+        }
+    }
+
+    /// The Prometheus text exposition format.
+    pub fn metrics(body: String) -> Self {
+        Self {
+            status: 200,
+            content_type: "text/plain; version=0.0.4",
+            body,
+        }
+    }
+
+    pub fn error(status: u16, message: &str) -> Self {
+        Self::json(status, &json!({ "error": message }))
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            408 => "Request Timeout",
+            409 => "Conflict",
+            503 => "Service Unavailable",
+            504 => "Gateway Timeout",
+            _ => "",
+        };
+        format!(
+            "HTTP/1.1 {} {reason}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\
+             Connection: close\r\n\r\n{}",
+            self.status,
+            self.content_type,
+            self.body.len(),
+            self.body
+        )
+        .into_bytes()
+    }
+}
+
+/// Sends the reply for one request; clones share the same channel and only
+/// the first reply is delivered.
+#[derive(Clone)]
+pub struct Responder(Arc<Mutex<Option<oneshot::Sender<Response>>>>);
+
+impl Responder {
+    pub fn send(&self, response: Response) {
+        if let Some(tx) = self.0.lock().ok().and_then(|mut tx| tx.take()) {
+            // The client may have given up; nothing to do then.
+            let _ = tx.send(response);
+        }
+    }
+}
+
+impl fmt::Debug for Responder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Responder")
+    }
+}
+
+/// An authenticated request awaiting the app's reply.
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub route: Route,
+    pub responder: Responder,
+}
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    /// Listening on this URL.
+    Listening(String),
+    /// The server could not start.
+    Failed(String),
+    Request(Request),
+}
+
+/// Serve the API on `127.0.0.1:port` for as long as the stream is polled.
+pub fn serve(port: u16, token: String) -> impl Stream<Item = Event> {
+    iced::stream::channel(32, move |mut output: mpsc::Sender<Event>| async move {
+        let listener = match TcpListener::bind(("127.0.0.1", port)).await {
+            Ok(l) => l,
+            Err(e) => {
+                let _ = output
+                    .send(Event::Failed(format!("bind 127.0.0.1:{port}: {e}")))
+                    .await;
+                return;
+            }
+        };
+        let _ = output
+            .send(Event::Listening(format!("http://127.0.0.1:{port}")))
+            .await;
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tokio::spawn(handle(stream, token.clone(), output.clone()));
+                }
+                // Out of file descriptors and the like; back off briefly.
+                Err(_) => tokio::time::sleep(Duration::from_millis(100)).await,
+            }
+        }
+    })
+}
+
+/// Request line and headers.
+struct Head {
+    method: String,
+    target: String,
+    /// Lowercased names.
+    headers: Vec<(String, String)>,
+}
+
+impl Head {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+async fn handle(mut stream: TcpStream, token: String, output: mpsc::Sender<Event>) {
+    let response = match timeout(READ_TIMEOUT, read_head(&mut stream)).await {
+        Err(_) => Response::error(408, "request timed out"),
+        Ok(Err(e)) => Response::error(400, &format!("{e:#}")),
+        Ok(Ok(head)) => dispatch(&head, &token, output).await,
+    };
+    let _ = stream.write_all(&response.to_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+async fn dispatch(head: &Head, token: &str, mut output: mpsc::Sender<Event>) -> Response {
+    let presented = head
+        .header("authorization")
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or("");
+    if !constant_time_eq(presented.trim().as_bytes(), token.as_bytes()) {
+        return Response::error(401, "missing or wrong bearer token");
+    }
+    let route = match route(&head.method, &head.target) {
+        Ok(r) => r,
+        Err(response) => return response,
+    };
+    let (tx, rx) = oneshot::channel();
+    let request = Request {
+        route,
+        responder: Responder(Arc::new(Mutex::new(Some(tx)))),
+    };
+    if output.send(Event::Request(request)).await.is_err() {
+        return Response::error(503, "the app is shutting down");
+    }
+    match timeout(RESPONSE_TIMEOUT, rx).await {
+        Ok(Ok(response)) => response,
+        _ => Response::error(504, "the app did not answer in time"),
+    }
+}
+
+/// Read the request head, then discard any body.
+async fn read_head(stream: &mut TcpStream) -> Result<Head> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    let end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if buf.len() > MAX_HEAD {
+            bail!("request head too large");
+        }
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            bail!("connection closed mid-request");
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+    let head = parse_head(std::str::from_utf8(&buf[..end]).context("request head is not UTF-8")?)?;
+
+    let body_len: usize = head
+        .header("content-length")
+        .map_or(Ok(0), str::parse)
+        .context("bad Content-Length")?;
+    let mut remaining = body_len.min(MAX_BODY).saturating_sub(buf.len() - end - 4);
+    while remaining > 0 {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        remaining = remaining.saturating_sub(n);
+    }
+    Ok(head)
+}
+
+fn parse_head(text: &str) -> Result<Head> {
+    let mut lines = text.split("\r\n");
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split(' ');
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        bail!("malformed request line");
+    };
+    if !version.starts_with("HTTP/1.") {
+        bail!("unsupported protocol {version}");
+    }
+    let headers = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(n, v)| (n.trim().to_ascii_lowercase(), v.trim().to_owned()))
+        .collect();
+    Ok(Head {
+        method: method.to_owned(),
+        target: target.to_owned(),
+        headers,
+    })
+}
+
+/// Compare secrets without an early exit on the first differing byte.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::futures::StreamExt;
+
+    #[test]
+    fn routes_requests() {
+        assert_eq!(route("GET", "/status"), Ok(Route::Status));
+        assert_eq!(route("GET", "/metrics"), Ok(Route::Metrics));
+        assert_eq!(
+            route("GET", "/logs/electrs?lines=20"),
+            Ok(Route::Logs {
+                process: Process::Electrs,
+                lines: 20
+            })
+        );
+        assert_eq!(
+            route("POST", "/bitcoind/launch"),
+            Ok(Route::Launch(Process::Bitcoind))
+        );
+        assert_eq!(route("GET", "/bitcoind/launch").unwrap_err().status, 405);
+        assert_eq!(route("GET", "/nope").unwrap_err().status, 404);
+        assert_eq!(
+            route("GET", "/logs/bitcoind?lines=x").unwrap_err().status,
+            400
+        );
+    }
+
+    #[tokio::test]
+    async fn authenticates_and_relays_to_the_app() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, mut rx) = mpsc::channel(4);
+        let server = tokio::spawn(async move {
+            for _ in 0..2 {
+                let (stream, _) = listener.accept().await.unwrap();
+                handle(stream, "secret".into(), tx.clone()).await;
+            }
+        });
+        // Stand-in for `App`: answer status requests.
+        tokio::spawn(async move {
+            while let Some(Event::Request(req)) = rx.next().await {
+                assert_eq!(req.route, Route::Status);
+                req.responder
+                    .send(Response::json(200, &json!({ "ok": true })));
+            }
+        });
+
+        let request = |auth: &'static str| async move {
+            let mut s = TcpStream::connect(addr).await.unwrap();
+            s.write_all(format!("GET /status HTTP/1.1\r\nHost: x\r\n{auth}\r\n").as_bytes())
+                .await
+                .unwrap();
+            let mut reply = String::new();
+            s.read_to_string(&mut reply).await.unwrap();
+            reply
+        };
+        let denied = request("Authorization: Bearer wrong\r\n").await;
+        assert!(denied.starts_with("HTTP/1.1 401"));
+        let ok = request("Authorization: Bearer secret\r\n").await;
+        assert!(ok.starts_with("HTTP/1.1 200 OK"));
+        assert!(ok.ends_with("{\"ok\":true}"));
+        server.await.unwrap();
+    }
+}
@@ -0,0 +1,349 @@
+//! Executable format and architecture detection.
+//!
+//! Reads just enough of an ELF or Mach-O header (including universal "fat"
+//! Mach-O binaries) to tell which CPU architecture and OS a binary targets,
+//! so a mismatched download is flagged before launch instead of failing with
+//! `Exec format error` / "bad CPU type in executable".
+
+use std::{fs::File, io::Read, path::Path};
+
+use anyhow::{bail, Context, Result};
+
+/// CPU architecture a binary is compiled for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arch {
+    X86_64,
+    Aarch64,
+    X86,
+    Arm,
+    RiscV64,
+    PowerPc64,
+    Unknown(u32),
+}
+
+impl Arch {
+    /// Architecture of the machine this manager is running on.
+    pub fn host() -> Self {
+        match std::env::consts::ARCH {
+            "x86_64" => Self::X86_64,
+            "aarch64" => Self::Aarch64,
+            "x86" => Self::X86,
+            "arm" => Self::Arm,
+            "riscv64" => Self::RiscV64,
+            "powerpc64" => Self::PowerPc64,
+            _ => Self::Unknown(0),
+        }
+    }
+
+    fn from_elf_machine(machine: u16) -> Self {
+        match machine {
+            0x3e => Self::X86_64,
+            0xb7 => Self::Aarch64,
+            0x03 => Self::X86,
+            0x28 => Self::Arm,
+            0xf3 => Self::RiscV64,
+            0x15 => Self::PowerPc64,
+            other => Self::Unknown(u32::from(other)),
+        }
+    }
+
+    fn from_macho_cputype(cputype: u32) -> Self {
+        match cputype {
+            0x0100_0007 => Self::X86_64,
+            0x0100_000c => Self::Aarch64,
+            0x0000_0007 => Self::X86,
+            0x0000_000c => Self::Arm,
+            0x0100_0012 => Self::PowerPc64,
+            other => Self::Unknown(other),
+        }
+    }
+}
+
+impl std::fmt::Display for Arch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::X86_64 => write!(f, "x86_64"),
+            Self::Aarch64 => write!(f, "arm64"),
+            Self::X86 => write!(f, "x86"),
+            Self::Arm => write!(f, "arm"),
+            Self::RiscV64 => write!(f, "riscv64"),
+            Self::PowerPc64 => write!(f, "ppc64"),
+            Self::Unknown(id) => write!(f, "unknown({id:#x})"),
+        }
+    }
+}
+
+/// Operating system a binary targets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOs {
+    MacOs,
+    Linux,
+    FreeBsd,
+    Unknown,
+}
+
+impl BinaryOs {
+    /// OS of the machine this manager is running on.
+    pub fn host() -> Self {
+        match std::env::consts::OS {
+            "macos" => Self::MacOs,
+            "linux" => Self::Linux,
+            "freebsd" => Self::FreeBsd,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl std::fmt::Display for BinaryOs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::MacOs => "macOS",
+            Self::Linux => "Linux",
+            Self::FreeBsd => "FreeBSD",
+            Self::Unknown => "unknown OS",
+        })
+    }
+}
+
+/// Whether a binary can run on this host.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Compatibility {
+    Native,
+    /// `x86_64` binary on Apple Silicon — runs under Rosetta 2 (slower).
+    Rosetta,
+    Incompatible(String),
+}
+
+/// Parsed header information for one executable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinaryInfo {
+    pub os: BinaryOs,
+    /// One entry for thin binaries, several for universal Mach-O.
+    pub arches: Vec<Arch>,
+}
+
+impl BinaryInfo {
+    /// Compare against the host this manager runs on.
+    pub fn compatibility(&self) -> Compatibility {
+        self.compatibility_with(BinaryOs::host(), Arch::host())
+    }
+
+    pub fn compatibility_with(&self, os: BinaryOs, arch: Arch) -> Compatibility {
+        if self.os != os {
+            return Compatibility::Incompatible(format!(
+                "built for {}, this machine runs {os}",
+                self.os
+            ));
+        }
+        if self.arches.contains(&arch) {
+            return Compatibility::Native;
+        }
+        if os == BinaryOs::MacOs && arch == Arch::Aarch64 && self.arches.contains(&Arch::X86_64) {
+            return Compatibility::Rosetta;
+        }
+        Compatibility::Incompatible(format!(
+            "built for {}, this machine is {arch}",
+            self.arch_label()
+        ))
+    }
+
+    /// `arm64`, or `x86_64+arm64` for universal binaries.
+    pub fn arch_label(&self) -> String {
+        self.arches
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+}
+
+impl std::fmt::Display for BinaryInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.arch_label(), self.os)
+    }
+}
+
+// ── Header parsing ────────────────────────────────────────────────────────────
+
+const ELF_MAGIC: [u8; 4] = [0x7f, b'E', b'L', b'F'];
+const MH_MAGIC_64: u32 = 0xfeed_facf;
+const MH_MAGIC: u32 = 0xfeed_face;
+const FAT_MAGIC: u32 = 0xcafe_babe;
+const FAT_MAGIC_64: u32 = 0xcafe_babf;
+
+/// Read and parse the header of the executable at `path`.
+pub fn inspect(path: &Path) -> Result<BinaryInfo> {
+    let mut header = Vec::with_capacity(4096);
+    File::open(path)
+        .with_context(|| format!("open {}", path.display()))?
+        .take(4096)
+        .read_to_end(&mut header)
+        .with_context(|| format!("read {}", path.display()))?;
+    parse_header(&header).with_context(|| format!("inspect {}", path.display()))
+}
+
+/// Parse an executable header from its first bytes.
+pub fn parse_header(bytes: &[u8]) -> Result<BinaryInfo> {
+    if bytes.len() < 8 {
+        bail!("file too short to be an executable");
+    }
+
+    if bytes[..4] == ELF_MAGIC {
+        return parse_elf(bytes);
+    }
+
+    let le = u32_le(bytes, 0).unwrap_or_default();
+    if le == MH_MAGIC_64 || le == MH_MAGIC {
+        let cputype = u32_le(bytes, 4).context("truncated Mach-O header")?;
+        return Ok(BinaryInfo {
+            os: BinaryOs::MacOs,
+            arches: vec![Arch::from_macho_cputype(cputype)],
+        });
+    }
+
+    let be = u32_be(bytes, 0).unwrap_or_default();
+    if be == FAT_MAGIC || be == FAT_MAGIC_64 {
+        return parse_fat(bytes, be == FAT_MAGIC_64);
+    }
+
+    if bytes.starts_with(b"#!") {
+        bail!("script, not a native executable");
+    }
+    bail!("not an ELF or Mach-O executable")
+}
+
+fn parse_elf(bytes: &[u8]) -> Result<BinaryInfo> {
+    // e_ident[EI_DATA]: 1 = little-endian, 2 = big-endian
+    let big_endian = bytes.get(5) == Some(&2);
+    let os = match bytes.get(7) {
+        Some(0 | 3) => BinaryOs::Linux,
+        Some(9) => BinaryOs::FreeBsd,
+        _ => BinaryOs::Unknown,
+    };
+    let raw = bytes.get(18..20).context("truncated ELF header")?;
+    let machine = if big_endian {
+        u16::from_be_bytes([raw[0], raw[1]])
+    } else {
+        u16::from_le_bytes([raw[0], raw[1]])
+    };
+    Ok(BinaryInfo {
+        os,
+        arches: vec![Arch::from_elf_machine(machine)],
+    })
+}
+
+fn parse_fat(bytes: &[u8], is_64: bool) -> Result<BinaryInfo> {
+    let count = u32_be(bytes, 4).context("truncated fat header")?;
+    // Java class files share the 0xcafebabe magic; their "count" is a
+    // version number well above any realistic slice count.
+    if count == 0 || count > 16 {
+        bail!("not a universal Mach-O binary");
+    }
+    let entry_size = if is_64 { 32 } else { 20 };
+    let arches = (0..count as usize)
+        .map(|i| {
+            u32_be(bytes, 8 + i * entry_size)
+                .map(Arch::from_macho_cputype)
+                .context("truncated fat header")
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(BinaryInfo {
+        os: BinaryOs::MacOs,
+        arches,
+    })
+}
+
+fn u32_le(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn u32_be(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elf(machine: u16) -> Vec<u8> {
+        let mut h = vec![0u8; 64];
+        h[..4].copy_from_slice(&ELF_MAGIC);
+        h[4] = 2; // 64-bit
+        h[5] = 1; // little-endian
+        h[18..20].copy_from_slice(&machine.to_le_bytes());
+        h
+    }
+
+    fn macho(cputype: u32) -> Vec<u8> {
+        let mut h = vec![0u8; 32];
+        h[..4].copy_from_slice(&MH_MAGIC_64.to_le_bytes());
+        h[4..8].copy_from_slice(&cputype.to_le_bytes());
+        h
+    }
+
+    #[test]
+    fn parses_elf_and_macho() {
+        let linux_x86 = parse_header(&elf(0x3e)).unwrap();
+        assert_eq!(linux_x86.os, BinaryOs::Linux);
+        assert_eq!(linux_x86.arches, vec![Arch::X86_64]);
+
+        let linux_arm = parse_header(&elf(0xb7)).unwrap();
+        assert_eq!(linux_arm.arches, vec![Arch::Aarch64]);
+
+        let mac_arm = parse_header(&macho(0x0100_000c)).unwrap();
+        assert_eq!(mac_arm.os, BinaryOs::MacOs);
+        assert_eq!(mac_arm.to_string(), "arm64 macOS");
+    }
+
+    #[test]
+    fn parses_universal_binary() {
+        let mut h = vec![0u8; 64];
+        h[..4].copy_from_slice(&FAT_MAGIC.to_be_bytes());
+        h[4..8].copy_from_slice(&2u32.to_be_bytes());
+        h[8..12].copy_from_slice(&0x0100_0007u32.to_be_bytes());
+        h[28..32].copy_from_slice(&0x0100_000cu32.to_be_bytes());
+        let info = parse_header(&h).unwrap();
+        assert_eq!(info.arches, vec![Arch::X86_64, Arch::Aarch64]);
+        assert_eq!(info.arch_label(), "x86_64+arm64");
+    }
+
+    #[test]
+    fn rejects_non_executables() {
+        assert!(parse_header(b"#!/bin/sh\necho hi\n").is_err());
+        assert!(parse_header(b"hello world, not a binary").is_err());
+        // Java class file: 0xcafebabe followed by a large version number.
+        let mut class = vec![0xca, 0xfe, 0xba, 0xbe, 0x00, 0x00, 0x00, 0x41];
+        class.extend_from_slice(&[0; 16]);
+        assert!(parse_header(&class).is_err());
+    }
+
+    #[test]
+    fn compatibility_rules() {
+        let mac_x86 = parse_header(&macho(0x0100_0007)).unwrap();
+        let mac_arm = parse_header(&macho(0x0100_000c)).unwrap();
+        let linux_x86 = parse_header(&elf(0x3e)).unwrap();
+
+        assert_eq!(
+            mac_arm.compatibility_with(BinaryOs::MacOs, Arch::Aarch64),
+            Compatibility::Native
+        );
+        assert_eq!(
+            mac_x86.compatibility_with(BinaryOs::MacOs, Arch::Aarch64),
+            Compatibility::Rosetta
+        );
+        assert!(matches!(
+            mac_arm.compatibility_with(BinaryOs::MacOs, Arch::X86_64),
+            Compatibility::Incompatible(_)
+        ));
+        assert!(matches!(
+            linux_x86.compatibility_with(BinaryOs::MacOs, Arch::X86_64),
+            Compatibility::Incompatible(_)
+        ));
+        assert_eq!(
+            linux_x86.compatibility_with(BinaryOs::Linux, Arch::X86_64),
+            Compatibility::Native
+        );
+    }
+}
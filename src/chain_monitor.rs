@@ -0,0 +1,173 @@
+//! Best-block tracking across `getblockchaininfo` polls.
+//!
+//! [`TipMonitor`] remembers the last tip seen and when it arrived, which is
+//! enough to notice a tip that stops moving.  Whether a replaced tip was
+//! reorganised away needs the node's view of the old block, so that check
+//! lives in the async [`detect_reorg`].
+
+use anyhow::Result;
+
+use crate::rpc::{self, RpcAuth};
+
+/// Headers walked back from a replaced tip looking for the fork point.
+/// Deeper reorgs are reported with this as a lower bound.
+const MAX_REORG_WALK: u64 = 100;
+
+/// A best block, as reported by `getblockchaininfo`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tip {
+    pub height: u64,
+    pub hash: String,
+}
+
+/// What changed when a tip was observed.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Observation {
+    /// The previous tip, when the best block changed.
+    pub replaced: Option<Tip>,
+    /// Seconds the previous tip stood, when a stall had been reported for it.
+    pub resumed_after: Option<u64>,
+}
+
+/// Last best block and how long it has stood.
+#[derive(Debug, Default)]
+pub struct TipMonitor {
+    tip: Option<Tip>,
+    /// Unix seconds when `tip` was first seen.
+    since: u64,
+    /// A stall has been reported for `tip`.
+    stalled: bool,
+}
+
+impl TipMonitor {
+    /// Record the current best block at unix time `now`.
+    pub fn observe(&mut self, tip: Tip, now: u64) -> Observation {
+        if self.tip.as_ref() == Some(&tip) {
+            return Observation::default();
+        }
+        let obs = Observation {
+            resumed_after: self.stalled.then(|| now.saturating_sub(self.since)),
+            replaced: self.tip.replace(tip),
+        };
+        self.since = now;
+        self.stalled = false;
+        obs
+    }
+
+    /// Seconds the tip has stood, once it reaches `stall_secs`.  Fires once
+    /// per tip; `stall_secs == 0` disables the check.
+    pub fn check_stall(&mut self, now: u64, stall_secs: u64) -> Option<u64> {
+        if self.stalled || stall_secs == 0 || self.tip.is_none() {
+            return None;
+        }
+        let age = now.saturating_sub(self.since);
+        if age < stall_secs {
+            return None;
+        }
+        self.stalled = true;
+        Some(age)
+    }
+
+    /// Seconds the current tip has stood.
+    pub fn age(&self, now: u64) -> Option<u64> {
+        self.tip.as_ref().map(|_| now.saturating_sub(self.since))
+    }
+
+    pub fn tip(&self) -> Option<&Tip> {
+        self.tip.as_ref()
+    }
+
+    /// Forget the tip (the node stopped; a restart starts a new baseline).
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// A replaced tip that is no longer on the active chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reorg {
+    /// Blocks disconnected from the old chain.
+    pub depth: u64,
+    /// Height of the last block both chains share.
+    pub fork_height: u64,
+    pub old: Tip,
+    pub new: Tip,
+}
+
+impl Reorg {
+    pub fn describe(&self) -> String {
+        let plural = if self.depth == 1 { "" } else { "s" };
+        format!(
+            "Reorg of {} block{plural} after height {}: tip {} ({}) replaced by {} ({})",
+            self.depth,
+            self.fork_height,
+            self.old.hash,
+            self.old.height,
+            self.new.hash,
+            self.new.height
+        )
+    }
+}
+
+/// Check whether `old` was reorganised away in favour of `new`, walking back
+/// from `old` to find where the chains fork.
+pub async fn detect_reorg(auth: &RpcAuth, old: Tip, new: Tip) -> Result<Option<Reorg>> {
+    let mut header = rpc::get_block_header(auth, &old.hash).await?;
+    if header.confirmations >= 0 {
+        // Still on the active chain: the new tip simply extends it.
+        return Ok(None);
+    }
+    let mut depth = 0;
+    while header.confirmations < 0 && depth < MAX_REORG_WALK {
+        depth += 1;
+        let Some(prev) = header.previousblockhash.clone() else {
+            break;
+        };
+        header = rpc::get_block_header(auth, &prev).await?;
+    }
+    Ok(Some(Reorg {
+        depth,
+        fork_height: old.height.saturating_sub(depth),
+        old,
+        new,
+    }))
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tip(height: u64, hash: &str) -> Tip {
+        Tip {
+            height,
+            hash: hash.to_owned(),
+        }
+    }
+
+    #[test]
+    fn reports_replacement_stall_once_and_resume() {
+        let mut m = TipMonitor::default();
+        assert_eq!(m.observe(tip(100, "a"), 1_000), Observation::default());
+        assert_eq!(m.observe(tip(100, "a"), 1_005), Observation::default());
+
+        assert_eq!(m.check_stall(1_500, 600), None);
+        assert_eq!(m.check_stall(1_600, 600), Some(600));
+        assert_eq!(m.check_stall(1_700, 600), None);
+
+        let obs = m.observe(tip(101, "b"), 2_000);
+        assert_eq!(obs.replaced, Some(tip(100, "a")));
+        assert_eq!(obs.resumed_after, Some(1_000));
+
+        // No stall was reported for "b", so replacing it does not resume.
+        let obs = m.observe(tip(101, "c"), 2_010);
+        assert_eq!(obs.replaced, Some(tip(101, "b")));
+        assert_eq!(obs.resumed_after, None);
+        assert_eq!(m.check_stall(9_999, 0), None);
+
+        m.reset();
+        assert!(m.tip().is_none());
+        assert_eq!(m.check_stall(9_999, 1), None);
+    }
+}
@@ -3,12 +3,17 @@
 //! Stored as JSON in `~/Library/Application Support/BitcoinNodeManager/config.json`
 //! (macOS) or `~/.config/BitcoinNodeManager/config.json` (other Unix).
 
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{Context, Result};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
 
+use crate::{alerts::Rule, hooks, tuning::Preset};
+
 const APP_NAME: &str = "BitcoinNodeManager";
 const CONFIG_FILENAME: &str = "config.json";
 
@@ -22,6 +27,155 @@ pub struct Config {
     pub bitcoin_data_path: PathBuf,
     /// Electrs database directory.
     pub electrs_data_path: PathBuf,
+    /// Consider release candidates / betas when updating binaries.
+    #[serde(default)]
+    pub include_prereleases: bool,
+    /// Where the updater looks for new builds, in priority order.
+    /// Missing ⇒ filled with the defaults on load; an empty list stays empty.
+    #[serde(default)]
+    pub update_sources: Vec<UpdateSourceConfig>,
+    /// BitForge.app, offered when none of the update sources are available.
+    #[serde(default = "default_bitforge_app_path")]
+    pub bitforge_app_path: PathBuf,
+    /// `maxfeerate` for broadcasts, in sat/vB; higher rates are refused.
+    #[serde(default = "default_max_fee_rate")]
+    pub max_fee_rate: f64,
+    /// Minutes without a new block before a stall is logged; `0` disables.
+    #[serde(default = "default_stall_minutes")]
+    pub stall_minutes: u64,
+    /// ZMQ block / mempool notifications (see `zmq`).
+    #[serde(default)]
+    pub zmq: ZmqConfig,
+    /// Local HTTP control API (see `api`).
+    #[serde(default)]
+    pub api: ApiConfig,
+    /// Alert rules and where notifications go (see `alerts`).
+    #[serde(default)]
+    pub alerts: AlertsConfig,
+    /// Scripts run on manager events (see `hooks`).
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    /// bitcoind resource preset (see `tuning`).
+    #[serde(default)]
+    pub tuning: TuningConfig,
+    /// Block pruning (see `prune`).
+    #[serde(default)]
+    pub prune: PruneConfig,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TuningConfig {
+    pub preset: Preset,
+    /// Launch with a large `dbcache` until the node is synced; switched
+    /// off automatically once it is.
+    pub ibd_boost: bool,
+}
+
+/// Pruning, passed to bitcoind as `-prune` on launch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PruneConfig {
+    pub enabled: bool,
+    /// Block files to keep, in GiB.
+    pub target_gib: u64,
+}
+
+impl Default for PruneConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_gib: 10,
+        }
+    }
+}
+
+/// Event scripts.  A missing or empty entry runs nothing.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    pub scripts: BTreeMap<hooks::Event, String>,
+    /// Give the new-block script to bitcoind as `-blocknotify` instead of
+    /// running it from the manager.
+    pub blocknotify: bool,
+    /// Script bitcoind runs as `-walletnotify` for each wallet transaction.
+    pub walletnotify: String,
+}
+
+/// Alert rules and their actions.  Empty action strings are skipped.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertsConfig {
+    pub rules: Vec<Rule>,
+    /// URL each alert is sent to as a JSON POST (ntfy, gotify, …).
+    pub webhook_url: String,
+    /// Script run for each alert.
+    pub command: String,
+}
+
+/// The localhost-only HTTP API.  The token is generated the first time the
+/// API is enabled.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiConfig {
+    pub enabled: bool,
+    pub port: u16,
+    /// Bearer token every request must present.
+    pub token: String,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 8765,
+            token: String::new(),
+        }
+    }
+}
+
+/// `zmqpub*` endpoints written to bitcoin.conf and subscribed to.  An empty
+/// endpoint leaves that notification off; topics may share an endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ZmqConfig {
+    pub enabled: bool,
+    /// `zmqpubhashblock`: hash of each new best block.
+    pub hashblock: String,
+    /// `zmqpubrawtx`: every transaction entering the mempool or a block.
+    pub rawtx: String,
+    /// `zmqpubsequence`: block connect/disconnect and mempool add/remove.
+    pub sequence: String,
+}
+
+impl Default for ZmqConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hashblock: "tcp://127.0.0.1:28332".into(),
+            rawtx: "tcp://127.0.0.1:28333".into(),
+            sequence: "tcp://127.0.0.1:28334".into(),
+        }
+    }
+}
+
+/// A configured update source (see `update_source`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum UpdateSourceConfig {
+    /// Local directory containing `bitcoin-X.Y/`, `electrs-X.Y.Z/` folders.
+    LocalDir { path: PathBuf },
+    /// HTTP directory listing with the same layout.
+    HttpMirror { url: String },
+}
+
+impl std::fmt::Display for UpdateSourceConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LocalDir { path } => write!(f, "{}", path.display()),
+            Self::HttpMirror { url } => write!(f, "{url}"),
+        }
+    }
 }
 
 impl Config {
@@ -31,7 +185,12 @@ impl Config {
         let path = Self::config_file_path();
 
         match Self::load_from_file(&path) {
-            Ok(cfg) => cfg,
+            Ok((mut cfg, has_sources)) => {
+                if !has_sources {
+                    cfg.update_sources = defaults.update_sources;
+                }
+                cfg
+            }
             Err(e) => {
                 eprintln!("Config load error ({e}), using defaults.");
                 defaults
@@ -54,10 +213,7 @@ impl Config {
 
     /// Path to the JSON config file on this platform.
     pub fn config_file_path() -> PathBuf {
-        ProjectDirs::from("", "", APP_NAME).map_or_else(
-            || dirs_fallback().join(CONFIG_FILENAME),
-            |proj| proj.config_dir().join(CONFIG_FILENAME),
-        )
+        app_file_path(CONFIG_FILENAME)
     }
 
     // ── Internal helpers ─────────────────────────────────────────────────────
@@ -67,14 +223,74 @@ impl Config {
             binaries_path: ssd_root.join("Binaries"),
             bitcoin_data_path: ssd_root.join("BitcoinChain"),
             electrs_data_path: ssd_root.join("ElectrsDB"),
+            include_prereleases: false,
+            update_sources: default_update_sources(ssd_root),
+            bitforge_app_path: default_bitforge_app_path(),
+            max_fee_rate: default_max_fee_rate(),
+            stall_minutes: default_stall_minutes(),
+            zmq: ZmqConfig::default(),
+            api: ApiConfig::default(),
+            alerts: AlertsConfig::default(),
+            hooks: HooksConfig::default(),
+            tuning: TuningConfig::default(),
+            prune: PruneConfig::default(),
         }
     }
 
-    fn load_from_file(path: &PathBuf) -> Result<Self> {
+    /// The config, and whether it names its update sources at all.
+    fn load_from_file(path: &PathBuf) -> Result<(Self, bool)> {
         let text = std::fs::read_to_string(path)
             .with_context(|| format!("read config {}", path.display()))?;
-        serde_json::from_str(&text).context("parse config JSON")
+        let value: serde_json::Value = serde_json::from_str(&text).context("parse config JSON")?;
+        let has_sources = value.get("update_sources").is_some();
+        let cfg = serde_json::from_value(value).context("parse config JSON")?;
+        Ok((cfg, has_sources))
+    }
+}
+
+/// `BitForge`'s build output in `~/Downloads`, then an `Updates/` folder on the SSD.
+fn default_update_sources(ssd_root: &Path) -> Vec<UpdateSourceConfig> {
+    let mut sources = Vec::new();
+    if let Some(home) = home_dir() {
+        sources.push(UpdateSourceConfig::LocalDir {
+            path: home
+                .join("Downloads")
+                .join("bitcoin_builds")
+                .join("binaries"),
+        });
     }
+    sources.push(UpdateSourceConfig::LocalDir {
+        path: ssd_root.join("Updates"),
+    });
+    sources
+}
+
+fn default_bitforge_app_path() -> PathBuf {
+    PathBuf::from("/Applications/BitForge.app")
+}
+
+/// Path of `name` in the app's config directory (next to `config.json`).
+pub fn app_file_path(name: &str) -> PathBuf {
+    ProjectDirs::from("", "", APP_NAME).map_or_else(
+        || dirs_fallback().join(name),
+        |proj| proj.config_dir().join(name),
+    )
+}
+
+/// Core's own default `maxfeerate` (0.10 BTC/kvB).
+const fn default_max_fee_rate() -> f64 {
+    10_000.0
+}
+
+/// An hour without a block happens a few times a day on mainnet; much
+/// longer is worth a look.
+const fn default_stall_minutes() -> u64 {
+    90
+}
+
+/// The user's home directory, if it can be determined.
+pub fn home_dir() -> Option<PathBuf> {
+    directories::BaseDirs::new().map(|b| b.home_dir().to_path_buf())
 }
 
 fn dirs_fallback() -> PathBuf {
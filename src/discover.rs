@@ -0,0 +1,426 @@
+//! Finding node data that already exists on this machine, so a first run
+//! can adopt it instead of starting a new chain on the SSD.
+//!
+//! A bitcoin data directory is recognised by `blocks/`, `chainstate/` or
+//! `bitcoin.conf`; its network comes from `chain=` (or `testnet=1` and
+//! friends) in `bitcoin.conf`.  An electrs DB directory holds one `RocksDB`
+//! per network, named as electrs names them.
+//!
+//! The tip is the last `UpdateTip` in `debug.log`.  Without a log it is the
+//! highest BIP34 coinbase height in the newest `blk*.dat` file, which is the
+//! highest block downloaded rather than validated.  Electrs keeps its tip
+//! inside `RocksDB`, so an electrs DB shows no height.
+
+use std::{
+    collections::HashSet,
+    fmt,
+    fs::{self, File},
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use crate::{config, prune};
+
+/// How much of the end of `debug.log` is searched for the tip.
+const LOG_TAIL: u64 = 2 << 20;
+
+/// Network message start, at the front of every block in `blk*.dat`.
+const MAGICS: [[u8; 4]; 5] = [
+    [0xf9, 0xbe, 0xb4, 0xd9], // main
+    [0x0b, 0x11, 0x09, 0x07], // testnet3
+    [0x1c, 0x16, 0x3f, 0x28], // testnet4
+    [0x0a, 0x03, 0xcf, 0x40], // signet
+    [0xfa, 0xbf, 0xb5, 0xda], // regtest
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Bitcoin,
+    Electrs,
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Bitcoin => "Bitcoin data",
+            Self::Electrs => "Electrs DB",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Network {
+    #[default]
+    Main,
+    Testnet3,
+    Testnet4,
+    Signet,
+    Regtest,
+}
+
+impl Network {
+    const ALL: [Network; 5] = [
+        Network::Main,
+        Network::Testnet3,
+        Network::Testnet4,
+        Network::Signet,
+        Network::Regtest,
+    ];
+
+    /// Where bitcoind keeps this network's data inside the data directory.
+    fn bitcoin_subdir(self) -> &'static str {
+        match self {
+            Self::Main => "",
+            Self::Testnet3 => "testnet3",
+            Self::Testnet4 => "testnet4",
+            Self::Signet => "signet",
+            Self::Regtest => "regtest",
+        }
+    }
+
+    /// Where electrs keeps this network's index inside its DB directory.
+    fn electrs_subdir(self) -> &'static str {
+        match self {
+            Self::Main => "bitcoin",
+            Self::Testnet3 => "testnet",
+            Self::Testnet4 => "testnet4",
+            Self::Signet => "signet",
+            Self::Regtest => "regtest",
+        }
+    }
+
+    /// The network `bitcoin.conf` selects: the last `chain=` (or
+    /// `testnet=1`, `signet=1`, `regtest=1`) before the first section.
+    pub fn from_conf(conf: &str) -> Self {
+        let mut network = Self::Main;
+        for line in conf.lines() {
+            let line = line.trim();
+            if line.starts_with('[') {
+                break;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let on = value.trim() == "1";
+            network = match key.trim() {
+                "chain" => match value.trim() {
+                    "main" => Self::Main,
+                    "test" => Self::Testnet3,
+                    "testnet4" => Self::Testnet4,
+                    "signet" => Self::Signet,
+                    "regtest" => Self::Regtest,
+                    _ => network,
+                },
+                "testnet" if on => Self::Testnet3,
+                "testnet4" if on => Self::Testnet4,
+                "signet" if on => Self::Signet,
+                "regtest" if on => Self::Regtest,
+                _ => network,
+            };
+        }
+        network
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Main => "mainnet",
+            Self::Testnet3 => "testnet3",
+            Self::Testnet4 => "testnet4",
+            Self::Signet => "signet",
+            Self::Regtest => "regtest",
+        })
+    }
+}
+
+/// An existing data directory or electrs DB.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found {
+    pub kind: Kind,
+    /// The directory to put in the config.
+    pub path: PathBuf,
+    pub network: Network,
+    pub size_bytes: u64,
+    /// Last known tip height; `None` for electrs or a node never started.
+    pub tip: Option<u64>,
+}
+
+/// Directories worth probing: the usual bitcoind and electrs locations in
+/// the home directory, the SSD root and every directory directly on it.
+pub fn candidates(ssd_root: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(home) = config::home_dir() {
+        dirs.push(home.join(".bitcoin"));
+        dirs.push(
+            home.join("Library")
+                .join("Application Support")
+                .join("Bitcoin"),
+        );
+        dirs.push(home.join(".electrs"));
+        dirs.push(home.join(".electrs").join("db"));
+        dirs.push(home.join(".local").join("share").join("electrs"));
+    }
+    dirs.push(ssd_root.to_owned());
+    if let Ok(entries) = fs::read_dir(ssd_root) {
+        let mut children: Vec<_> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_dir())
+            .collect();
+        children.sort();
+        dirs.extend(children);
+    }
+    dirs
+}
+
+/// Probe every candidate.  Runs on a blocking thread: sizing a full node's
+/// data directory walks thousands of files.
+pub fn scan(ssd_root: &Path) -> Vec<Found> {
+    let mut seen = HashSet::new();
+    let mut found = Vec::new();
+    for dir in candidates(ssd_root) {
+        let Ok(real) = dir.canonicalize() else {
+            continue;
+        };
+        if !seen.insert(real) {
+            continue;
+        }
+        found.extend(probe_bitcoin(&dir));
+        found.extend(probe_electrs(&dir));
+    }
+    found
+}
+
+/// `dir` as a bitcoin data directory, if it looks like one.
+pub fn probe_bitcoin(dir: &Path) -> Option<Found> {
+    let conf = fs::read_to_string(dir.join("bitcoin.conf")).ok();
+    let network = conf.as_deref().map(Network::from_conf).unwrap_or_default();
+    let net_dir = dir.join(network.bitcoin_subdir());
+    let has_data = net_dir.join("blocks").is_dir() || net_dir.join("chainstate").is_dir();
+    if !has_data && conf.is_none() {
+        return None;
+    }
+    let tip = tip_from_log(&net_dir.join("debug.log"))
+        .or_else(|| tip_from_blocks(&net_dir.join("blocks")));
+    Some(Found {
+        kind: Kind::Bitcoin,
+        path: dir.to_owned(),
+        network,
+        size_bytes: prune::dir_size(dir),
+        tip,
+    })
+}
+
+/// `dir` as an electrs DB directory, if it holds an index.  Mainnet wins
+/// when there are several.
+pub fn probe_electrs(dir: &Path) -> Option<Found> {
+    let network = Network::ALL
+        .into_iter()
+        .find(|n| dir.join(n.electrs_subdir()).join("CURRENT").is_file())?;
+    Some(Found {
+        kind: Kind::Electrs,
+        path: dir.to_owned(),
+        network,
+        size_bytes: prune::dir_size(dir),
+        tip: None,
+    })
+}
+
+/// Height in the last `UpdateTip` line of `debug.log`.
+fn tip_from_log(path: &Path) -> Option<u64> {
+    let mut file = File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(LOG_TAIL)))
+        .ok()?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf).ok()?;
+    last_update_tip(&String::from_utf8_lossy(&buf))
+}
+
+fn last_update_tip(log: &str) -> Option<u64> {
+    log.lines()
+        .rev()
+        .filter(|l| l.contains("UpdateTip:"))
+        .find_map(|l| {
+            let rest = &l[l.find(" height=")? + " height=".len()..];
+            rest.split_whitespace().next()?.parse().ok()
+        })
+}
+
+/// Highest coinbase height in the newest block file.
+fn tip_from_blocks(blocks: &Path) -> Option<u64> {
+    let newest = fs::read_dir(blocks)
+        .ok()?
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .filter(|n| n.starts_with("blk") && Path::new(n).extension().is_some_and(|e| e == "dat"))
+        .max()?;
+    let mut data = fs::read(blocks.join(newest)).ok()?;
+    // bitcoind 28+ obfuscates block files with the key in xor.dat.
+    if let Ok(key) = fs::read(blocks.join("xor.dat")) {
+        if !key.is_empty() {
+            for (i, b) in data.iter_mut().enumerate() {
+                *b ^= key[i % key.len()];
+            }
+        }
+    }
+    max_coinbase_height(&data)
+}
+
+/// Walk `magic | size | block` records and return the highest BIP34 height
+/// among version ≥ 2 blocks.  Stops at the zeroed space bitcoind
+/// preallocates at the end of the file.
+fn max_coinbase_height(data: &[u8]) -> Option<u64> {
+    let mut pos = 0;
+    let mut best = None;
+    while pos + 8 <= data.len() {
+        if !MAGICS.iter().any(|m| data[pos..pos + 4] == *m) {
+            break;
+        }
+        let size = u32::from_le_bytes(data[pos + 4..pos + 8].try_into().ok()?) as usize;
+        let block = data.get(pos + 8..pos + 8 + size)?;
+        if let Some(height) = coinbase_height(block) {
+            best = best.max(Some(height));
+        }
+        pos += 8 + size;
+    }
+    best
+}
+
+fn coinbase_height(block: &[u8]) -> Option<u64> {
+    let version = i32::from_le_bytes(block.get(..4)?.try_into().ok()?);
+    if version < 2 {
+        // Before BIP34 the coinbase need not start with the height.
+        return None;
+    }
+    let mut pos = 80;
+    read_varint(block, &mut pos)?; // transaction count
+    pos += 4; // transaction version
+    if block.get(pos..pos + 2)? == [0x00, 0x01] {
+        pos += 2; // segwit marker and flag
+    }
+    read_varint(block, &mut pos)?; // input count
+    pos += 36; // null prevout
+    read_varint(block, &mut pos)?; // script length
+    let op = *block.get(pos)?;
+    match op {
+        0x00 => Some(0),
+        0x51..=0x60 => Some(u64::from(op - 0x50)),
+        1..=8 => {
+            let bytes = block.get(pos + 1..pos + 1 + usize::from(op))?;
+            let mut le = [0u8; 8];
+            le[..bytes.len()].copy_from_slice(bytes);
+            Some(u64::from_le_bytes(le))
+        }
+        _ => None,
+    }
+}
+
+fn read_varint(data: &[u8], pos: &mut usize) -> Option<u64> {
+    let first = *data.get(*pos)?;
+    let width = match first {
+        0xfd => 2,
+        0xfe => 4,
+        0xff => 8,
+        n => {
+            *pos += 1;
+            return Some(u64::from(n));
+        }
+    };
+    let bytes = data.get(*pos + 1..*pos + 1 + width)?;
+    let mut le = [0u8; 8];
+    le[..width].copy_from_slice(bytes);
+    *pos += 1 + width;
+    Some(u64::from_le_bytes(le))
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn network_from_conf() {
+        assert_eq!(Network::from_conf("server=1\n"), Network::Main);
+        assert_eq!(Network::from_conf("chain=signet\n"), Network::Signet);
+        assert_eq!(Network::from_conf("testnet=1\n"), Network::Testnet3);
+        assert_eq!(Network::from_conf("testnet=0\n"), Network::Main);
+        assert_eq!(Network::from_conf("chain=testnet4\n"), Network::Testnet4);
+        // Settings in a section do not pick the chain.
+        assert_eq!(Network::from_conf("[main]\nregtest=1\n"), Network::Main);
+    }
+
+    #[test]
+    fn tip_from_debug_log() {
+        let log = "\
+2026-10-01T10:00:00Z UpdateTip: new best=00aa height=905000 version=0x20000000 log2_work=95.1\n\
+2026-10-01T10:09:00Z UpdateTip: new best=00bb height=905001 version=0x20000000 log2_work=95.1\n\
+2026-10-01T10:09:01Z Saw new header hash=00cc height=905002\n";
+        assert_eq!(last_update_tip(log), Some(905_001));
+        assert_eq!(last_update_tip("nothing here\n"), None);
+    }
+
+    /// A block record whose coinbase pushes `height` in `push` bytes.
+    fn record(version: i32, height: u64, push: usize, segwit: bool) -> Vec<u8> {
+        let mut block = version.to_le_bytes().to_vec();
+        block.extend([0u8; 76]); // rest of the header
+        block.push(1); // one transaction
+        block.extend(1i32.to_le_bytes());
+        if segwit {
+            block.extend([0x00, 0x01]);
+        }
+        block.push(1); // one input
+        block.extend([0u8; 32]);
+        block.extend([0xff; 4]);
+        let mut script = vec![u8::try_from(push).unwrap()];
+        script.extend(&height.to_le_bytes()[..push]);
+        block.push(u8::try_from(script.len()).unwrap());
+        block.extend(script);
+        let mut rec = MAGICS[0].to_vec();
+        rec.extend(u32::try_from(block.len()).unwrap().to_le_bytes());
+        rec.extend(block);
+        rec
+    }
+
+    #[test]
+    fn coinbase_heights_from_block_file() {
+        let mut data = record(0x2000_0000, 905_010, 3, true);
+        data.extend(record(0x2000_0000, 905_012, 3, false));
+        data.extend(record(0x2000_0000, 905_011, 3, true));
+        // A version 1 block says nothing about its height.
+        data.extend(record(1, 9_999_999, 3, false));
+        data.extend([0u8; 64]);
+        assert_eq!(max_coinbase_height(&data), Some(905_012));
+        assert_eq!(max_coinbase_height(&[0u8; 16]), None);
+    }
+
+    #[test]
+    fn probes_directories() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let btc = root.join("Bitcoin");
+        fs::create_dir_all(btc.join("signet").join("blocks")).unwrap();
+        fs::write(btc.join("bitcoin.conf"), "chain=signet\n").unwrap();
+        fs::write(
+            btc.join("signet").join("debug.log"),
+            "UpdateTip: new best=00 height=250000 version=0x20000000\n",
+        )
+        .unwrap();
+        let els = root.join("electrs");
+        fs::create_dir_all(els.join("bitcoin")).unwrap();
+        fs::write(els.join("bitcoin").join("CURRENT"), "MANIFEST-000001\n").unwrap();
+
+        let found = probe_bitcoin(&btc).unwrap();
+        assert_eq!(found.network, Network::Signet);
+        assert_eq!(found.tip, Some(250_000));
+        assert!(found.size_bytes > 0);
+        assert!(probe_electrs(&btc).is_none());
+
+        let found = probe_electrs(&els).unwrap();
+        assert_eq!((found.kind, found.network), (Kind::Electrs, Network::Main));
+        assert!(probe_bitcoin(&els).is_none());
+        assert!(probe_bitcoin(&root.join("missing")).is_none());
+    }
+}
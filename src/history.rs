@@ -0,0 +1,159 @@
+//! Persistent history of things the manager did or saw: broadcasts,
+//! chain events (reorgs, stalls) and alerts.
+//!
+//! Stored as JSON Lines in `history.jsonl` next to `config.json`, one
+//! [`Entry`] per line, so appends never rewrite the file and a torn last
+//! line only loses that entry.
+
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+
+const HISTORY_FILENAME: &str = "history.jsonl";
+
+/// What an entry records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    /// A transaction sent with `sendrawtransaction`.
+    Broadcast,
+    /// The best block was reorganised away.
+    Reorg,
+    /// No new block for longer than the configured stall period.
+    Stall,
+    /// A new block arrived after a stall.
+    Resumed,
+    /// An alert rule fired or resolved.
+    Alert,
+}
+
+impl std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Broadcast => "Broadcast",
+            Self::Reorg => "Reorg",
+            Self::Stall => "Stall",
+            Self::Resumed => "Resumed",
+            Self::Alert => "Alert",
+        })
+    }
+}
+
+/// One line of the history file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    /// Unix seconds.
+    pub time: u64,
+    pub kind: Kind,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub txid: Option<String>,
+}
+
+impl Entry {
+    /// A new entry stamped with the current time.
+    pub fn now(kind: Kind, message: impl Into<String>) -> Self {
+        Self {
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            kind,
+            message: message.into(),
+            txid: None,
+        }
+    }
+
+    pub fn with_txid(mut self, txid: impl Into<String>) -> Self {
+        self.txid = Some(txid.into());
+        self
+    }
+}
+
+/// Path of the history file on this platform.
+pub fn file_path() -> PathBuf {
+    config::app_file_path(HISTORY_FILENAME)
+}
+
+/// Append `entry` to the history file.
+pub fn append(entry: &Entry) -> Result<()> {
+    append_to(&file_path(), entry)
+}
+
+/// The newest `limit` entries, oldest first.  A missing file is empty.
+pub fn recent(limit: usize) -> Result<Vec<Entry>> {
+    recent_from(&file_path(), limit)
+}
+
+fn append_to(path: &Path, entry: &Entry) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("create history dir {}", parent.display()))?;
+    }
+    let mut line = serde_json::to_string(entry).context("serialise history entry")?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut f| f.write_all(line.as_bytes()))
+        .with_context(|| format!("append to history {}", path.display()))
+}
+
+fn recent_from(path: &Path, limit: usize) -> Result<Vec<Entry>> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(t) => t,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(e).with_context(|| format!("read history {}", path.display()));
+        }
+    };
+    // Skip lines that do not parse (torn writes, entries from newer versions).
+    let entries: Vec<Entry> = text
+        .lines()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect();
+    let skip = entries.len().saturating_sub(limit);
+    Ok(entries.into_iter().skip(skip).collect())
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_and_reads_back_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(HISTORY_FILENAME);
+
+        assert!(recent_from(&path, 10).unwrap().is_empty());
+        for i in 0..3 {
+            append_to(
+                &path,
+                &Entry::now(Kind::Broadcast, format!("tx {i}")).with_txid(format!("{i:064}")),
+            )
+            .unwrap();
+        }
+        // A torn line is skipped.
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"{\"time\":")
+            .unwrap();
+
+        let entries = recent_from(&path, 2).unwrap();
+        let messages: Vec<&str> = entries.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["tx 1", "tx 2"]);
+        assert_eq!(entries[1].kind, Kind::Broadcast);
+    }
+}
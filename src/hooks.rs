@@ -0,0 +1,216 @@
+//! User scripts run on manager events (node started, stopped, crashed,
+//! synced, new block, update installed).
+//!
+//! Context goes in `BNM_*` environment variables.  Each script gets
+//! [`TIMEOUT`] to finish; its output is copied into the terminal panel of
+//! the process the event concerns.  New-block and wallet scripts can
+//! instead be handed to bitcoind as `-blocknotify` / `-walletnotify`, which
+//! runs them once per block or transaction rather than once per poll.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::{process::Command, time::timeout};
+
+use crate::{
+    config::HooksConfig,
+    process_manager::{push_line, OutputQueue},
+};
+
+/// Time a script may run before it is killed.
+pub const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Output lines copied to the terminal per run.
+const MAX_OUTPUT_LINES: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Event {
+    NodeStarted,
+    NodeStopped,
+    NodeCrashed,
+    NodeSynced,
+    NewBlock,
+    UpdateInstalled,
+}
+
+impl Event {
+    pub const ALL: [Event; 6] = [
+        Event::NodeStarted,
+        Event::NodeStopped,
+        Event::NodeCrashed,
+        Event::NodeSynced,
+        Event::NewBlock,
+        Event::UpdateInstalled,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::NodeStarted => "Node started",
+            Self::NodeStopped => "Node stopped",
+            Self::NodeCrashed => "Node crashed",
+            Self::NodeSynced => "Node synced",
+            Self::NewBlock => "New block",
+            Self::UpdateInstalled => "Update installed",
+        }
+    }
+
+    /// Value of `BNM_EVENT`.
+    fn name(self) -> &'static str {
+        match self {
+            Self::NodeStarted => "node_started",
+            Self::NodeStopped => "node_stopped",
+            Self::NodeCrashed => "node_crashed",
+            Self::NodeSynced => "node_synced",
+            Self::NewBlock => "new_block",
+            Self::UpdateInstalled => "update_installed",
+        }
+    }
+}
+
+/// One script run: the event, its context and any arguments.
+#[derive(Debug, Clone)]
+pub struct Invocation {
+    pub event: Event,
+    env: Vec<(&'static str, String)>,
+    args: Vec<String>,
+}
+
+impl Invocation {
+    pub fn new(event: Event) -> Self {
+        Self {
+            event,
+            env: vec![("BNM_EVENT", event.name().to_owned())],
+            args: Vec::new(),
+        }
+    }
+
+    /// `BNM_PROCESS`: `bitcoind` or `electrs`.
+    #[must_use]
+    pub fn process(self, name: &str) -> Self {
+        self.env("BNM_PROCESS", name)
+    }
+
+    #[must_use]
+    pub fn env(mut self, key: &'static str, value: impl Into<String>) -> Self {
+        self.env.push((key, value.into()));
+        self
+    }
+
+    #[must_use]
+    pub fn arg(mut self, value: impl Into<String>) -> Self {
+        self.args.push(value.into());
+        self
+    }
+}
+
+/// The script configured for `event`, unless bitcoind runs it itself.
+pub fn script_for(cfg: &HooksConfig, event: Event) -> Option<&str> {
+    if event == Event::NewBlock && cfg.blocknotify {
+        return None;
+    }
+    cfg.scripts
+        .get(&event)
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+}
+
+/// Launch arguments wiring scripts into bitcoind's own notifications.
+pub fn bitcoind_args(cfg: &HooksConfig) -> Vec<String> {
+    let mut args = Vec::new();
+    let new_block = cfg.scripts.get(&Event::NewBlock).map_or("", |s| s.trim());
+    if cfg.blocknotify && !new_block.is_empty() {
+        args.push(format!("-blocknotify={} %s", shell_quote(new_block)));
+    }
+    let wallet = cfg.walletnotify.trim();
+    if !wallet.is_empty() {
+        args.push(format!("-walletnotify={} %s %w", shell_quote(wallet)));
+    }
+    args
+}
+
+/// bitcoind runs notify commands through `/bin/sh`; quote the path so
+/// spaces (common on external volumes) survive.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Run `script` for `hook`, copying its output into `queue`.
+pub async fn run(script: String, hook: Invocation, queue: OutputQueue) {
+    let name = hook.event.name();
+    push_line(&queue, format!("Hook {name}: running {script}"));
+    let output = Command::new(&script)
+        .args(&hook.args)
+        .envs(hook.env.iter().map(|(k, v)| (k, v)))
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let output = match timeout(TIMEOUT, output).await {
+        Err(_) => {
+            push_line(
+                &queue,
+                format!("Hook {name}: killed after {} s", TIMEOUT.as_secs()),
+            );
+            return;
+        }
+        Ok(Err(e)) => {
+            push_line(&queue, format!("Hook {name}: cannot run {script}: {e}"));
+            return;
+        }
+        Ok(Ok(output)) => output,
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for line in stdout.lines().chain(stderr.lines()).take(MAX_OUTPUT_LINES) {
+        push_line(&queue, format!("[{name}] {line}"));
+    }
+    if !output.status.success() {
+        push_line(&queue, format!("Hook {name}: {}", output.status));
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notify_args_quote_paths_and_skip_manager_run() {
+        let mut cfg = HooksConfig::default();
+        cfg.scripts
+            .insert(Event::NewBlock, "/Volumes/My SSD/on block.sh".into());
+        assert_eq!(
+            script_for(&cfg, Event::NewBlock),
+            Some("/Volumes/My SSD/on block.sh")
+        );
+        assert!(bitcoind_args(&cfg).is_empty());
+
+        cfg.blocknotify = true;
+        cfg.walletnotify = "/opt/it's.sh".into();
+        assert_eq!(script_for(&cfg, Event::NewBlock), None);
+        assert_eq!(
+            bitcoind_args(&cfg),
+            [
+                "-blocknotify='/Volumes/My SSD/on block.sh' %s",
+                r"-walletnotify='/opt/it'\''s.sh' %s %w",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn captures_output_and_passes_context() {
+        let queue = crate::process_manager::new_queue();
+        let hook = Invocation::new(Event::NodeSynced)
+            .process("bitcoind")
+            .arg("-c")
+            .arg("echo $BNM_EVENT $BNM_PROCESS; exit 3");
+        run("/bin/sh".into(), hook, queue.clone()).await;
+        let lines: Vec<String> = queue.lock().unwrap().iter().cloned().collect();
+        assert!(lines.contains(&"[node_synced] node_synced bitcoind".to_owned()));
+        assert_eq!(
+            lines.last().map(String::as_str),
+            Some("Hook node_synced: exit status: 3")
+        );
+    }
+}
@@ -0,0 +1,253 @@
+//! One-shot bitcoind launches for repairs: `-reindex`,
+//! `-reindex-chainstate`, a wallet rescan, and `-assumevalid` overrides.
+//!
+//! A [`OneShot`] is handed to exactly one launch and never written to the
+//! config, so the next plain **Launch** starts normally again.  bitcoind
+//! dropped its `-rescan` option in v23; the rescan mode launches normally
+//! and calls `rescanblockchain` on every loaded wallet once RPC answers.
+//!
+//! Time estimates are deliberately rough: block data processed per second
+//! scales with cores, and checking every signature costs about three times
+//! as much as trusting the default assumevalid block.
+
+use std::fmt::{self, Write as _};
+
+use anyhow::{bail, Result};
+
+use crate::{prune, tuning::Machine};
+
+const MIB: f64 = (1 << 20) as f64;
+
+/// Block data validated per second per core, with the default assumevalid.
+const VALIDATE_MIB_PER_CORE: f64 = 2.5;
+
+/// Cores beyond this barely help (bitcoind caps script threads at 15).
+const MAX_USEFUL_CORES: u64 = 16;
+
+/// Block data a wallet rescan reads per second; bounded by the disk.
+const RESCAN_MIB_PER_SEC: f64 = 150.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Mode {
+    #[default]
+    Normal,
+    Reindex,
+    ReindexChainstate,
+    Rescan,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 4] = [
+        Mode::Normal,
+        Mode::Reindex,
+        Mode::ReindexChainstate,
+        Mode::Rescan,
+    ];
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Normal => "Normal start",
+            Self::Reindex => "Reindex (-reindex)",
+            Self::ReindexChainstate => "Rebuild chainstate (-reindex-chainstate)",
+            Self::Rescan => "Rescan wallets",
+        })
+    }
+}
+
+/// Options for the next launch only.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OneShot {
+    pub mode: Mode,
+    /// Height the wallet rescan starts at.
+    pub rescan_from: u64,
+    /// `-assumevalid`: empty keeps Core's default, `0` checks every
+    /// signature, a block hash trusts signatures up to that block.
+    pub assumevalid: String,
+}
+
+impl OneShot {
+    /// bitcoind arguments for this launch.
+    pub fn args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        match self.mode {
+            Mode::Reindex => args.push("-reindex".to_owned()),
+            Mode::ReindexChainstate => args.push("-reindex-chainstate".to_owned()),
+            Mode::Normal | Mode::Rescan => {}
+        }
+        let assumevalid = self.assumevalid.trim();
+        if !assumevalid.is_empty() {
+            args.push(format!("-assumevalid={assumevalid}"));
+        }
+        args
+    }
+
+    /// Refuse combinations bitcoind would reject at startup.
+    pub fn check(&self, pruned: bool) -> Result<()> {
+        let assumevalid = self.assumevalid.trim();
+        if !assumevalid.is_empty()
+            && assumevalid != "0"
+            && !(assumevalid.len() == 64 && assumevalid.bytes().all(|b| b.is_ascii_hexdigit()))
+        {
+            bail!("assumevalid must be 0 or a 64-character block hash");
+        }
+        if pruned && self.mode == Mode::ReindexChainstate {
+            bail!("a pruned node cannot rebuild its chainstate from block files; use -reindex");
+        }
+        if self.mode == Mode::Normal && assumevalid.is_empty() {
+            bail!("nothing to change: pick a mode or an assumevalid value");
+        }
+        Ok(())
+    }
+
+    /// Rough bounds on how long the launch keeps the node busy, in
+    /// seconds.  `blocks_bytes` is what is on disk, `tip` the last known
+    /// height.  `None` when the mode adds no work of its own.
+    pub fn estimate(
+        &self,
+        m: &Machine,
+        blocks_bytes: u64,
+        tip: u64,
+        pruned: bool,
+    ) -> Option<(u64, u64)> {
+        #[allow(clippy::cast_precision_loss)]
+        let (bytes, mib_per_sec) = match self.mode {
+            Mode::Normal => return None,
+            Mode::Reindex | Mode::ReindexChainstate => {
+                // A pruned node has to download every block again.
+                let bytes = if pruned {
+                    prune::FULL_BLOCKS_ESTIMATE
+                } else {
+                    blocks_bytes
+                };
+                let cores = m.cpus.clamp(1, MAX_USEFUL_CORES) as f64;
+                let mut rate = VALIDATE_MIB_PER_CORE * cores;
+                if self.assumevalid.trim() == "0" {
+                    rate /= 3.0;
+                }
+                if self.mode == Mode::ReindexChainstate {
+                    // The block index is kept.
+                    rate *= 1.1;
+                }
+                (bytes as f64, rate)
+            }
+            Mode::Rescan => {
+                let share = if tip > 0 {
+                    tip.saturating_sub(self.rescan_from) as f64 / tip as f64
+                } else {
+                    1.0
+                };
+                (blocks_bytes as f64 * share, RESCAN_MIB_PER_SEC)
+            }
+        };
+        let secs = bytes / MIB / mib_per_sec;
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Some(((secs * 0.6) as u64, (secs * 1.5) as u64))
+    }
+
+    /// What the launch does, shown before it is confirmed.
+    pub fn describe(&self, pruned: bool) -> String {
+        let mut s = match self.mode {
+            Mode::Normal => String::new(),
+            Mode::Reindex if pruned => "Reindex throws away the pruned blocks and downloads \
+                                        and validates the whole chain again."
+                .to_owned(),
+            Mode::Reindex => "Reindex rebuilds the block index and chainstate from the block \
+                              files on disk. Use it when bitcoind reports a corrupted block \
+                              database."
+                .to_owned(),
+            Mode::ReindexChainstate => "Rebuild the chainstate (UTXO set) by replaying the \
+                                        block files, keeping the block index. Use it when \
+                                        only the chainstate is corrupted."
+                .to_owned(),
+            Mode::Rescan => format!(
+                "Start normally, then rescan every loaded wallet from height {} with \
+                 rescanblockchain.",
+                self.rescan_from
+            ),
+        };
+        match self.assumevalid.trim() {
+            "" => {}
+            "0" => s.push_str(" Every signature is checked (assumevalid=0), which is much slower."),
+            hash => {
+                let _ = write!(s, " Signatures are trusted up to block {hash}.");
+            }
+        }
+        s.push_str(" Only this launch uses these options.");
+        s.trim_start().to_owned()
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIB: u64 = 1 << 30;
+
+    #[test]
+    fn args_and_checks() {
+        let mut shot = OneShot {
+            mode: Mode::Reindex,
+            ..OneShot::default()
+        };
+        assert_eq!(shot.args(), ["-reindex"]);
+        assert!(shot.check(true).is_ok());
+
+        shot.mode = Mode::ReindexChainstate;
+        shot.assumevalid = "0".into();
+        assert_eq!(shot.args(), ["-reindex-chainstate", "-assumevalid=0"]);
+        assert!(shot.check(false).is_ok());
+        assert!(shot.check(true).is_err());
+
+        shot.mode = Mode::Rescan;
+        shot.assumevalid.clear();
+        assert!(shot.args().is_empty());
+
+        shot.mode = Mode::Normal;
+        assert!(shot.check(false).is_err());
+        shot.assumevalid =
+            "00000000000000000001a0a448d6cf2546b06801389cc030b2b18c6491266815".into();
+        assert!(shot.check(false).is_ok());
+        shot.assumevalid = "deadbeef".into();
+        assert!(shot.check(false).is_err());
+    }
+
+    #[test]
+    fn estimates_scale_with_work() {
+        let m = Machine {
+            ram_bytes: 16 * GIB,
+            cpus: 8,
+            free_disk_bytes: 0,
+        };
+        let reindex = OneShot {
+            mode: Mode::Reindex,
+            ..OneShot::default()
+        };
+        let (low, high) = reindex.estimate(&m, 700 * GIB, 900_000, false).unwrap();
+        assert!(low < high);
+        assert!((3 * 3600..12 * 3600).contains(&low), "{low}");
+
+        let all_sigs = OneShot {
+            assumevalid: "0".into(),
+            ..reindex.clone()
+        };
+        let (slow, _) = all_sigs.estimate(&m, 700 * GIB, 900_000, false).unwrap();
+        assert!(slow > 2 * low);
+
+        let rescan = OneShot {
+            mode: Mode::Rescan,
+            rescan_from: 450_000,
+            ..OneShot::default()
+        };
+        let (half, _) = rescan.estimate(&m, 700 * GIB, 900_000, false).unwrap();
+        let full = OneShot {
+            rescan_from: 0,
+            ..rescan.clone()
+        };
+        assert!(half < full.estimate(&m, 700 * GIB, 900_000, false).unwrap().0);
+        assert_eq!(OneShot::default().estimate(&m, 700 * GIB, 0, false), None);
+    }
+}
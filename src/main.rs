@@ -5,11 +5,27 @@
 //!   2. Resolves the SSD root (directory containing this binary).
 //!   3. Hands off to the Iced application loop.
 
+mod alerts;
+mod api;
+mod binary_arch;
+mod chain_monitor;
 mod config;
+mod discover;
+mod electrum;
+mod history;
+mod hooks;
+mod launch_mode;
+mod metrics;
+mod migrate;
 mod process_manager;
+mod prune;
+mod resources;
 mod rpc;
+mod tuning;
 mod ui;
+mod update_source;
 mod updater;
+mod zmq;
 
 use std::{
     fs::{self, OpenOptions},
@@ -18,7 +34,7 @@ use std::{
     process,
 };
 
-use iced::{window, Size, Task};
+use iced::{window, Size};
 
 /// Attempt to acquire an exclusive advisory lock on a temp file.
 /// Returns an open file handle on success (caller must keep it alive).
@@ -76,8 +92,9 @@ fn main() -> iced::Result {
         ..Default::default()
     })
     .run_with(move || {
-        let app = ui::App::new(&ssd_root);
-        (app, Task::none())
+        let mut app = ui::App::new(&ssd_root);
+        let startup = app.startup();
+        (app, startup)
     })
 }
 
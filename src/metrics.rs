@@ -0,0 +1,343 @@
+//! Prometheus metrics for the node and the manager (`GET /metrics` on the
+//! HTTP API).
+//!
+//! Gauges are rendered from a [`NodeState`] the app fills from what it
+//! already polls, plus a [`Polled`] sample taken on each RPC tick while the
+//! API is on.  RPC latency is the one thing recorded as it happens: every
+//! JSON-RPC round trip lands in a per-method histogram.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    path::PathBuf,
+    sync::{LazyLock, Mutex},
+    time::Duration,
+};
+
+use crate::{
+    electrum,
+    process_manager::ELECTRUM_RPC_ADDR,
+    rpc::{self, RpcAuth},
+};
+
+/// Upper bounds (seconds) of the RPC latency buckets.
+const LATENCY_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    /// Non-cumulative count per bucket; the last slot is `+Inf`.
+    buckets: [u64; LATENCY_BUCKETS.len() + 1],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, secs: f64) {
+        let slot = LATENCY_BUCKETS
+            .iter()
+            .position(|&le| secs <= le)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        self.buckets[slot] += 1;
+        self.sum += secs;
+        self.count += 1;
+    }
+}
+
+static RPC_LATENCY: LazyLock<Mutex<BTreeMap<String, Histogram>>> =
+    LazyLock::new(|| Mutex::new(BTreeMap::new()));
+
+/// Methods the manager itself calls, kept sorted.  Anything else (console
+/// input) is recorded as `other`, so typos cannot add series without bound.
+const KNOWN_METHODS: &[&str] = &[
+    "analyzepsbt",
+    "backupwallet",
+    "combinepsbt",
+    "createwallet",
+    "decodepsbt",
+    "decoderawtransaction",
+    "deriveaddresses",
+    "disconnectnode",
+    "dumptxoutset",
+    "estimatesmartfee",
+    "finalizepsbt",
+    "getbalances",
+    "getblock",
+    "getblockchaininfo",
+    "getblockhash",
+    "getblockheader",
+    "getchainstates",
+    "getchaintips",
+    "getdescriptorinfo",
+    "getmempoolinfo",
+    "getnettotals",
+    "getnetworkinfo",
+    "getpeerinfo",
+    "getrawmempool",
+    "getrawtransaction",
+    "getwalletinfo",
+    "help",
+    "listwalletdir",
+    "listwallets",
+    "loadtxoutset",
+    "loadwallet",
+    "rescanblockchain",
+    "sendrawtransaction",
+    "setban",
+    "stop",
+    "testmempoolaccept",
+    "unloadwallet",
+    "validateaddress",
+];
+
+/// Record one RPC round trip.
+pub fn observe_rpc(method: &str, elapsed: Duration) {
+    let method = if KNOWN_METHODS.binary_search(&method).is_ok() {
+        method
+    } else {
+        "other"
+    };
+    if let Ok(mut map) = RPC_LATENCY.lock() {
+        map.entry(method.to_owned())
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+    }
+}
+
+/// Figures only the exporter needs, fetched on each RPC tick.
+#[derive(Debug, Clone, Default)]
+pub struct Polled {
+    pub peers: Option<u64>,
+    pub mempool_txs: Option<u64>,
+    pub mempool_bytes: Option<u64>,
+    pub electrs_height: Option<u64>,
+}
+
+/// Sample peers, the mempool and (when running) electrs' tip.  Failures
+/// leave the figure out.
+pub async fn poll(auth: RpcAuth, electrs_running: bool) -> Polled {
+    let peers = rpc::get_network_info(&auth)
+        .await
+        .ok()
+        .map(|n| n.connections);
+    let mempool = rpc::get_mempool_info(&auth).await.ok();
+    let electrs_height = if electrs_running {
+        match electrum::Client::connect(ELECTRUM_RPC_ADDR).await {
+            Ok(mut client) => client.tip_height().await.ok(),
+            Err(_) => None,
+        }
+    } else {
+        None
+    };
+    Polled {
+        peers,
+        mempool_txs: mempool.as_ref().map(|m| m.size),
+        mempool_bytes: mempool.as_ref().map(|m| m.bytes),
+        electrs_height,
+    }
+}
+
+/// What the app knows at scrape time.  `None` is left out of the output
+/// rather than reported as zero.
+#[derive(Debug, Default)]
+pub struct NodeState {
+    pub bitcoind_up: bool,
+    pub electrs_up: bool,
+    pub blocks: Option<u64>,
+    pub headers: Option<u64>,
+    pub verification_progress: Option<f64>,
+    pub polled: Polled,
+    pub bitcoind_restarts: u64,
+    pub electrs_restarts: u64,
+    /// `(label, directory)` pairs whose free space is reported.
+    pub disks: Vec<(&'static str, PathBuf)>,
+}
+
+/// Render `state` and the RPC histograms in the Prometheus text format.
+pub fn render(state: &NodeState) -> String {
+    let mut out = String::new();
+    let mut metric = |kind: &str, name: &str, help: &str, samples: &[(&str, f64)]| {
+        let _ = writeln!(out, "# HELP {name} {help}\n# TYPE {name} {kind}");
+        for (labels, value) in samples {
+            let _ = writeln!(out, "{name}{labels} {value}");
+        }
+    };
+    let up = |b: bool| if b { 1.0 } else { 0.0 };
+    metric(
+        "gauge",
+        "bnm_process_up",
+        "Whether the process is running (1) or not (0).",
+        &[
+            ("{process=\"bitcoind\"}", up(state.bitcoind_up)),
+            ("{process=\"electrs\"}", up(state.electrs_up)),
+        ],
+    );
+    #[allow(clippy::cast_precision_loss)]
+    let restarts = [
+        ("{process=\"bitcoind\"}", state.bitcoind_restarts as f64),
+        ("{process=\"electrs\"}", state.electrs_restarts as f64),
+    ];
+    metric(
+        "counter",
+        "bnm_process_restarts_total",
+        "Launches after the first since the manager started.",
+        &restarts,
+    );
+    #[allow(clippy::cast_precision_loss)]
+    let optional = [
+        (
+            "bitcoind_blocks",
+            "Height of the active chain tip.",
+            state.blocks.map(|v| v as f64),
+        ),
+        (
+            "bitcoind_headers",
+            "Height of the best known header.",
+            state.headers.map(|v| v as f64),
+        ),
+        (
+            "bitcoind_verification_progress",
+            "Estimated fraction of the chain verified (0-1).",
+            state.verification_progress,
+        ),
+        (
+            "bitcoind_peers",
+            "Connected peers.",
+            state.polled.peers.map(|v| v as f64),
+        ),
+        (
+            "bitcoind_mempool_transactions",
+            "Transactions in the mempool.",
+            state.polled.mempool_txs.map(|v| v as f64),
+        ),
+        (
+            "bitcoind_mempool_bytes",
+            "Virtual size of the mempool in bytes.",
+            state.polled.mempool_bytes.map(|v| v as f64),
+        ),
+        (
+            "electrs_indexed_height",
+            "Chain tip height as indexed by electrs.",
+            state.polled.electrs_height.map(|v| v as f64),
+        ),
+    ];
+    for (name, help, value) in optional {
+        if let Some(v) = value {
+            metric("gauge", name, help, &[("", v)]);
+        }
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let disks: Vec<(String, f64)> = state
+        .disks
+        .iter()
+        .filter_map(|(label, dir)| {
+            let free = disk_free_bytes(dir)?;
+            Some((format!("{{volume=\"{label}\"}}"), free as f64))
+        })
+        .collect();
+    if !disks.is_empty() {
+        let samples: Vec<(&str, f64)> = disks.iter().map(|(l, v)| (l.as_str(), *v)).collect();
+        metric(
+            "gauge",
+            "bnm_disk_free_bytes",
+            "Free space available to the manager on the data volume.",
+            &samples,
+        );
+    }
+    render_latency(&mut out);
+    out
+}
+
+fn render_latency(out: &mut String) {
+    let Ok(map) = RPC_LATENCY.lock() else {
+        return;
+    };
+    let name = "bitcoind_rpc_duration_seconds";
+    let _ = writeln!(
+        out,
+        "# HELP {name} JSON-RPC round-trip time by method.\n# TYPE {name} histogram"
+    );
+    for (method, h) in map.iter() {
+        let method = escape_label(method);
+        let mut cumulative = 0;
+        for (i, count) in h.buckets.iter().enumerate() {
+            cumulative += count;
+            let le = LATENCY_BUCKETS
+                .get(i)
+                .map_or_else(|| "+Inf".to_owned(), ToString::to_string);
+            let _ = writeln!(
+                out,
+                "{name}_bucket{{method=\"{method}\",le=\"{le}\"}} {cumulative}"
+            );
+        }
+        let _ = writeln!(out, "{name}_sum{{method=\"{method}\"}} {}", h.sum);
+        let _ = writeln!(out, "{name}_count{{method=\"{method}\"}} {}", h.count);
+    }
+}
+
+/// Escape a label value as the text exposition format requires.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Bytes available to unprivileged users on the volume holding `dir`.
+pub fn disk_free_bytes(dir: &std::path::Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = std::ffi::CString::new(dir.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `path` is a valid NUL-terminated string and `stat` is a
+    // properly sized out-parameter.
+    if unsafe { libc::statvfs(path.as_ptr(), &raw mut stat) } != 0 {
+        return None;
+    }
+    #[allow(clippy::useless_conversion)] // The field widths differ by platform.
+    Some(u64::from(stat.f_bavail) * u64::from(stat.f_frsize))
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_gauges_and_cumulative_histograms() {
+        observe_rpc("getchainstates", Duration::from_millis(3));
+        observe_rpc("getchainstates", Duration::from_millis(300));
+        let text = render(&NodeState {
+            bitcoind_up: true,
+            blocks: Some(850_000),
+            disks: vec![("tmp", std::env::temp_dir())],
+            ..NodeState::default()
+        });
+        assert!(text.contains("bnm_process_up{process=\"bitcoind\"} 1\n"));
+        assert!(text.contains("bnm_process_up{process=\"electrs\"} 0\n"));
+        assert!(text.contains("bitcoind_blocks 850000\n"));
+        assert!(text.contains("# TYPE bnm_process_restarts_total counter\n"));
+        assert!(!text.contains("bitcoind_peers"));
+        assert!(text.contains("bnm_disk_free_bytes{volume=\"tmp\"}"));
+        let bucket = |le: &str| {
+            format!("bitcoind_rpc_duration_seconds_bucket{{method=\"getchainstates\",le=\"{le}\"}}")
+        };
+        assert!(text.contains(&format!("{} 1\n", bucket("0.005"))));
+        assert!(text.contains(&format!("{} 1\n", bucket("0.25"))));
+        assert!(text.contains(&format!("{} 2\n", bucket("0.5"))));
+        assert!(text.contains(&format!("{} 2\n", bucket("+Inf"))));
+    }
+
+    #[test]
+    fn unknown_methods_share_one_escaped_series() {
+        assert!(KNOWN_METHODS.windows(2).all(|w| w[0] < w[1]));
+        observe_rpc("getblokcount", Duration::from_millis(1));
+        observe_rpc("bad\"name\n", Duration::from_millis(1));
+        let text = render(&NodeState::default());
+        assert!(text.contains("bitcoind_rpc_duration_seconds_count{method=\"other\"} 2\n"));
+        assert!(!text.contains("getblokcount"));
+        assert_eq!(escape_label("a\\b\"c\nd"), "a\\\\b\\\"c\\nd");
+    }
+}
@@ -0,0 +1,534 @@
+//! Copying a bitcoin data directory or electrs DB to a new location.
+//!
+//! [`plan`] walks the source and checks the destination has room for what
+//! is not there yet.  [`copy`] then copies file by file through a
+//! `.bnm-partial` file that is renamed into place once complete, and gives
+//! each file the source's modification time.  A run that is cancelled or
+//! interrupted resumes where it stopped: finished files whose size and
+//! mtime match are skipped, and a partial file is appended to when the
+//! source still has the size and mtime recorded next to it when it was
+//! started.  Otherwise (bitcoind rewrote `mempool.dat`, say) the file is
+//! copied again from the start.  [`verify`]
+//! checks every file's size and mtime before the caller switches paths.
+//!
+//! Symlinks in the source (`blocks/` on another drive, say) are followed,
+//! so the new location holds real files.  A destination that already holds
+//! anything but an earlier run's files is refused rather than merged into.
+//!
+//! The source is only read, so it stays usable until the copy is verified.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{bail, Context, Result};
+
+use crate::metrics;
+
+/// Suffix of a file still being copied.
+const PARTIAL_SUFFIX: &str = ".bnm-partial";
+
+/// Suffix of the record of which source a partial file was started from.
+const SOURCE_SUFFIX: &str = ".bnm-partial-src";
+
+/// exFAT and HFS+ store mtimes coarser than APFS or ext4.
+const MTIME_TOLERANCE: Duration = Duration::from_secs(2);
+
+const CHUNK: usize = 4 << 20;
+
+/// Mismatches listed by [`verify`] before it stops counting.
+const MAX_REPORTED: usize = 5;
+
+/// One regular file in the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// Path relative to the source root.
+    pub rel: PathBuf,
+    pub len: u64,
+    pub modified: SystemTime,
+}
+
+/// What a copy from `src` to `dst` involves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub src: PathBuf,
+    pub dst: PathBuf,
+    pub files: Vec<Entry>,
+    pub dirs: Vec<PathBuf>,
+    pub total_bytes: u64,
+    /// Bytes already at the destination from an earlier run.
+    pub present_bytes: u64,
+    /// Free space on the destination volume, when it could be read.
+    pub free_bytes: Option<u64>,
+}
+
+impl Plan {
+    /// Bytes still to be written.
+    pub fn needed_bytes(&self) -> u64 {
+        self.total_bytes - self.present_bytes
+    }
+
+    pub fn fits(&self) -> bool {
+        self.free_bytes
+            .is_none_or(|free| free >= self.needed_bytes())
+    }
+}
+
+/// Counters the copy updates as it goes; `cancel` stops it between chunks.
+#[derive(Debug, Default)]
+pub struct Progress {
+    pub copied_bytes: AtomicU64,
+    pub files_done: AtomicU64,
+    pub cancel: AtomicBool,
+}
+
+/// Walk `src` and size up copying it to `dst`.
+pub fn plan(src: &Path, dst: &Path) -> Result<Plan> {
+    if !src.is_dir() {
+        bail!("{} is not a directory", src.display());
+    }
+    let src_abs = src
+        .canonicalize()
+        .with_context(|| format!("resolve {}", src.display()))?;
+    let dst_abs = resolve_new(dst)?;
+    if dst_abs.starts_with(&src_abs) || src_abs.starts_with(&dst_abs) {
+        bail!("the new location must not be inside the old one, or the other way round");
+    }
+
+    let mut plan = Plan {
+        src: src.to_owned(),
+        dst: dst.to_owned(),
+        files: Vec::new(),
+        dirs: Vec::new(),
+        total_bytes: 0,
+        present_bytes: 0,
+        free_bytes: None,
+    };
+    walk(src, Path::new(""), &mut vec![src_abs], &mut plan)?;
+    check_destination(dst, Path::new(""), &plan)?;
+    plan.total_bytes = plan.files.iter().map(|f| f.len).sum();
+    plan.present_bytes = plan
+        .files
+        .iter()
+        .map(|f| {
+            let target = dst.join(&f.rel);
+            if matches(&target, f) {
+                f.len
+            } else {
+                resumable_len(&target, f).unwrap_or(0)
+            }
+        })
+        .sum();
+    plan.free_bytes = existing_ancestor(&dst_abs).and_then(metrics::disk_free_bytes);
+    Ok(plan)
+}
+
+/// Add the files and directories under `root/rel` to `plan`, following
+/// symlinks.  `open` holds the resolved directories being walked, to catch
+/// a link back up the tree.
+fn walk(root: &Path, rel: &Path, open: &mut Vec<PathBuf>, plan: &mut Plan) -> Result<()> {
+    let dir = root.join(rel);
+    let mut entries: Vec<_> = fs::read_dir(&dir)
+        .with_context(|| format!("read {}", dir.display()))?
+        .collect::<io::Result<_>>()
+        .with_context(|| format!("read {}", dir.display()))?;
+    entries.sort_by_key(fs::DirEntry::file_name);
+    for entry in entries {
+        let rel = rel.join(entry.file_name());
+        let path = entry.path();
+        let meta = fs::metadata(&path).with_context(|| {
+            if path.is_symlink() {
+                format!("{} is a broken symlink", path.display())
+            } else {
+                format!("stat {}", path.display())
+            }
+        })?;
+        if meta.is_dir() {
+            let target = path
+                .canonicalize()
+                .with_context(|| format!("resolve {}", path.display()))?;
+            if open.contains(&target) {
+                bail!(
+                    "{} links back to {}, which is already being copied",
+                    path.display(),
+                    target.display()
+                );
+            }
+            plan.dirs.push(rel.clone());
+            open.push(target);
+            walk(root, &rel, open, plan)?;
+            open.pop();
+        } else if meta.is_file() {
+            plan.files.push(Entry {
+                rel,
+                len: meta.len(),
+                modified: meta.modified().context("read mtime")?,
+            });
+        }
+        // Sockets and FIFOs are not part of a datadir; skip them.
+    }
+    Ok(())
+}
+
+/// Refuse a destination holding anything but `plan`'s directories, files
+/// and partial copies of them from an earlier run.
+fn check_destination(dst: &Path, rel: &Path, plan: &Plan) -> Result<()> {
+    let dir = dst.join(rel);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound && rel.as_os_str().is_empty() => {
+            return Ok(());
+        }
+        Err(e) => return Err(e).with_context(|| format!("read {}", dir.display())),
+    };
+    for entry in entries {
+        let entry = entry.with_context(|| format!("read {}", dir.display()))?;
+        let rel = rel.join(entry.file_name());
+        let kind = entry
+            .file_type()
+            .with_context(|| format!("stat {}", entry.path().display()))?;
+        if kind.is_dir() && plan.dirs.contains(&rel) {
+            check_destination(dst, &rel, plan)?;
+            continue;
+        }
+        // Finder leaves these in any folder it has shown.
+        if entry.file_name() == ".DS_Store" {
+            continue;
+        }
+        let name = rel.to_string_lossy();
+        let copied = name
+            .strip_suffix(SOURCE_SUFFIX)
+            .or_else(|| name.strip_suffix(PARTIAL_SUFFIX))
+            .unwrap_or(&name);
+        if !(kind.is_file() && plan.files.iter().any(|f| f.rel == Path::new(copied))) {
+            bail!(
+                "{} already holds {}, which is not part of this copy; \
+                 choose an empty folder",
+                dst.display(),
+                rel.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Absolute form of `path`, which may not exist yet.
+fn resolve_new(path: &Path) -> Result<PathBuf> {
+    let abs = if path.is_absolute() {
+        path.to_owned()
+    } else {
+        std::env::current_dir()?.join(path)
+    };
+    let Some(base) = existing_ancestor(&abs) else {
+        return Ok(abs);
+    };
+    let rest = abs.strip_prefix(base).unwrap_or(Path::new(""));
+    Ok(base
+        .canonicalize()
+        .with_context(|| format!("resolve {}", base.display()))?
+        .join(rest))
+}
+
+fn existing_ancestor(path: &Path) -> Option<&Path> {
+    path.ancestors().find(|p| p.exists())
+}
+
+fn partial_path(target: &Path) -> PathBuf {
+    with_suffix(target, PARTIAL_SUFFIX)
+}
+
+fn source_path(target: &Path) -> PathBuf {
+    with_suffix(target, SOURCE_SUFFIX)
+}
+
+fn with_suffix(target: &Path, suffix: &str) -> PathBuf {
+    let mut name = target.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Size and mtime of the source, as recorded next to a partial file.
+fn source_stamp(entry: &Entry) -> String {
+    let t = entry
+        .modified
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    format!("{} {}.{:09}", entry.len, t.as_secs(), t.subsec_nanos())
+}
+
+/// Length of the partial copy of `entry` at `target`, if it can be
+/// appended to: started from this same source and not longer than it.
+fn resumable_len(target: &Path, entry: &Entry) -> Option<u64> {
+    let stamp = fs::read_to_string(source_path(target)).ok()?;
+    if stamp != source_stamp(entry) {
+        return None;
+    }
+    let len = fs::metadata(partial_path(target)).ok()?.len();
+    (len <= entry.len).then_some(len)
+}
+
+/// `path` has `entry`'s size and (within tolerance) mtime.
+fn matches(path: &Path, entry: &Entry) -> bool {
+    let Ok(meta) = fs::metadata(path) else {
+        return false;
+    };
+    let Ok(modified) = meta.modified() else {
+        return false;
+    };
+    let skew = modified
+        .duration_since(entry.modified)
+        .or_else(|_| entry.modified.duration_since(modified))
+        .unwrap_or(Duration::MAX);
+    meta.len() == entry.len && skew <= MTIME_TOLERANCE
+}
+
+/// Copy everything in `plan`, resuming an earlier run.  Returns early with
+/// an error when `progress.cancel` is set.
+pub fn copy(plan: &Plan, progress: &Progress) -> Result<()> {
+    fs::create_dir_all(&plan.dst).with_context(|| format!("create {}", plan.dst.display()))?;
+    for dir in &plan.dirs {
+        let d = plan.dst.join(dir);
+        fs::create_dir_all(&d).with_context(|| format!("create {}", d.display()))?;
+    }
+    for entry in &plan.files {
+        let target = plan.dst.join(&entry.rel);
+        if matches(&target, entry) {
+            progress
+                .copied_bytes
+                .fetch_add(entry.len, Ordering::Relaxed);
+        } else {
+            copy_file(&plan.src.join(&entry.rel), &target, entry, progress)?;
+        }
+        progress.files_done.fetch_add(1, Ordering::Relaxed);
+    }
+    Ok(())
+}
+
+fn copy_file(src: &Path, target: &Path, entry: &Entry, progress: &Progress) -> Result<()> {
+    let partial = partial_path(target);
+    let stamp = source_path(target);
+    let done = resumable_len(target, entry);
+    let mut out = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&partial)
+        .with_context(|| format!("open {}", partial.display()))?;
+    let done = if let Some(done) = done {
+        done
+    } else {
+        // Left over from another version of the file: start over.
+        out.set_len(0)
+            .with_context(|| format!("truncate {}", partial.display()))?;
+        fs::write(&stamp, source_stamp(entry))
+            .with_context(|| format!("write {}", stamp.display()))?;
+        0
+    };
+    progress.copied_bytes.fetch_add(done, Ordering::Relaxed);
+
+    let mut input = File::open(src).with_context(|| format!("open {}", src.display()))?;
+    input.seek(SeekFrom::Start(done))?;
+    let mut buf = vec![0; CHUNK];
+    loop {
+        if progress.cancel.load(Ordering::Relaxed) {
+            bail!("cancelled; run it again to resume");
+        }
+        let n = input
+            .read(&mut buf)
+            .with_context(|| format!("read {}", src.display()))?;
+        if n == 0 {
+            break;
+        }
+        out.write_all(&buf[..n])
+            .with_context(|| format!("write {}", partial.display()))?;
+        progress.copied_bytes.fetch_add(n as u64, Ordering::Relaxed);
+    }
+    if !matches(src, entry) {
+        bail!(
+            "{} changed while it was copied; run it again to copy it afresh",
+            src.display()
+        );
+    }
+    out.sync_all()
+        .with_context(|| format!("sync {}", partial.display()))?;
+    out.set_modified(entry.modified)
+        .with_context(|| format!("set mtime of {}", partial.display()))?;
+    drop(out);
+    fs::rename(&partial, target).with_context(|| format!("rename to {}", target.display()))?;
+    fs::remove_file(&stamp).with_context(|| format!("remove {}", stamp.display()))
+}
+
+/// Check every file in `plan` arrived with its size and mtime.
+pub fn verify(plan: &Plan) -> Result<()> {
+    let bad: Vec<&Entry> = plan
+        .files
+        .iter()
+        .filter(|entry| !matches(&plan.dst.join(&entry.rel), entry))
+        .collect();
+    if bad.is_empty() {
+        return Ok(());
+    }
+    let listed: Vec<String> = bad
+        .iter()
+        .take(MAX_REPORTED)
+        .map(|e| e.rel.display().to_string())
+        .collect();
+    bail!(
+        "{} of {} files differ from the source: {}{}",
+        bad.len(),
+        plan.files.len(),
+        listed.join(", "),
+        if bad.len() > MAX_REPORTED {
+            ", …"
+        } else {
+            ""
+        }
+    )
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::FileExt;
+
+    use super::*;
+
+    fn datadir(root: &Path) {
+        fs::create_dir_all(root.join("blocks/index")).unwrap();
+        fs::create_dir_all(root.join("chainstate")).unwrap();
+        fs::write(root.join("bitcoin.conf"), "server=1\n").unwrap();
+        fs::write(root.join("blocks/blk00000.dat"), vec![7u8; 10_000]).unwrap();
+        fs::write(root.join("blocks/index/000001.ldb"), vec![1u8; 300]).unwrap();
+    }
+
+    #[test]
+    fn copies_resumes_and_verifies() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (src, dst) = (tmp.path().join("src"), tmp.path().join("dst"));
+        datadir(&src);
+
+        // An interrupted earlier run: one partial block file.
+        let first = super::plan(&src, &dst).unwrap();
+        let blk = first
+            .files
+            .iter()
+            .find(|f| f.rel.ends_with("blk00000.dat"))
+            .unwrap();
+        interrupted(&dst, blk, &[7u8; 4000]);
+
+        let plan = plan(&src, &dst).unwrap();
+        assert_eq!(plan.files.len(), 3);
+        assert!(plan.dirs.contains(&PathBuf::from("chainstate")));
+        assert_eq!(plan.total_bytes, 10_000 + 300 + 9);
+        assert_eq!(plan.present_bytes, 4000);
+        assert!(plan.fits());
+
+        let progress = Progress::default();
+        copy(&plan, &progress).unwrap();
+        assert_eq!(
+            progress.copied_bytes.load(Ordering::Relaxed),
+            plan.total_bytes
+        );
+        assert_eq!(
+            fs::read(dst.join("blocks/blk00000.dat")).unwrap(),
+            vec![7u8; 10_000]
+        );
+        assert!(dst.join("chainstate").is_dir());
+        verify(&plan).unwrap();
+
+        // A second run has nothing left to do.
+        assert_eq!(super::plan(&src, &dst).unwrap().needed_bytes(), 0);
+
+        fs::write(dst.join("bitcoin.conf"), "changed\n").unwrap();
+        let err = verify(&plan).unwrap_err().to_string();
+        assert!(err.starts_with("1 of 3 files differ"), "{err}");
+    }
+
+    /// Leave what a run stopped after `copied` bytes of `entry` leaves.
+    fn interrupted(dst: &Path, entry: &Entry, copied: &[u8]) {
+        let target = dst.join(&entry.rel);
+        fs::create_dir_all(target.parent().unwrap()).unwrap();
+        fs::write(partial_path(&target), copied).unwrap();
+        fs::write(source_path(&target), source_stamp(entry)).unwrap();
+    }
+
+    #[test]
+    fn restarts_a_partial_file_whose_source_changed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (src, dst) = (tmp.path().join("src"), tmp.path().join("dst"));
+        fs::create_dir_all(&src).unwrap();
+        fs::write(src.join("mempool.dat"), vec![1u8; 6000]).unwrap();
+
+        let first = plan(&src, &dst).unwrap();
+        interrupted(&dst, &first.files[0], &[1u8; 2500]);
+        assert_eq!(plan(&src, &dst).unwrap().present_bytes, 2500);
+
+        // bitcoind rewrites the file before the copy is resumed.
+        let file = File::options()
+            .write(true)
+            .open(src.join("mempool.dat"))
+            .unwrap();
+        file.write_all_at(&[2u8; 6000], 0).unwrap();
+        file.set_modified(first.files[0].modified + Duration::from_secs(60))
+            .unwrap();
+        drop(file);
+
+        let second = plan(&src, &dst).unwrap();
+        assert_eq!(second.present_bytes, 0);
+        copy(&second, &Progress::default()).unwrap();
+        verify(&second).unwrap();
+        assert_eq!(fs::read(dst.join("mempool.dat")).unwrap(), vec![2u8; 6000]);
+        assert!(!source_path(&dst.join("mempool.dat")).exists());
+    }
+
+    #[test]
+    fn refuses_nested_locations_and_cancels() {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = tmp.path().join("src");
+        datadir(&src);
+        assert!(plan(&src, &src.join("copy")).is_err());
+        assert!(plan(&src, &src).is_err());
+
+        let dst = tmp.path().join("cancelled");
+        let plan = plan(&src, &dst).unwrap();
+        let progress = Progress::default();
+        progress.cancel.store(true, Ordering::Relaxed);
+        assert!(copy(&plan, &progress).is_err());
+        verify(&plan).unwrap_err();
+    }
+
+    #[test]
+    fn follows_symlinks_and_refuses_a_foreign_destination() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (src, dst) = (tmp.path().join("src"), tmp.path().join("dst"));
+        datadir(&src);
+        // `blocks/` kept on another drive.
+        let elsewhere = tmp.path().join("hdd-blocks");
+        fs::rename(src.join("blocks"), &elsewhere).unwrap();
+        std::os::unix::fs::symlink(&elsewhere, src.join("blocks")).unwrap();
+
+        let plan = super::plan(&src, &dst).unwrap();
+        assert_eq!(plan.total_bytes, 10_000 + 300 + 9);
+        copy(&plan, &Progress::default()).unwrap();
+        verify(&plan).unwrap();
+        assert!(!dst.join("blocks").is_symlink());
+
+        // A finished copy may be planned again, but not into other data.
+        super::plan(&src, &dst).unwrap();
+        fs::write(dst.join("blocks/wallet.dat"), "keys").unwrap();
+        let err = super::plan(&src, &dst).unwrap_err().to_string();
+        assert!(err.contains("blocks/wallet.dat"), "{err}");
+
+        std::os::unix::fs::symlink(&src, elsewhere.join("loop")).unwrap();
+        let err = super::plan(&src, &tmp.path().join("other")).unwrap_err();
+        assert!(err.to_string().contains("links back"), "{err}");
+        fs::remove_file(elsewhere.join("loop")).unwrap();
+        std::os::unix::fs::symlink(tmp.path().join("gone"), src.join("broken")).unwrap();
+        let err = super::plan(&src, &tmp.path().join("other")).unwrap_err();
+        assert!(err.to_string().contains("broken symlink"), "{err}");
+    }
+}
@@ -29,7 +29,7 @@ pub fn new_queue() -> OutputQueue {
     Arc::new(Mutex::new(VecDeque::new()))
 }
 
-fn push_line(queue: &OutputQueue, line: String) {
+pub fn push_line(queue: &OutputQueue, line: String) {
     if let Ok(mut q) = queue.lock() {
         // Cap at 10 000 lines to bound memory usage.
         if q.len() > 10_000 {
@@ -47,6 +47,10 @@ pub struct ProcessHandle {
 }
 
 impl ProcessHandle {
+    pub fn pid(&self) -> u32 {
+        self.child.id()
+    }
+
     /// Returns `true` if the process is still alive.
     pub fn is_running(&mut self) -> bool {
         matches!(self.child.try_wait(), Ok(None))
@@ -75,12 +79,14 @@ impl ProcessHandle {
 
 // ── Bitcoin ───────────────────────────────────────────────────────────────────
 
-/// Launch `bitcoind` and stream its output into `queue`.
+/// Launch `bitcoind` with `extra_args` after the defaults and stream its
+/// output into `queue`.
 ///
 /// Returns a handle to the spawned process and starts a background reader thread.
 pub fn launch_bitcoind(
     binaries_path: &Path,
     data_dir: &Path,
+    extra_args: &[String],
     queue: OutputQueue,
 ) -> Result<ProcessHandle> {
     let bitcoind = binaries_path.join("bitcoind");
@@ -91,11 +97,12 @@ pub fn launch_bitcoind(
     std::fs::create_dir_all(data_dir)
         .with_context(|| format!("create bitcoin data dir {}", data_dir.display()))?;
 
-    let cmd = [
+    let mut cmd = vec![
         bitcoind.to_string_lossy().into_owned(),
         format!("-datadir={}", data_dir.display()),
         "-printtoconsole".into(),
     ];
+    cmd.extend_from_slice(extra_args);
 
     push_line(&queue, format!("$ {}", cmd.join(" ")));
 
@@ -111,6 +118,9 @@ pub fn launch_bitcoind(
 
 // ── Electrs ───────────────────────────────────────────────────────────────────
 
+/// Address electrs serves the Electrum protocol on.
+pub const ELECTRUM_RPC_ADDR: &str = "127.0.0.1:50001";
+
 /// Launch `electrs` and stream its output into `queue`.
 pub fn launch_electrs(
     binaries_path: &Path,
@@ -135,7 +145,7 @@ pub fn launch_electrs(
         "--db-dir".into(),
         electrs_db_dir.to_string_lossy().into_owned(),
         "--electrum-rpc-addr".into(),
-        "127.0.0.1:50001".into(),
+        ELECTRUM_RPC_ADDR.into(),
     ];
 
     push_line(&queue, format!("$ {}", cmd.join(" ")));
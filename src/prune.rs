@@ -0,0 +1,179 @@
+//! Pruned-node support: the prune target, what it will cost on disk, and
+//! what already is there.
+//!
+//! Pruning is passed to bitcoind as `-prune=<MiB> -txindex=0` (see
+//! [`crate::tuning::Settings::args`]); the generated `bitcoin.conf` turns
+//! `txindex` on, and bitcoind refuses to start with both.  A full node
+//! switches to pruned in place on its next start, deleting old block files.
+//! Going back needs `-reindex`, which downloads the whole chain again.
+//! Electrs reads every block from bitcoind, so it cannot index a pruned node.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+use crate::{config::PruneConfig, tuning};
+
+const GIB: u64 = 1 << 30;
+
+/// Chainstate size assumed before one exists on disk (mainnet, 2026).
+const CHAINSTATE_ESTIMATE: u64 = 12 * GIB;
+
+/// Block and undo files of an unpruned mainnet node (2026).
+pub const FULL_BLOCKS_ESTIMATE: u64 = 750 * GIB;
+
+/// `-prune` value for `cfg`, in MiB, or `None` when pruning is off.
+pub fn target_mib(cfg: &PruneConfig) -> Option<u64> {
+    cfg.enabled
+        .then(|| (cfg.target_gib * 1024).max(tuning::MIN_PRUNE_MIB))
+}
+
+/// The `prune=` value `conf` sets for mainnet: the last one at the top or
+/// in `[main]`.  `0` (off) is `None`; `1` (manual pruning) still prunes.
+pub fn conf_prune_mib(conf: &str) -> Option<u64> {
+    let mut section = "";
+    let mut value = None;
+    for line in conf.lines() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim();
+            continue;
+        }
+        if !section.is_empty() && section != "main" {
+            continue;
+        }
+        if let Some((key, v)) = line.split_once('=') {
+            if key.trim() == "prune" {
+                value = v.trim().parse::<u64>().ok();
+            }
+        }
+    }
+    value.filter(|&v| v > 0)
+}
+
+/// What the data directory holds now.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Scan {
+    /// `prune=` in `bitcoin.conf`, in MiB.
+    pub conf_prune_mib: Option<u64>,
+    /// `blocks/`: block and undo files plus the block index.
+    pub blocks_bytes: u64,
+    pub chainstate_bytes: u64,
+    /// `indexes/txindex/`; `None` when there is none.
+    pub txindex_bytes: Option<u64>,
+}
+
+impl Scan {
+    /// Walk `data_dir`.  Missing directories count as empty.
+    pub fn run(data_dir: &Path) -> Self {
+        let conf = fs::read_to_string(data_dir.join("bitcoin.conf")).unwrap_or_default();
+        let txindex = txindex_dir(data_dir);
+        Self {
+            conf_prune_mib: conf_prune_mib(&conf),
+            blocks_bytes: dir_size(&data_dir.join("blocks")),
+            chainstate_bytes: dir_size(&data_dir.join("chainstate")),
+            txindex_bytes: txindex.is_dir().then(|| dir_size(&txindex)),
+        }
+    }
+
+    /// Disk the node needs with a `target_mib` prune target.
+    pub fn pruned_total(&self, target_mib: u64) -> u64 {
+        let chainstate = if self.chainstate_bytes > 0 {
+            self.chainstate_bytes
+        } else {
+            CHAINSTATE_ESTIMATE
+        };
+        // bitcoind keeps at least the target, plus the block index and a
+        // partly filled file beyond it.
+        target_mib * (1 << 20) + chainstate + GIB
+    }
+
+    /// Bytes the next start deletes when switching to `target_mib`, plus
+    /// the txindex that pruning leaves unused.
+    pub fn reclaimable(&self, target_mib: u64) -> u64 {
+        self.blocks_bytes.saturating_sub(target_mib * (1 << 20)) + self.txindex_bytes.unwrap_or(0)
+    }
+}
+
+fn txindex_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("indexes").join("txindex")
+}
+
+/// Remove the transaction index, unused once the node is pruned.
+pub fn delete_txindex(data_dir: &Path) -> Result<()> {
+    let dir = txindex_dir(data_dir);
+    fs::remove_dir_all(&dir).with_context(|| format!("delete {}", dir.display()))
+}
+
+/// Total size of the files under `dir`.
+pub fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(m) if m.is_dir() => dir_size(&entry.path()),
+            Ok(m) => m.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_mainnet_prune_from_conf() {
+        assert_eq!(conf_prune_mib("server=1\ntxindex=1\n"), None);
+        assert_eq!(conf_prune_mib("prune=0\n"), None);
+        assert_eq!(conf_prune_mib("prune=1\n"), Some(1));
+        assert_eq!(
+            conf_prune_mib("prune=2000\n[test]\nprune=550\n[main]\n prune = 5000\n"),
+            Some(5000)
+        );
+        assert_eq!(conf_prune_mib("[signet]\nprune=550\n"), None);
+    }
+
+    #[test]
+    fn target_respects_bitcoind_minimum() {
+        let mut cfg = PruneConfig {
+            enabled: false,
+            target_gib: 0,
+        };
+        assert_eq!(target_mib(&cfg), None);
+        cfg.enabled = true;
+        assert_eq!(target_mib(&cfg), Some(tuning::MIN_PRUNE_MIB));
+        cfg.target_gib = 20;
+        assert_eq!(target_mib(&cfg), Some(20_480));
+    }
+
+    #[test]
+    fn scans_data_dir_and_estimates() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        fs::create_dir_all(dir.join("blocks/index")).unwrap();
+        fs::create_dir_all(dir.join("indexes/txindex")).unwrap();
+        fs::write(dir.join("blocks/blk00000.dat"), vec![0u8; 3000]).unwrap();
+        fs::write(dir.join("blocks/index/000001.ldb"), vec![0u8; 1000]).unwrap();
+        fs::write(dir.join("indexes/txindex/000002.ldb"), vec![0u8; 500]).unwrap();
+        fs::write(dir.join("bitcoin.conf"), "prune=550\n").unwrap();
+
+        let scan = Scan::run(dir);
+        assert_eq!(scan.conf_prune_mib, Some(550));
+        assert_eq!(scan.blocks_bytes, 4000);
+        assert_eq!(scan.chainstate_bytes, 0);
+        assert_eq!(scan.txindex_bytes, Some(500));
+        assert_eq!(scan.reclaimable(550), 500);
+        assert_eq!(scan.pruned_total(1024), 14 * GIB);
+
+        delete_txindex(dir).unwrap();
+        assert_eq!(Scan::run(dir).txindex_bytes, None);
+    }
+}
@@ -0,0 +1,302 @@
+//! Per-process resource usage: CPU, resident memory, open file descriptors,
+//! disk throughput and thread count.
+//!
+//! On macOS the counters come from `proc_pidinfo` (`PROC_PIDTASKINFO`,
+//! `PROC_PIDLISTFDS`) and `proc_pid_rusage`; elsewhere from `/proc/<pid>`.
+//! Counters (CPU time, bytes read / written) become rates by diffing two
+//! samples, so the first sample after a launch shows no rates.
+
+use std::{collections::VecDeque, time::Instant};
+
+use anyhow::Result;
+
+/// Samples kept for the sparklines (two minutes at the 2 s tick).
+pub const HISTORY: usize = 60;
+
+/// Raw counters at one instant.
+#[derive(Debug, Clone)]
+pub struct Sample {
+    at: Instant,
+    /// User + system CPU time.
+    cpu_nanos: u64,
+    threads: u64,
+    rss_bytes: u64,
+    /// Open file descriptors; may be unreadable.
+    fds: Option<u64>,
+    /// Bytes read from / written to storage; may be unreadable.
+    io: Option<(u64, u64)>,
+}
+
+pub use platform::sample;
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::{ffi::c_void, mem::size_of, time::Instant};
+
+    use anyhow::{bail, Result};
+
+    use super::Sample;
+
+    #[repr(C)]
+    struct Timebase {
+        numer: u32,
+        denom: u32,
+    }
+
+    extern "C" {
+        fn mach_timebase_info(info: *mut Timebase) -> libc::c_int;
+    }
+
+    /// Read the counters of `pid`.
+    pub fn sample(pid: u32) -> Result<Sample> {
+        let at = Instant::now();
+        let Ok(pid) = libc::c_int::try_from(pid) else {
+            bail!("pid {pid} out of range");
+        };
+
+        // SAFETY: `task` is a plain C struct, valid when zeroed.
+        let mut task: libc::proc_taskinfo = unsafe { std::mem::zeroed() };
+        let size = libc::c_int::try_from(size_of::<libc::proc_taskinfo>())?;
+        // SAFETY: the buffer is `task` and the size passed is its own.
+        let written = unsafe {
+            libc::proc_pidinfo(
+                pid,
+                libc::PROC_PIDTASKINFO,
+                0,
+                (&raw mut task).cast::<c_void>(),
+                size,
+            )
+        };
+        if written != size {
+            bail!("proc_pidinfo({pid}): {}", std::io::Error::last_os_error());
+        }
+
+        Ok(Sample {
+            at,
+            cpu_nanos: mach_to_nanos(task.pti_total_user + task.pti_total_system),
+            threads: u64::try_from(task.pti_threadnum).unwrap_or(0),
+            rss_bytes: task.pti_resident_size,
+            fds: open_fds(pid),
+            io: disk_io(pid),
+        })
+    }
+
+    /// CPU times are in Mach absolute time units, which are not nanoseconds
+    /// on Apple Silicon.
+    fn mach_to_nanos(ticks: u64) -> u64 {
+        let mut tb = Timebase { numer: 0, denom: 0 };
+        // SAFETY: `tb` is a valid out-parameter.
+        if unsafe { mach_timebase_info(&raw mut tb) } != 0 || tb.denom == 0 {
+            return ticks;
+        }
+        let nanos = u128::from(ticks) * u128::from(tb.numer) / u128::from(tb.denom);
+        u64::try_from(nanos).unwrap_or(u64::MAX)
+    }
+
+    fn open_fds(pid: libc::c_int) -> Option<u64> {
+        // A null buffer asks for the size needed; the table can grow between
+        // the calls, so leave room.
+        // SAFETY: a null buffer with size 0 is the documented size query.
+        let needed =
+            unsafe { libc::proc_pidinfo(pid, libc::PROC_PIDLISTFDS, 0, std::ptr::null_mut(), 0) };
+        let entry = size_of::<libc::proc_fdinfo>();
+        let count = usize::try_from(needed).ok()? / entry + 16;
+        let mut fds: Vec<libc::proc_fdinfo> = Vec::with_capacity(count);
+        let size = libc::c_int::try_from(count * entry).ok()?;
+        // SAFETY: `fds` has capacity for `size` bytes of `proc_fdinfo`.
+        let written = unsafe {
+            libc::proc_pidinfo(
+                pid,
+                libc::PROC_PIDLISTFDS,
+                0,
+                fds.as_mut_ptr().cast::<c_void>(),
+                size,
+            )
+        };
+        let written = usize::try_from(written).ok().filter(|&w| w > 0)?;
+        u64::try_from(written / entry).ok()
+    }
+
+    fn disk_io(pid: libc::c_int) -> Option<(u64, u64)> {
+        // SAFETY: `usage` is a plain C struct, valid when zeroed.
+        let mut usage: libc::rusage_info_v2 = unsafe { std::mem::zeroed() };
+        // SAFETY: `RUSAGE_INFO_V2` fills exactly a `rusage_info_v2`.
+        let rc = unsafe {
+            libc::proc_pid_rusage(
+                pid,
+                libc::RUSAGE_INFO_V2,
+                (&raw mut usage).cast::<libc::rusage_info_t>(),
+            )
+        };
+        (rc == 0).then_some((usage.ri_diskio_bytesread, usage.ri_diskio_byteswritten))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    use std::{fs, time::Instant};
+
+    use anyhow::{Context, Result};
+
+    use super::{sysconf, Sample};
+
+    /// Read the counters of `pid`.
+    pub fn sample(pid: u32) -> Result<Sample> {
+        let at = Instant::now();
+        let stat = fs::read_to_string(format!("/proc/{pid}/stat"))
+            .with_context(|| format!("read /proc/{pid}/stat"))?;
+        let (cpu_ticks, threads, rss_pages) = parse_stat(&stat).context("parse /proc stat")?;
+        let fds = fs::read_dir(format!("/proc/{pid}/fd"))
+            .ok()
+            .map(|dir| dir.count() as u64);
+        let io = fs::read_to_string(format!("/proc/{pid}/io"))
+            .ok()
+            .and_then(|s| parse_io(&s));
+        Ok(Sample {
+            at,
+            cpu_nanos: cpu_ticks * 1_000_000_000 / sysconf(libc::_SC_CLK_TCK, 100),
+            threads,
+            rss_bytes: rss_pages * sysconf(libc::_SC_PAGESIZE, 4096),
+            fds,
+            io,
+        })
+    }
+
+    /// `(utime + stime, num_threads, rss)` from a `/proc/<pid>/stat` line.
+    pub(super) fn parse_stat(stat: &str) -> Option<(u64, u64, u64)> {
+        // The command name is in parentheses and may itself contain spaces or
+        // parentheses; fields are counted from after the last `)`.
+        let rest = &stat[stat.rfind(')')? + 1..];
+        let fields: Vec<&str> = rest.split_whitespace().collect();
+        // `rest` starts at field 3 (state); see proc(5).
+        let field = |n: usize| fields.get(n - 3)?.parse::<u64>().ok();
+        Some((field(14)? + field(15)?, field(20)?, field(24)?))
+    }
+
+    pub(super) fn parse_io(io: &str) -> Option<(u64, u64)> {
+        let value = |key: &str| {
+            io.lines()
+                .find_map(|l| l.strip_prefix(key)?.trim().parse::<u64>().ok())
+        };
+        Some((value("read_bytes:")?, value("write_bytes:")?))
+    }
+}
+
+/// A `sysconf` value, or `fallback` when it is unavailable.
+pub fn sysconf(name: libc::c_int, fallback: u64) -> u64 {
+    // SAFETY: sysconf only reads a configuration value.
+    let v = unsafe { libc::sysconf(name) };
+    u64::try_from(v).ok().filter(|&v| v > 0).unwrap_or(fallback)
+}
+
+/// Usage over the interval between two samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Usage {
+    /// Percent of one core (can exceed 100 on several cores).
+    pub cpu_percent: f64,
+    pub rss_bytes: u64,
+    pub fds: Option<u64>,
+    /// Bytes per second read from and written to storage.
+    pub disk_rates: Option<(f64, f64)>,
+    pub threads: u64,
+}
+
+/// The last sample and recent usage of one process.
+#[derive(Debug, Default)]
+pub struct Monitor {
+    last: Option<Sample>,
+    history: VecDeque<Usage>,
+    /// Why the last sample failed, if it did.
+    pub error: Option<String>,
+}
+
+impl Monitor {
+    pub fn record(&mut self, result: Result<Sample>) {
+        let sample = match result {
+            Ok(s) => s,
+            Err(e) => {
+                self.error = Some(format!("{e:#}"));
+                return;
+            }
+        };
+        self.error = None;
+        if let Some(prev) = &self.last {
+            let secs = sample.at.duration_since(prev.at).as_secs_f64();
+            if secs > 0.0 {
+                #[allow(clippy::cast_precision_loss)]
+                let rate = |now: u64, then: u64| now.saturating_sub(then) as f64 / secs;
+                // CPU nanoseconds per second → percent of one core.
+                let cpu_percent = rate(sample.cpu_nanos, prev.cpu_nanos) / 1e7;
+                let disk_rates = sample
+                    .io
+                    .zip(prev.io)
+                    .map(|((r, w), (pr, pw))| (rate(r, pr), rate(w, pw)));
+                if self.history.len() == HISTORY {
+                    self.history.pop_front();
+                }
+                self.history.push_back(Usage {
+                    cpu_percent,
+                    rss_bytes: sample.rss_bytes,
+                    fds: sample.fds,
+                    disk_rates,
+                    threads: sample.threads,
+                });
+            }
+        }
+        self.last = Some(sample);
+    }
+
+    pub fn latest(&self) -> Option<&Usage> {
+        self.history.back()
+    }
+
+    /// One value per recorded interval, oldest first.
+    pub fn series(&self, value: impl Fn(&Usage) -> f64) -> Vec<f64> {
+        self.history.iter().map(value).collect()
+    }
+
+    /// Start over (the process was relaunched).
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(target_os = "macos"))]
+    use super::platform::{parse_io, parse_stat};
+
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn parses_stat_with_awkward_command_name() {
+        let stat = "4242 (b-msghand) (x)) S 1 4242 4242 0 -1 4194560 1 0 0 0 \
+                    150 50 0 0 20 0 12 0 100 123456789 2048 18446744073709551615";
+        assert_eq!(parse_stat(stat), Some((200, 12, 2048)));
+        assert_eq!(parse_stat("garbage"), None);
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn parses_io() {
+        let io = "rchar: 10\nwchar: 20\nsyscr: 1\nsyscw: 2\n\
+                  read_bytes: 4096\nwrite_bytes: 8192\ncancelled_write_bytes: 0\n";
+        assert_eq!(parse_io(io), Some((4096, 8192)));
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    #[test]
+    fn samples_own_process_into_usage() {
+        let mut monitor = Monitor::default();
+        monitor.record(sample(std::process::id()));
+        assert!(monitor.latest().is_none());
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        monitor.record(sample(std::process::id()));
+        let usage = monitor.latest().expect("second sample gives usage");
+        assert!(usage.rss_bytes > 0);
+        assert!(usage.threads >= 1);
+    }
+}
@@ -3,19 +3,60 @@
 //! Uses cookie-file authentication by default (the `.cookie` file that
 //! `bitcoind` writes on every startup).  Falls back to `rpcuser`/`rpcpassword`
 //! from `bitcoin.conf` when no cookie is found.
+//!
+//! Typed wrappers for individual RPC families live in submodules and are
+//! re-exported here.
+
+mod blockchain;
+mod mempool;
+mod network;
+mod psbt;
+mod rawtx;
+mod snapshot;
+mod util;
+mod wallet;
+
+pub use blockchain::{
+    get_block, get_block_hash, get_block_header, get_chain_tips, get_transaction, Block, ChainTip,
+    Transaction,
+};
+pub use mempool::{
+    btc_per_kvb_to_sat_per_vb, estimate_smart_fee, get_mempool_info, get_mempool_stats,
+    FeeEstimate, MempoolInfo, MempoolStats,
+};
+pub use network::{
+    ban_peer, disconnect_node, get_net_totals, get_network_info, get_peer_info, NetTotals,
+    NetworkInfo, PeerInfo,
+};
+pub use psbt::{
+    analyze_psbt, combine_psbt, decode_psbt, finalize_psbt, DecodedPsbt, FinalizedPsbt,
+    InputAnalysis, MissingData, PsbtAnalysis, PsbtInput,
+};
+pub use rawtx::{decode_raw_transaction, send_raw_transaction, test_mempool_accept, MempoolAccept};
+pub use snapshot::{
+    dump_txoutset, get_chainstates, load_txoutset, Chainstates, DumpType, DumpedSnapshot,
+};
+pub use util::{derive_addresses, get_descriptor_info, validate_address};
+pub use wallet::{
+    backup_wallet, create_wallet, get_balances, get_wallet_info, list_wallet_dir, list_wallets,
+    load_wallet, rescan_blockchain, unload_wallet, Balances, CreateWallet, WalletInfo,
+};
 
 use std::path::Path;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::{bail, Context, Result};
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 
+/// Timeout for ordinary polling calls.
+const RPC_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Lazily-built HTTP client (one per poll cycle is fine; keep it cheap).
-fn http_client() -> Result<Client> {
+fn http_client(timeout: Duration) -> Result<Client> {
     Client::builder()
-        .timeout(Duration::from_secs(5))
+        .timeout(timeout)
         .build()
         .context("build reqwest client")
 }
@@ -45,6 +86,8 @@ pub struct BlockchainInfo {
     pub verification_progress: f64,
     pub chain: String,
     pub initial_block_download: bool,
+    pub best_block_hash: String,
+    pub pruned: bool,
 }
 
 // ── Authentication ────────────────────────────────────────────────────────────
@@ -130,8 +173,115 @@ fn read_static_credentials(data_dir: &Path) -> Option<(String, String)> {
 
 /// Make a single synchronous-style async RPC call.
 pub async fn call(auth: &RpcAuth, method: &str, params: Value) -> Result<Value> {
-    let client = http_client()?;
-    let url = format!("http://127.0.0.1:{}/", auth.port);
+    call_at(auth, None, method, params, RPC_TIMEOUT).await
+}
+
+/// Like [`call`], but against one loaded wallet (`/wallet/<name>`) and with
+/// an explicit timeout.  `None` targets the node endpoint, which wallet
+/// RPCs accept when exactly one wallet is loaded.
+pub async fn call_at(
+    auth: &RpcAuth,
+    wallet: Option<&str>,
+    method: &str,
+    params: Value,
+    timeout: Duration,
+) -> Result<Value> {
+    call_optional_at(auth, wallet, method, params, timeout)
+        .await?
+        .context("RPC result was null")
+}
+
+/// Call a method that answers `null` on success (`setban`, `backupwallet`, …).
+pub async fn call_void(auth: &RpcAuth, method: &str, params: Value) -> Result<()> {
+    call_void_at(auth, None, method, params, RPC_TIMEOUT).await
+}
+
+/// [`call_void`] with the wallet endpoint and timeout of [`call_at`].
+pub async fn call_void_at(
+    auth: &RpcAuth,
+    wallet: Option<&str>,
+    method: &str,
+    params: Value,
+    timeout: Duration,
+) -> Result<()> {
+    call_optional_at(auth, wallet, method, params, timeout).await?;
+    Ok(())
+}
+
+/// Like [`call`], but a `null` result is `Ok(None)` rather than an error.
+pub async fn call_optional(auth: &RpcAuth, method: &str, params: Value) -> Result<Option<Value>> {
+    call_optional_at(auth, None, method, params, RPC_TIMEOUT).await
+}
+
+/// Like [`call_at`], but a `null` result is `Ok(None)` rather than an error.
+async fn call_optional_at(
+    auth: &RpcAuth,
+    wallet: Option<&str>,
+    method: &str,
+    params: Value,
+    timeout: Duration,
+) -> Result<Option<Value>> {
+    let resp = post(auth, wallet, method, params, timeout).await?;
+    let body = resp.bytes().await.context("read RPC response")?;
+    decode_response(&body)
+}
+
+/// Split a response body into its result; only a non-null `error` fails.
+fn decode_response(body: &[u8]) -> Result<Option<Value>> {
+    let rpc_resp: RpcResponse = serde_json::from_slice(body).context("parse RPC response")?;
+    if let Some(err) = rpc_resp.error {
+        bail!("RPC error: {err}");
+    }
+    Ok(rpc_resp.result)
+}
+
+/// Like [`call`], but returns the undecoded response body.
+///
+/// For results that can be hundreds of megabytes (`getrawmempool true`):
+/// the caller decodes with [`parse_raw`] off the async runtime.
+pub async fn call_raw(
+    auth: &RpcAuth,
+    method: &str,
+    params: Value,
+    timeout: Duration,
+) -> Result<Vec<u8>> {
+    let resp = post(auth, None, method, params, timeout).await?;
+    let body = resp.bytes().await.context("read RPC response")?;
+    Ok(body.to_vec())
+}
+
+/// Decode a body returned by [`call_raw`] straight into `T`.
+pub fn parse_raw<T: DeserializeOwned>(body: &[u8]) -> Result<T> {
+    #[derive(Deserialize)]
+    struct Typed<T> {
+        result: Option<T>,
+        error: Option<Value>,
+    }
+
+    let resp: Typed<T> = serde_json::from_slice(body).context("parse RPC response")?;
+    if let Some(err) = resp.error {
+        bail!("RPC error: {err}");
+    }
+    resp.result.context("RPC result was null")
+}
+
+async fn post(
+    auth: &RpcAuth,
+    wallet: Option<&str>,
+    method: &str,
+    params: Value,
+    timeout: Duration,
+) -> Result<reqwest::Response> {
+    let client = http_client(timeout)?;
+    let mut url = reqwest::Url::parse(&format!("http://127.0.0.1:{}/", auth.port))
+        .context("build RPC URL")?;
+    if let Some(name) = wallet {
+        // Pushing a segment percent-encodes spaces and slashes in the name.
+        url.path_segments_mut()
+            .map_err(|()| anyhow::anyhow!("RPC URL cannot have a path"))?
+            .pop_if_empty()
+            .extend(["wallet", name]);
+    }
 
     let req = RpcRequest {
         jsonrpc: "1.0",
@@ -140,26 +290,22 @@ pub async fn call(auth: &RpcAuth, method: &str, params: Value) -> Result<Value>
         params,
     };
 
+    let started = Instant::now();
     let resp = client
-        .post(&url)
+        .post(url)
         .basic_auth(&auth.user, Some(&auth.password))
         .json(&req)
         .send()
-        .await
-        .context("RPC HTTP request")?;
+        .await;
+    crate::metrics::observe_rpc(method, started.elapsed());
+    let resp = resp.context("RPC HTTP request")?;
 
     let status = resp.status();
     if status == 401 {
         bail!("RPC authentication failed (401). Check bitcoin.conf credentials or .cookie file.");
     }
 
-    let rpc_resp: RpcResponse = resp.json().await.context("parse RPC response")?;
-
-    if let Some(err) = rpc_resp.error {
-        bail!("RPC error: {err}");
-    }
-
-    rpc_resp.result.context("RPC result was null")
+    Ok(resp)
 }
 
 /// Call `getblockchaininfo` and return parsed data.
@@ -172,6 +318,8 @@ pub async fn get_blockchain_info(auth: &RpcAuth) -> Result<BlockchainInfo> {
         verification_progress: v["verificationprogress"].as_f64().unwrap_or(0.0),
         chain: v["chain"].as_str().unwrap_or("").to_owned(),
         initial_block_download: v["initialblockdownload"].as_bool().unwrap_or(true),
+        best_block_hash: v["bestblockhash"].as_str().unwrap_or("").to_owned(),
+        pruned: v["pruned"].as_bool().unwrap_or(false),
     })
 }
 
@@ -203,3 +351,27 @@ pub fn ensure_bitcoin_conf(data_dir: &Path) -> Result<()> {
     .with_context(|| format!("write bitcoin.conf {}", conf_path.display()))?;
     Ok(())
 }
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn null_result_is_not_an_error() {
+        assert_eq!(
+            decode_response(br#"{"result":null,"error":null,"id":"bnm"}"#).unwrap(),
+            None
+        );
+        assert_eq!(
+            decode_response(br#"{"result":5,"error":null,"id":"bnm"}"#).unwrap(),
+            Some(Value::from(5))
+        );
+        let err = decode_response(
+            br#"{"result":null,"error":{"code":-23,"message":"Error: IP/Subnet already banned"},"id":"bnm"}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().starts_with("RPC error:"), "{err}");
+    }
+}
@@ -0,0 +1,355 @@
+//! Typed wrappers for block and transaction lookups: `getblockhash`,
+//! `getblockheader`, `getblock` (verbosity 2), `getrawtransaction` and
+//! `getchaintips`.
+
+use std::{collections::HashMap, time::Duration};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{call, call_raw, parse_raw, RpcAuth};
+
+/// A full block at verbosity 2 can be tens of megabytes of JSON.
+const BLOCK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Inputs whose previous outputs are looked up one by one when the node is
+/// too old to return `prevout` itself.
+const MAX_PREVOUT_LOOKUPS: usize = 100;
+
+/// Parsed result of `getblockheader`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BlockHeader {
+    pub hash: String,
+    pub height: u64,
+    /// `-1` when the block is not on the active chain.
+    pub confirmations: i64,
+    pub time: u64,
+    #[serde(default)]
+    pub mediantime: u64,
+    #[serde(rename = "nTx")]
+    pub n_tx: u64,
+    #[serde(default)]
+    pub difficulty: f64,
+    #[serde(default)]
+    pub version: i64,
+    #[serde(default)]
+    pub merkleroot: String,
+    #[serde(default)]
+    pub previousblockhash: Option<String>,
+    #[serde(default)]
+    pub nextblockhash: Option<String>,
+}
+
+/// Parsed result of `getblock <hash> 2`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Block {
+    #[serde(flatten)]
+    pub header: BlockHeader,
+    pub size: u64,
+    #[serde(default)]
+    pub strippedsize: u64,
+    pub weight: u64,
+    pub tx: Vec<Transaction>,
+}
+
+impl Block {
+    /// Fees collected by the block: coinbase outputs minus the subsidy.
+    pub fn total_fees(&self) -> Option<f64> {
+        let coinbase = self.tx.first()?;
+        let fees = coinbase.output_total() - block_subsidy(self.header.height);
+        Some(fees.max(0.0))
+    }
+}
+
+/// New coins created by the block at `height`, in BTC.
+pub fn block_subsidy(height: u64) -> f64 {
+    let halvings = height / 210_000;
+    if halvings >= 64 {
+        return 0.0;
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let sats = ((50 * 100_000_000_u64) >> halvings) as f64;
+    sats / 100_000_000.0
+}
+
+/// `scriptPubKey` of an output.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ScriptPubKey {
+    #[serde(default)]
+    pub address: Option<String>,
+    /// `witness_v0_keyhash`, `pubkeyhash`, `nulldata`, …
+    #[serde(default, rename = "type")]
+    pub kind: String,
+}
+
+impl ScriptPubKey {
+    /// Address if the script has one, otherwise its type.
+    pub fn describe(&self) -> String {
+        self.address
+            .clone()
+            .unwrap_or_else(|| format!("<{}>", self.kind))
+    }
+}
+
+/// Previous output spent by an input (`prevout`, Core 25+ / `getblock 3`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct PrevOut {
+    pub value: f64,
+    #[serde(rename = "scriptPubKey")]
+    pub script_pub_key: ScriptPubKey,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TxIn {
+    /// Absent for the coinbase input.
+    #[serde(default)]
+    pub txid: Option<String>,
+    #[serde(default)]
+    pub vout: Option<u32>,
+    #[serde(default)]
+    pub coinbase: Option<String>,
+    #[serde(default = "final_sequence")]
+    pub sequence: u64,
+    #[serde(default)]
+    pub prevout: Option<PrevOut>,
+}
+
+const fn final_sequence() -> u64 {
+    0xffff_ffff
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TxOut {
+    pub value: f64,
+    pub n: u32,
+    #[serde(rename = "scriptPubKey")]
+    pub script_pub_key: ScriptPubKey,
+}
+
+/// A transaction as returned by `getrawtransaction` (verbose) or inside
+/// `getblock 2`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Transaction {
+    pub txid: String,
+    #[serde(default)]
+    pub hash: String,
+    pub size: u64,
+    pub vsize: u64,
+    pub weight: u64,
+    #[serde(default)]
+    pub version: i64,
+    #[serde(default)]
+    pub locktime: u64,
+    pub vin: Vec<TxIn>,
+    pub vout: Vec<TxOut>,
+    /// Fee in BTC; reported by `getblock 2` (Core 23+) and
+    /// `getrawtransaction 2`, or filled in from looked-up prevouts.
+    #[serde(default)]
+    pub fee: Option<f64>,
+    #[serde(default)]
+    pub blockhash: Option<String>,
+    #[serde(default)]
+    pub confirmations: Option<u64>,
+    #[serde(default)]
+    pub blocktime: Option<u64>,
+}
+
+impl Transaction {
+    pub fn is_coinbase(&self) -> bool {
+        self.vin.first().is_some_and(|i| i.coinbase.is_some())
+    }
+
+    /// Any input with a sequence below `0xfffffffe` opts in to replacement.
+    pub fn signals_rbf(&self) -> bool {
+        !self.is_coinbase() && self.vin.iter().any(|i| i.sequence < 0xffff_fffe)
+    }
+
+    pub fn output_total(&self) -> f64 {
+        self.vout.iter().map(|o| o.value).sum()
+    }
+
+    /// Fee rate in sat/vB, when the fee is known.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn fee_rate(&self) -> Option<f64> {
+        let fee = self.fee?;
+        (self.vsize > 0).then(|| fee * 100_000_000.0 / self.vsize as f64)
+    }
+
+    /// Derive the fee from input prevouts when every input has one.
+    fn fill_fee_from_prevouts(&mut self) {
+        if self.fee.is_some() || self.is_coinbase() {
+            return;
+        }
+        let inputs: Option<f64> = self
+            .vin
+            .iter()
+            .map(|i| i.prevout.as_ref().map(|p| p.value))
+            .sum();
+        if let Some(inputs) = inputs {
+            // Round to whole satoshis to hide float noise.
+            self.fee = Some(((inputs - self.output_total()) * 1e8).round() / 1e8);
+        }
+    }
+}
+
+/// One entry of `getchaintips`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChainTip {
+    pub height: u64,
+    pub hash: String,
+    /// Blocks between this tip and the active chain (`0` for the active tip).
+    pub branchlen: u64,
+    /// `active`, `valid-fork`, `valid-headers`, `headers-only` or `invalid`.
+    pub status: String,
+}
+
+impl ChainTip {
+    /// Height of the last block this tip shares with the active chain.
+    pub fn fork_height(&self) -> u64 {
+        self.height.saturating_sub(self.branchlen)
+    }
+}
+
+/// Call `getchaintips`.
+pub async fn get_chain_tips(auth: &RpcAuth) -> Result<Vec<ChainTip>> {
+    let v = call(auth, "getchaintips", json!([])).await?;
+    serde_json::from_value(v).context("parse getchaintips")
+}
+
+/// Call `getblockhash`.
+pub async fn get_block_hash(auth: &RpcAuth, height: u64) -> Result<String> {
+    let v = call(auth, "getblockhash", json!([height])).await?;
+    serde_json::from_value(v).context("parse getblockhash")
+}
+
+/// Call `getblockheader` (verbose).
+pub async fn get_block_header(auth: &RpcAuth, hash: &str) -> Result<BlockHeader> {
+    let v = call(auth, "getblockheader", json!([hash, true])).await?;
+    serde_json::from_value(v).context("parse getblockheader")
+}
+
+/// Call `getblock <hash> 2`, decoding on the blocking pool.
+pub async fn get_block(auth: &RpcAuth, hash: &str) -> Result<Block> {
+    let body = call_raw(auth, "getblock", json!([hash, 2]), BLOCK_TIMEOUT).await?;
+    tokio::task::spawn_blocking(move || parse_raw::<Block>(&body).context("parse getblock"))
+        .await
+        .context("block decode task")?
+}
+
+/// Call `getrawtransaction` and make sure inputs carry their `prevout`
+/// where possible, so the fee is known.
+///
+/// Core 25+ returns prevouts with verbosity 2; older nodes only accept a
+/// boolean, in which case prevouts are fetched individually (this relies on
+/// `txindex=1` for confirmed parents).
+pub async fn get_transaction(auth: &RpcAuth, txid: &str) -> Result<Transaction> {
+    let v = match call(auth, "getrawtransaction", json!([txid, 2])).await {
+        Ok(v) => v,
+        Err(_) => call(auth, "getrawtransaction", json!([txid, true])).await?,
+    };
+    let mut tx: Transaction = serde_json::from_value(v).context("parse getrawtransaction")?;
+
+    let missing = tx
+        .vin
+        .iter()
+        .filter(|i| i.prevout.is_none() && i.txid.is_some())
+        .count();
+    if missing > 0 && missing <= MAX_PREVOUT_LOOKUPS {
+        let mut parents: HashMap<String, Transaction> = HashMap::new();
+        for input in &mut tx.vin {
+            let (Some(parent_id), Some(n)) = (input.txid.clone(), input.vout) else {
+                continue;
+            };
+            if input.prevout.is_some() {
+                continue;
+            }
+            if !parents.contains_key(&parent_id) {
+                let Ok(v) = call(auth, "getrawtransaction", json!([parent_id, true])).await else {
+                    continue;
+                };
+                let Ok(parent) = serde_json::from_value(v) else {
+                    continue;
+                };
+                parents.insert(parent_id.clone(), parent);
+            }
+            input.prevout = parents[&parent_id]
+                .vout
+                .iter()
+                .find(|o| o.n == n)
+                .map(|o| PrevOut {
+                    value: o.value,
+                    script_pub_key: o.script_pub_key.clone(),
+                });
+        }
+    }
+    tx.fill_fee_from_prevouts();
+    Ok(tx)
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subsidy_halves() {
+        assert!((block_subsidy(0) - 50.0).abs() < 1e-12);
+        assert!((block_subsidy(209_999) - 50.0).abs() < 1e-12);
+        assert!((block_subsidy(210_000) - 25.0).abs() < 1e-12);
+        assert!((block_subsidy(840_000) - 3.125).abs() < 1e-12);
+        assert!(block_subsidy(64 * 210_000).abs() < 1e-12);
+    }
+
+    #[test]
+    fn parses_block_and_fees() {
+        let v = json!({
+            "hash": "00ab", "height": 840_000, "confirmations": 3, "time": 1_713_571_767,
+            "nTx": 2, "previousblockhash": "00aa", "size": 500, "weight": 1600,
+            "tx": [
+                {
+                    "txid": "c0", "size": 100, "vsize": 100, "weight": 400,
+                    "vin": [{ "coinbase": "03", "sequence": 4_294_967_295_u64 }],
+                    "vout": [{ "value": 3.125_05, "n": 0,
+                               "scriptPubKey": { "address": "bc1qminer", "type": "witness_v0_keyhash" } }]
+                },
+                {
+                    "txid": "t1", "size": 200, "vsize": 150, "weight": 600, "fee": 0.000_05,
+                    "vin": [{ "txid": "p0", "vout": 1, "sequence": 0 }],
+                    "vout": [{ "value": 0.1, "n": 0, "scriptPubKey": { "type": "nulldata" } }]
+                }
+            ]
+        });
+        let block: Block = serde_json::from_value(v).unwrap();
+        assert_eq!(block.header.height, 840_000);
+        assert!(block.header.nextblockhash.is_none());
+        assert!(block.tx[0].is_coinbase());
+        assert!(!block.tx[0].signals_rbf());
+        assert!(block.tx[1].signals_rbf());
+        assert!((block.total_fees().unwrap() - 0.000_05).abs() < 1e-9);
+        assert!((block.tx[1].fee_rate().unwrap() - 33.333).abs() < 1e-3);
+        assert_eq!(block.tx[1].vout[0].script_pub_key.describe(), "<nulldata>");
+    }
+
+    #[test]
+    fn fee_from_prevouts() {
+        let v = json!({
+            "txid": "t1", "size": 200, "vsize": 141, "weight": 561,
+            "vin": [
+                { "txid": "p0", "vout": 0, "prevout": { "value": 0.5, "scriptPubKey": { "type": "x" } } },
+                { "txid": "p1", "vout": 3, "prevout": { "value": 0.25, "scriptPubKey": { "type": "x" } } }
+            ],
+            "vout": [{ "value": 0.749_99, "n": 0, "scriptPubKey": { "type": "x" } }]
+        });
+        let mut tx: Transaction = serde_json::from_value(v).unwrap();
+        tx.fill_fee_from_prevouts();
+        assert!((tx.fee.unwrap() - 0.000_01).abs() < 1e-12);
+
+        // One input without a prevout: fee stays unknown.
+        tx.fee = None;
+        tx.vin[1].prevout = None;
+        tx.fill_fee_from_prevouts();
+        assert!(tx.fee.is_none());
+    }
+}
@@ -0,0 +1,332 @@
+//! Typed wrappers for the mempool RPCs: `getmempoolinfo`, `getrawmempool true`
+//! and `estimatesmartfee`, plus the fee-rate histogram built from the raw
+//! mempool.
+
+use std::{collections::HashMap, time::Duration};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{call, call_raw, parse_raw, RpcAuth};
+
+/// `getrawmempool true` can take a while on a full mempool.
+const RAW_MEMPOOL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Virtual size available to transactions in a block (4 M weight units
+/// minus Core's 4000 WU coinbase reservation).
+const BLOCK_VSIZE: u64 = 999_000;
+
+/// Lower edges of the histogram buckets in sat/vB.
+const BUCKET_EDGES: [f64; 20] = [
+    0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 8.0, 10.0, 12.0, 15.0, 20.0, 30.0, 40.0, 50.0, 75.0, 100.0,
+    150.0, 200.0, 500.0,
+];
+
+/// Convert a BTC/kvB rate (as the RPCs report it) to sat/vB.
+pub fn btc_per_kvb_to_sat_per_vb(rate: f64) -> f64 {
+    rate * 100_000.0
+}
+
+/// Parsed result of `getmempoolinfo`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MempoolInfo {
+    /// `false` while the mempool is still being loaded from `mempool.dat`.
+    #[serde(default = "default_true")]
+    pub loaded: bool,
+    /// Number of transactions.
+    pub size: u64,
+    /// Sum of virtual sizes.
+    pub bytes: u64,
+    /// Memory usage in bytes.
+    pub usage: u64,
+    /// `-maxmempool` in bytes.
+    pub maxmempool: u64,
+    /// Minimum fee rate for acceptance, BTC/kvB.
+    pub mempoolminfee: f64,
+    /// `-minrelaytxfee`, BTC/kvB.
+    pub minrelaytxfee: f64,
+    /// Sum of fees in BTC (Core 23+).
+    #[serde(default)]
+    pub total_fee: Option<f64>,
+}
+
+const fn default_true() -> bool {
+    true
+}
+
+/// Result of `estimatesmartfee` for one confirmation target.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeeEstimate {
+    /// Requested confirmation target.
+    #[serde(skip)]
+    pub target: u16,
+    /// BTC/kvB; absent when Core has too little data.
+    #[serde(default)]
+    pub feerate: Option<f64>,
+    /// Target the estimate was actually made for.
+    #[serde(default)]
+    pub blocks: u16,
+    #[serde(default)]
+    pub errors: Vec<String>,
+}
+
+/// Fee fields of a `getrawmempool true` entry, in BTC.
+#[derive(Debug, Clone, Deserialize)]
+struct EntryFees {
+    base: f64,
+    #[serde(default)]
+    ancestor: Option<f64>,
+}
+
+/// The parts of a `getrawmempool true` entry we use.
+#[derive(Debug, Clone, Deserialize)]
+struct MempoolEntry {
+    vsize: u64,
+    fees: EntryFees,
+    #[serde(default)]
+    ancestorsize: Option<u64>,
+}
+
+impl MempoolEntry {
+    fn fee_rate(&self) -> f64 {
+        sat_per_vb(self.fees.base, self.vsize)
+    }
+
+    /// Rate the block template sorts by: the lower of the transaction's own
+    /// rate and its ancestor package rate.
+    fn mining_score(&self) -> f64 {
+        let own = self.fee_rate();
+        match (self.fees.ancestor, self.ancestorsize) {
+            (Some(fee), Some(size)) => own.min(sat_per_vb(fee, size)),
+            _ => own,
+        }
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn sat_per_vb(fee_btc: f64, vsize: u64) -> f64 {
+    if vsize == 0 {
+        return 0.0;
+    }
+    fee_btc * 100_000_000.0 / vsize as f64
+}
+
+/// One histogram bar: transactions paying at least `min_rate` and less
+/// than `max_rate` sat/vB.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeBucket {
+    pub min_rate: f64,
+    /// `None` for the open-ended top bucket.
+    pub max_rate: Option<f64>,
+    pub count: u64,
+    pub vsize: u64,
+}
+
+/// Fee rates of the transactions that would fill the next block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NextBlock {
+    pub min_rate: f64,
+    pub median_rate: f64,
+    pub max_rate: f64,
+    pub tx_count: u64,
+    pub vsize: u64,
+}
+
+/// Everything derived from `getrawmempool true`.
+#[derive(Debug, Clone, Default)]
+pub struct MempoolStats {
+    pub histogram: Vec<FeeBucket>,
+    /// `None` when the mempool is empty.
+    pub next_block: Option<NextBlock>,
+}
+
+impl MempoolStats {
+    fn from_entries<'a>(entries: impl IntoIterator<Item = &'a MempoolEntry>) -> Self {
+        let mut histogram: Vec<FeeBucket> = BUCKET_EDGES
+            .iter()
+            .enumerate()
+            .map(|(i, &min_rate)| FeeBucket {
+                min_rate,
+                max_rate: BUCKET_EDGES.get(i + 1).copied(),
+                count: 0,
+                vsize: 0,
+            })
+            .collect();
+
+        let mut scored: Vec<(f64, u64)> = Vec::new();
+        for entry in entries {
+            let rate = entry.fee_rate();
+            let idx = BUCKET_EDGES.partition_point(|&edge| edge <= rate).max(1) - 1;
+            histogram[idx].count += 1;
+            histogram[idx].vsize += entry.vsize;
+            scored.push((entry.mining_score(), entry.vsize));
+        }
+
+        Self {
+            histogram,
+            next_block: project_next_block(scored),
+        }
+    }
+}
+
+/// Greedily fill one block with the highest-scoring transactions.  One that
+/// no longer fits is skipped, so smaller ones further down still fill the gap.
+fn project_next_block(mut scored: Vec<(f64, u64)>) -> Option<NextBlock> {
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut vsize = 0;
+    let mut block = Vec::new();
+    for &(rate, size) in &scored {
+        if vsize + size > BLOCK_VSIZE {
+            continue;
+        }
+        vsize += size;
+        block.push((rate, size));
+    }
+    let (max_rate, min_rate) = (block.first()?.0, block.last()?.0);
+
+    // Median by virtual size, not by transaction count.
+    let mut acc = 0;
+    let median_rate = block
+        .iter()
+        .find(|(_, size)| {
+            acc += size;
+            acc * 2 >= vsize
+        })
+        .map_or(min_rate, |(rate, _)| *rate);
+
+    Some(NextBlock {
+        min_rate,
+        median_rate,
+        max_rate,
+        tx_count: block.len() as u64,
+        vsize,
+    })
+}
+
+/// Call `getmempoolinfo`.
+pub async fn get_mempool_info(auth: &RpcAuth) -> Result<MempoolInfo> {
+    let v = call(auth, "getmempoolinfo", json!([])).await?;
+    serde_json::from_value(v).context("parse getmempoolinfo")
+}
+
+/// Call `estimatesmartfee` for `target` blocks.
+pub async fn estimate_smart_fee(auth: &RpcAuth, target: u16) -> Result<FeeEstimate> {
+    let v = call(auth, "estimatesmartfee", json!([target])).await?;
+    let mut estimate: FeeEstimate = serde_json::from_value(v).context("parse estimatesmartfee")?;
+    estimate.target = target;
+    Ok(estimate)
+}
+
+/// Fetch `getrawmempool true` and build the histogram and next-block
+/// projection.  Decoding runs on the blocking pool since the verbose
+/// mempool can be very large.
+pub async fn get_mempool_stats(auth: &RpcAuth) -> Result<MempoolStats> {
+    let body = call_raw(auth, "getrawmempool", json!([true]), RAW_MEMPOOL_TIMEOUT).await?;
+    tokio::task::spawn_blocking(move || {
+        let entries: HashMap<String, MempoolEntry> =
+            parse_raw(&body).context("parse getrawmempool")?;
+        Ok(MempoolStats::from_entries(entries.values()))
+    })
+    .await
+    .context("mempool analysis task")?
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(vsize: u64, sat: u64) -> MempoolEntry {
+        #[allow(clippy::cast_precision_loss)]
+        let base = sat as f64 / 100_000_000.0;
+        MempoolEntry {
+            vsize,
+            fees: EntryFees {
+                base,
+                ancestor: Some(base),
+            },
+            ancestorsize: Some(vsize),
+        }
+    }
+
+    #[test]
+    fn buckets_by_fee_rate() {
+        let entries = [
+            entry(100, 50),
+            entry(200, 400),
+            entry(100, 250),
+            entry(100, 90_000),
+        ];
+        let stats = MempoolStats::from_entries(&entries);
+        // 0.5 sat/vB → [0, 1)
+        assert_eq!(stats.histogram[0].count, 1);
+        // 2 sat/vB and 2.5 sat/vB → [2, 3)
+        assert_eq!(stats.histogram[2].count, 2);
+        assert_eq!(stats.histogram[2].vsize, 300);
+        // 900 sat/vB lands in the open-ended top bucket.
+        let top = stats.histogram.last().unwrap();
+        assert_eq!((top.count, top.max_rate), (1, None));
+    }
+
+    #[test]
+    fn next_block_uses_ancestor_score() {
+        // A 1 sat/vB parent whose 100 sat/vB child pays for it: the child's
+        // package rate is ~50 sat/vB, which bounds its score.
+        let parent = entry(100, 100);
+        let child = MempoolEntry {
+            vsize: 100,
+            fees: EntryFees {
+                base: 0.000_1,
+                ancestor: Some(0.000_101),
+            },
+            ancestorsize: Some(200),
+        };
+        assert!((child.mining_score() - 50.5).abs() < 1e-9);
+
+        let stats = MempoolStats::from_entries(&[parent, child, entry(400_000, 8_000_000)]);
+        let next = stats.next_block.unwrap();
+        assert_eq!(next.tx_count, 3);
+        assert!((next.max_rate - 50.5).abs() < 1e-9);
+        assert!((next.min_rate - 1.0).abs() < 1e-9);
+        assert!((next.median_rate - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn next_block_stops_at_block_size() {
+        let entries: Vec<MempoolEntry> = (1..=30).map(|i| entry(100_000, i * 100_000)).collect();
+        let next = MempoolStats::from_entries(&entries).next_block.unwrap();
+        assert_eq!(next.tx_count, 9);
+        assert!((next.min_rate - 22.0).abs() < 1e-9);
+        assert!(MempoolStats::from_entries(&[]).next_block.is_none());
+    }
+
+    #[test]
+    fn next_block_skips_what_does_not_fit() {
+        // 600 kvB at 10 sat/vB, then 500 kvB at 5 that no longer fits, then
+        // 1 kvB at 2 that still does.
+        let entries = [
+            entry(600_000, 6_000_000),
+            entry(500_000, 2_500_000),
+            entry(1_000, 2_000),
+        ];
+        let next = MempoolStats::from_entries(&entries).next_block.unwrap();
+        assert_eq!(next.tx_count, 2);
+        assert_eq!(next.vsize, 601_000);
+        assert!((next.min_rate - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn parses_raw_mempool_body() {
+        let body = br#"{"result":{"ab":{"vsize":141,"weight":561,"fees":{"base":0.00000282,
+            "modified":0.00000282,"ancestor":0.00000282,"descendant":0.00000282},
+            "ancestorsize":141}},"error":null,"id":"bnm"}"#;
+        let entries: HashMap<String, MempoolEntry> = parse_raw(body).unwrap();
+        assert!((entries["ab"].fee_rate() - 2.0).abs() < 1e-9);
+
+        let err = br#"{"result":null,"error":{"code":-28,"message":"Loading"},"id":"bnm"}"#;
+        assert!(parse_raw::<HashMap<String, MempoolEntry>>(err).is_err());
+    }
+}
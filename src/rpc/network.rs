@@ -0,0 +1,280 @@
+//! Typed wrappers for the P2P network RPCs: `getpeerinfo`, `getnetworkinfo`,
+//! `getnettotals`, `disconnectnode` and `setban`.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{call, call_void, RpcAuth};
+
+/// Network a peer is connected over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PeerNetwork {
+    Ipv4,
+    Ipv6,
+    Onion,
+    I2p,
+    Cjdns,
+    /// Local / private addresses (`not_publicly_routable`).
+    Local,
+}
+
+impl PeerNetwork {
+    /// Parse the `network` field reported by Core 22+.
+    fn from_rpc(s: &str) -> Option<Self> {
+        match s {
+            "ipv4" => Some(Self::Ipv4),
+            "ipv6" => Some(Self::Ipv6),
+            "onion" => Some(Self::Onion),
+            "i2p" => Some(Self::I2p),
+            "cjdns" => Some(Self::Cjdns),
+            "not_publicly_routable" => Some(Self::Local),
+            _ => None,
+        }
+    }
+
+    /// Guess the network from the address (older Core without `network`).
+    fn from_addr(addr: &str) -> Self {
+        let host = addr
+            .rsplit_once(':')
+            .map_or(addr, |(h, _)| h)
+            .to_ascii_lowercase();
+        if host.ends_with(".onion") {
+            Self::Onion
+        } else if host.ends_with(".i2p") {
+            Self::I2p
+        } else if host.starts_with("[fc") {
+            Self::Cjdns
+        } else if host.starts_with('[') {
+            Self::Ipv6
+        } else {
+            Self::Ipv4
+        }
+    }
+
+    /// `setban` only accepts IP addresses and subnets.
+    pub const fn is_bannable(self) -> bool {
+        matches!(self, Self::Ipv4 | Self::Ipv6 | Self::Cjdns | Self::Local)
+    }
+}
+
+impl std::fmt::Display for PeerNetwork {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Ipv4 => "ipv4",
+            Self::Ipv6 => "ipv6",
+            Self::Onion => "onion",
+            Self::I2p => "i2p",
+            Self::Cjdns => "cjdns",
+            Self::Local => "local",
+        })
+    }
+}
+
+/// One entry of `getpeerinfo`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PeerInfo {
+    pub id: u64,
+    pub addr: String,
+    #[serde(default, rename = "network")]
+    network_raw: String,
+    #[serde(default)]
+    pub inbound: bool,
+    /// `outbound-full-relay`, `block-relay-only`, `inbound`, `manual`, …
+    #[serde(default)]
+    pub connection_type: String,
+    #[serde(default)]
+    pub version: u64,
+    #[serde(default)]
+    pub subver: String,
+    /// Last ping round-trip in seconds; absent until the first pong.
+    #[serde(default)]
+    pub pingtime: Option<f64>,
+    #[serde(default)]
+    pub bytessent: u64,
+    #[serde(default)]
+    pub bytesrecv: u64,
+    /// `-1` until the peer has announced anything.
+    #[serde(default)]
+    pub synced_headers: i64,
+    #[serde(default)]
+    pub synced_blocks: i64,
+    /// Unix time the connection was established.
+    #[serde(default)]
+    pub conntime: u64,
+}
+
+impl PeerInfo {
+    pub fn network(&self) -> PeerNetwork {
+        PeerNetwork::from_rpc(&self.network_raw)
+            .unwrap_or_else(|| PeerNetwork::from_addr(&self.addr))
+    }
+
+    /// Address without the port, as `setban` expects it.
+    pub fn host(&self) -> &str {
+        let addr = self.addr.as_str();
+        if let Some(rest) = addr.strip_prefix('[') {
+            return rest.split_once(']').map_or(rest, |(h, _)| h);
+        }
+        match addr.rsplit_once(':') {
+            // A bare IPv6 address without brackets has several colons.
+            Some((h, _)) if !h.contains(':') => h,
+            _ => addr,
+        }
+    }
+}
+
+/// Per-network reachability from `getnetworkinfo`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NetworkReachability {
+    pub name: String,
+    #[serde(default)]
+    pub reachable: bool,
+    #[serde(default)]
+    pub proxy: String,
+}
+
+/// Address this node advertises, from `getnetworkinfo`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LocalAddress {
+    pub address: String,
+    pub port: u16,
+}
+
+/// Parsed result of `getnetworkinfo`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NetworkInfo {
+    pub version: u64,
+    pub subversion: String,
+    #[serde(default)]
+    pub connections: u64,
+    #[serde(default)]
+    pub connections_in: u64,
+    #[serde(default)]
+    pub connections_out: u64,
+    #[serde(default = "default_true")]
+    pub networkactive: bool,
+    #[serde(default)]
+    pub networks: Vec<NetworkReachability>,
+    /// Minimum relay fee in BTC/kvB.
+    #[serde(default)]
+    pub relayfee: f64,
+    #[serde(default)]
+    pub localaddresses: Vec<LocalAddress>,
+    /// A string before Core 28, an array of strings since.
+    #[serde(default)]
+    warnings: Value,
+}
+
+impl NetworkInfo {
+    pub fn warnings(&self) -> Vec<String> {
+        match &self.warnings {
+            Value::String(s) if !s.is_empty() => vec![s.clone()],
+            Value::Array(items) => items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_owned)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+const fn default_true() -> bool {
+    true
+}
+
+/// Parsed result of `getnettotals`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NetTotals {
+    pub totalbytesrecv: u64,
+    pub totalbytessent: u64,
+}
+
+/// Call `getpeerinfo`.
+pub async fn get_peer_info(auth: &RpcAuth) -> Result<Vec<PeerInfo>> {
+    let v = call(auth, "getpeerinfo", json!([])).await?;
+    serde_json::from_value(v).context("parse getpeerinfo")
+}
+
+/// Call `getnetworkinfo`.
+pub async fn get_network_info(auth: &RpcAuth) -> Result<NetworkInfo> {
+    let v = call(auth, "getnetworkinfo", json!([])).await?;
+    serde_json::from_value(v).context("parse getnetworkinfo")
+}
+
+/// Call `getnettotals`.
+pub async fn get_net_totals(auth: &RpcAuth) -> Result<NetTotals> {
+    let v = call(auth, "getnettotals", json!([])).await?;
+    serde_json::from_value(v).context("parse getnettotals")
+}
+
+/// Disconnect a peer by its `getpeerinfo` id.
+pub async fn disconnect_node(auth: &RpcAuth, peer_id: u64) -> Result<()> {
+    // Positional form is `disconnectnode "address" nodeid`; an empty address
+    // selects by id.
+    call_void(auth, "disconnectnode", json!(["", peer_id])).await
+}
+
+/// Ban an IP address (or subnet) for `seconds`.
+pub async fn ban_peer(auth: &RpcAuth, host: &str, seconds: u64) -> Result<()> {
+    call_void(auth, "setban", json!([host, "add", seconds])).await
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_peer_info() {
+        let v = json!([
+            {
+                "id": 7, "addr": "203.0.113.5:8333", "network": "ipv4", "inbound": false,
+                "connection_type": "outbound-full-relay", "version": 70_016,
+                "subver": "/Satoshi:27.0.0/", "pingtime": 0.042, "bytessent": 1200,
+                "bytesrecv": 98000, "synced_headers": 850_000, "synced_blocks": 850_000,
+                "conntime": 1_700_000_000
+            },
+            {
+                "id": 9, "addr": "[2001:db8::1]:8333", "inbound": true,
+                "connection_type": "inbound", "version": 70_016, "subver": "/Satoshi:26.0.0/",
+                "bytessent": 0, "bytesrecv": 0, "synced_headers": -1, "synced_blocks": -1
+            },
+            { "id": 11, "addr": "abcdefghijklmnop.onion:8333", "network": "onion" }
+        ]);
+        let peers: Vec<PeerInfo> = serde_json::from_value(v).unwrap();
+        assert_eq!(peers.len(), 3);
+        assert_eq!(peers[0].network(), PeerNetwork::Ipv4);
+        assert_eq!(peers[0].host(), "203.0.113.5");
+        assert_eq!(peers[0].pingtime, Some(0.042));
+        // No `network` field (pre-22 Core): derived from the address.
+        assert_eq!(peers[1].network(), PeerNetwork::Ipv6);
+        assert_eq!(peers[1].host(), "2001:db8::1");
+        assert!(peers[1].pingtime.is_none());
+        assert_eq!(peers[2].network(), PeerNetwork::Onion);
+        assert!(!peers[2].network().is_bannable());
+    }
+
+    #[test]
+    fn parses_network_info_warnings() {
+        let old = json!({
+            "version": 260_000, "subversion": "/Satoshi:26.0.0/", "connections": 10,
+            "relayfee": 0.00001, "warnings": ""
+        });
+        let info: NetworkInfo = serde_json::from_value(old).unwrap();
+        assert!(info.warnings().is_empty());
+        assert!(info.networkactive);
+
+        let new = json!({
+            "version": 280_000, "subversion": "/Satoshi:28.0.0/", "connections": 10,
+            "connections_in": 2, "connections_out": 8, "relayfee": 0.000001,
+            "networks": [{ "name": "onion", "reachable": false, "proxy": "" }],
+            "warnings": ["This is a pre-release test build"]
+        });
+        let info: NetworkInfo = serde_json::from_value(new).unwrap();
+        assert_eq!(info.warnings(), vec!["This is a pre-release test build"]);
+        assert_eq!(info.networks[0].name, "onion");
+    }
+}
@@ -0,0 +1,199 @@
+//! Typed wrappers for the PSBT RPCs: `decodepsbt`, `analyzepsbt`,
+//! `combinepsbt` and `finalizepsbt`.  PSBTs are passed as base64.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{
+    blockchain::{ScriptPubKey, Transaction},
+    call, RpcAuth,
+};
+
+/// Output being spent, as carried in a PSBT input.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WitnessUtxo {
+    /// BTC.
+    pub amount: f64,
+    #[serde(rename = "scriptPubKey")]
+    pub script_pub_key: ScriptPubKey,
+}
+
+/// One input of `decodepsbt`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PsbtInput {
+    #[serde(default)]
+    pub witness_utxo: Option<WitnessUtxo>,
+    /// Pubkey → signature.
+    #[serde(default)]
+    pub partial_signatures: HashMap<String, String>,
+    #[serde(default, rename = "final_scriptSig")]
+    pub final_script_sig: Option<serde_json::Value>,
+    #[serde(default)]
+    pub final_scriptwitness: Option<Vec<String>>,
+}
+
+impl PsbtInput {
+    pub fn is_finalized(&self) -> bool {
+        self.final_script_sig.is_some() || self.final_scriptwitness.is_some()
+    }
+}
+
+/// Parsed result of `decodepsbt`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DecodedPsbt {
+    /// The unsigned transaction.
+    pub tx: Transaction,
+    pub inputs: Vec<PsbtInput>,
+    /// BTC, when every input's UTXO is known.
+    #[serde(default)]
+    pub fee: Option<f64>,
+}
+
+/// What an input still lacks (`analyzepsbt`).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MissingData {
+    /// Key ids of pubkeys whose BIP 32 derivation path is missing.
+    #[serde(default)]
+    pub pubkeys: Vec<String>,
+    /// Key ids of pubkeys whose signature is missing.
+    #[serde(default)]
+    pub signatures: Vec<String>,
+    #[serde(default)]
+    pub redeemscript: Option<String>,
+    #[serde(default)]
+    pub witnessscript: Option<String>,
+}
+
+impl MissingData {
+    /// Short description of what is missing, e.g. `2 signatures`.
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+        if !self.signatures.is_empty() {
+            let n = self.signatures.len();
+            parts.push(format!("{n} signature{}", plural(n)));
+        }
+        if !self.pubkeys.is_empty() {
+            let n = self.pubkeys.len();
+            parts.push(format!("{n} key path{}", plural(n)));
+        }
+        if self.redeemscript.is_some() {
+            parts.push("redeem script".to_owned());
+        }
+        if self.witnessscript.is_some() {
+            parts.push("witness script".to_owned());
+        }
+        parts.join(", ")
+    }
+}
+
+/// One input of `analyzepsbt`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct InputAnalysis {
+    pub has_utxo: bool,
+    pub is_final: bool,
+    #[serde(default)]
+    pub missing: Option<MissingData>,
+    /// Role that should act on this input next.
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+/// Parsed result of `analyzepsbt`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PsbtAnalysis {
+    #[serde(default)]
+    pub inputs: Vec<InputAnalysis>,
+    #[serde(default)]
+    pub estimated_vsize: Option<u64>,
+    /// BTC/kvB.
+    #[serde(default)]
+    pub estimated_feerate: Option<f64>,
+    /// BTC.
+    #[serde(default)]
+    pub fee: Option<f64>,
+    /// Next role: `creator`, `updater`, `signer`, `finalizer` or `extractor`.
+    pub next: String,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Parsed result of `finalizepsbt`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FinalizedPsbt {
+    /// Network-serialised transaction, when complete.
+    #[serde(default)]
+    pub hex: Option<String>,
+    pub complete: bool,
+}
+
+/// Call `decodepsbt`.
+pub async fn decode_psbt(auth: &RpcAuth, psbt: &str) -> Result<DecodedPsbt> {
+    let v = call(auth, "decodepsbt", json!([psbt])).await?;
+    serde_json::from_value(v).context("parse decodepsbt")
+}
+
+/// Call `analyzepsbt`.
+pub async fn analyze_psbt(auth: &RpcAuth, psbt: &str) -> Result<PsbtAnalysis> {
+    let v = call(auth, "analyzepsbt", json!([psbt])).await?;
+    serde_json::from_value(v).context("parse analyzepsbt")
+}
+
+/// Call `combinepsbt`: merge signatures and data from PSBTs for the same
+/// transaction.
+pub async fn combine_psbt(auth: &RpcAuth, psbts: &[String]) -> Result<String> {
+    let v = call(auth, "combinepsbt", json!([psbts])).await?;
+    serde_json::from_value(v).context("parse combinepsbt")
+}
+
+/// Call `finalizepsbt`, extracting the transaction when complete.
+pub async fn finalize_psbt(auth: &RpcAuth, psbt: &str) -> Result<FinalizedPsbt> {
+    let v = call(auth, "finalizepsbt", json!([psbt, true])).await?;
+    serde_json::from_value(v).context("parse finalizepsbt")
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_decode_and_analysis() {
+        let decoded: DecodedPsbt = serde_json::from_value(json!({
+            "tx": {
+                "txid": "aa", "hash": "aa", "version": 2, "size": 94, "vsize": 94, "weight": 376,
+                "locktime": 0,
+                "vin": [{ "txid": "p0", "vout": 0, "sequence": 4_294_967_293_u64 }],
+                "vout": [{ "value": 0.009_9, "n": 0, "scriptPubKey": { "type": "witness_v0_keyhash", "address": "bc1qdest" } }]
+            },
+            "global_xpubs": [], "psbt_version": 0, "proprietary": [], "unknown": {},
+            "inputs": [{
+                "witness_utxo": { "amount": 0.01, "scriptPubKey": { "type": "witness_v0_keyhash", "address": "bc1qsrc" } },
+                "partial_signatures": { "02ab": "3044" }
+            }],
+            "outputs": [{}],
+            "fee": 0.000_1
+        }))
+        .unwrap();
+        assert_eq!(decoded.inputs[0].partial_signatures.len(), 1);
+        assert!(!decoded.inputs[0].is_finalized());
+        assert!(decoded.tx.signals_rbf());
+
+        let analysis: PsbtAnalysis = serde_json::from_value(json!({
+            "inputs": [{ "has_utxo": true, "is_final": false, "next": "signer",
+                         "missing": { "signatures": ["deadbeef"] } }],
+            "estimated_vsize": 110, "estimated_feerate": 0.000_909_09, "fee": 0.000_1,
+            "next": "signer"
+        }))
+        .unwrap();
+        assert_eq!(analysis.next, "signer");
+        assert_eq!(
+            analysis.inputs[0].missing.as_ref().unwrap().describe(),
+            "1 signature"
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! Typed wrappers for raw transaction submission: `decoderawtransaction`,
+//! `testmempoolaccept` and `sendrawtransaction`.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{call, RpcAuth, Transaction};
+
+/// Convert a sat/vB rate to BTC/kvB (the unit `maxfeerate` takes).
+pub fn sat_per_vb_to_btc_per_kvb(rate: f64) -> f64 {
+    rate / 100_000.0
+}
+
+/// Fees reported by `testmempoolaccept` for an accepted transaction.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AcceptFees {
+    /// BTC.
+    pub base: f64,
+    /// BTC/kvB, including any in-mempool package (Core 25+).
+    #[serde(default, rename = "effective-feerate")]
+    pub effective_feerate: Option<f64>,
+}
+
+/// One entry of `testmempoolaccept`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MempoolAccept {
+    pub allowed: bool,
+    #[serde(default)]
+    pub vsize: Option<u64>,
+    #[serde(default)]
+    pub fees: Option<AcceptFees>,
+    #[serde(default, rename = "reject-reason")]
+    pub reject_reason: Option<String>,
+}
+
+impl MempoolAccept {
+    /// Fee rate in sat/vB, when accepted.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn fee_rate(&self) -> Option<f64> {
+        let fees = self.fees.as_ref()?;
+        if let Some(rate) = fees.effective_feerate {
+            return Some(super::btc_per_kvb_to_sat_per_vb(rate));
+        }
+        let vsize = self.vsize.filter(|&v| v > 0)?;
+        Some(fees.base * 100_000_000.0 / vsize as f64)
+    }
+}
+
+/// Call `decoderawtransaction`.
+pub async fn decode_raw_transaction(auth: &RpcAuth, hex: &str) -> Result<Transaction> {
+    let v = call(auth, "decoderawtransaction", json!([hex])).await?;
+    serde_json::from_value(v).context("parse decoderawtransaction")
+}
+
+/// Call `testmempoolaccept` for a single transaction, rejecting fee rates
+/// above `max_fee_rate` sat/vB.
+pub async fn test_mempool_accept(
+    auth: &RpcAuth,
+    hex: &str,
+    max_fee_rate: f64,
+) -> Result<MempoolAccept> {
+    let v = call(
+        auth,
+        "testmempoolaccept",
+        json!([[hex], sat_per_vb_to_btc_per_kvb(max_fee_rate)]),
+    )
+    .await?;
+    let mut results: Vec<MempoolAccept> =
+        serde_json::from_value(v).context("parse testmempoolaccept")?;
+    if results.len() != 1 {
+        bail!("testmempoolaccept returned {} results", results.len());
+    }
+    Ok(results.remove(0))
+}
+
+/// Call `sendrawtransaction`; returns the txid.
+pub async fn send_raw_transaction(auth: &RpcAuth, hex: &str, max_fee_rate: f64) -> Result<String> {
+    let v = call(
+        auth,
+        "sendrawtransaction",
+        json!([hex, sat_per_vb_to_btc_per_kvb(max_fee_rate)]),
+    )
+    .await?;
+    serde_json::from_value(v).context("parse sendrawtransaction")
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_accept_and_reject() {
+        let ok: Vec<MempoolAccept> = serde_json::from_value(json!([{
+            "txid": "aa", "wtxid": "bb", "allowed": true, "vsize": 141,
+            "fees": { "base": 0.000_014_1, "effective-feerate": 0.000_1, "effective-includes": ["bb"] }
+        }]))
+        .unwrap();
+        assert!(ok[0].allowed);
+        assert!((ok[0].fee_rate().unwrap() - 10.0).abs() < 1e-9);
+
+        let rejected: MempoolAccept = serde_json::from_value(json!({
+            "txid": "aa", "wtxid": "bb", "allowed": false,
+            "reject-reason": "max-fee-exceeded"
+        }))
+        .unwrap();
+        assert_eq!(rejected.reject_reason.as_deref(), Some("max-fee-exceeded"));
+        assert!(rejected.fee_rate().is_none());
+    }
+
+    #[test]
+    fn converts_fee_rate_units() {
+        assert!((sat_per_vb_to_btc_per_kvb(10_000.0) - 0.1).abs() < 1e-12);
+    }
+}
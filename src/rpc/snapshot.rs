@@ -0,0 +1,168 @@
+//! Typed wrappers for `AssumeUTXO`: `loadtxoutset`, `dumptxoutset` and
+//! `getchainstates`.
+//!
+//! bitcoind only loads snapshots whose base block is one of the heights
+//! compiled into it, so a snapshot meant for other nodes is dumped with
+//! `rollback`, which rewinds to the latest such height, writes the set and
+//! rolls forward again.
+
+use std::{fmt, path::Path, time::Duration};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::{call, call_at, RpcAuth};
+
+/// Loading or writing the UTXO set takes minutes; a rollback dump can take
+/// much longer.
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(6 * 3600);
+
+/// Parsed result of `loadtxoutset`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LoadedSnapshot {
+    pub coins_loaded: u64,
+    pub tip_hash: String,
+    pub base_height: u64,
+}
+
+/// Parsed result of `dumptxoutset`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DumpedSnapshot {
+    pub coins_written: u64,
+    pub base_hash: String,
+    pub base_height: u64,
+    pub txoutset_hash: String,
+    #[serde(rename = "nchaintx")]
+    pub n_chain_tx: u64,
+}
+
+/// One chainstate in `getchainstates`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Chainstate {
+    pub blocks: u64,
+    /// Base block of the snapshot this chainstate was loaded from.
+    #[serde(default)]
+    pub snapshot_blockhash: Option<String>,
+    /// `false` for a snapshot chainstate until background validation has
+    /// reached its base block.
+    pub validated: bool,
+}
+
+/// Parsed result of `getchainstates`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Chainstates {
+    pub headers: u64,
+    /// The fully validated chainstate first, then the snapshot one while
+    /// both exist.
+    pub chainstates: Vec<Chainstate>,
+}
+
+impl Chainstates {
+    /// The snapshot chainstate still awaiting background validation.
+    pub fn snapshot(&self) -> Option<&Chainstate> {
+        self.chainstates.iter().find(|c| !c.validated)
+    }
+
+    /// The chainstate validating from genesis behind a snapshot.
+    pub fn background(&self) -> Option<&Chainstate> {
+        self.snapshot()?;
+        self.chainstates.iter().find(|c| c.validated)
+    }
+}
+
+/// How `dumptxoutset` picks the snapshot height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpType {
+    /// The current tip.  Only this node's own records can vouch for it.
+    Latest,
+    /// The latest height bitcoind accepts snapshots for.
+    Rollback,
+}
+
+impl DumpType {
+    pub const ALL: [DumpType; 2] = [DumpType::Rollback, DumpType::Latest];
+
+    fn param(self) -> &'static str {
+        match self {
+            Self::Latest => "latest",
+            Self::Rollback => "rollback",
+        }
+    }
+}
+
+impl fmt::Display for DumpType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Latest => "Current tip",
+            Self::Rollback => "Latest loadable height (rollback)",
+        })
+    }
+}
+
+/// Call `getchainstates`.
+pub async fn get_chainstates(auth: &RpcAuth) -> Result<Chainstates> {
+    let v = call(auth, "getchainstates", json!([])).await?;
+    serde_json::from_value(v).context("parse getchainstates")
+}
+
+/// Call `loadtxoutset` on the snapshot at `path`.
+pub async fn load_txoutset(auth: &RpcAuth, path: &Path) -> Result<LoadedSnapshot> {
+    let v = call_at(
+        auth,
+        None,
+        "loadtxoutset",
+        json!([path.to_string_lossy()]),
+        SNAPSHOT_TIMEOUT,
+    )
+    .await?;
+    serde_json::from_value(v).context("parse loadtxoutset")
+}
+
+/// Call `dumptxoutset`, writing to `path` (which must not exist).
+pub async fn dump_txoutset(auth: &RpcAuth, path: &Path, kind: DumpType) -> Result<DumpedSnapshot> {
+    let v = call_at(
+        auth,
+        None,
+        "dumptxoutset",
+        json!([path.to_string_lossy(), kind.param()]),
+        SNAPSHOT_TIMEOUT,
+    )
+    .await?;
+    serde_json::from_value(v).context("parse dumptxoutset")
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_snapshot_and_background_chainstates() {
+        let v = json!({
+            "headers": 905_000,
+            "chainstates": [
+                { "blocks": 120_000, "bestblockhash": "aa", "difficulty": 1.0,
+                  "verificationprogress": 0.01, "coins_db_cache_bytes": 1,
+                  "coins_tip_cache_bytes": 1, "validated": true },
+                { "blocks": 904_000, "bestblockhash": "bb", "difficulty": 1.0,
+                  "verificationprogress": 0.99, "coins_db_cache_bytes": 1,
+                  "coins_tip_cache_bytes": 1, "snapshot_blockhash": "cc",
+                  "validated": false }
+            ]
+        });
+        let states: Chainstates = serde_json::from_value(v).unwrap();
+        assert_eq!(states.snapshot().unwrap().blocks, 904_000);
+        assert_eq!(states.background().unwrap().blocks, 120_000);
+
+        let single: Chainstates = serde_json::from_value(json!({
+            "headers": 10,
+            "chainstates": [{ "blocks": 10, "bestblockhash": "aa",
+                              "verificationprogress": 1.0, "validated": true }]
+        }))
+        .unwrap();
+        assert!(single.snapshot().is_none());
+        assert!(single.background().is_none());
+    }
+}
@@ -0,0 +1,67 @@
+//! Typed wrappers for the address and descriptor utility RPCs:
+//! `validateaddress`, `getdescriptorinfo` and `deriveaddresses`.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{call, RpcAuth};
+
+/// Parsed result of `validateaddress`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AddressInfo {
+    pub isvalid: bool,
+    #[serde(default)]
+    pub address: String,
+    /// Hex-encoded output script.
+    #[serde(default, rename = "scriptPubKey")]
+    pub script_pub_key: String,
+    /// Why the address is invalid (Core 22+).
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Parsed result of `getdescriptorinfo`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DescriptorInfo {
+    /// Canonical descriptor including its checksum.
+    pub descriptor: String,
+    pub isrange: bool,
+}
+
+/// Call `validateaddress`; an invalid address is an error.
+pub async fn validate_address(auth: &RpcAuth, address: &str) -> Result<AddressInfo> {
+    let v = call(auth, "validateaddress", json!([address])).await?;
+    let info: AddressInfo = serde_json::from_value(v).context("parse validateaddress")?;
+    if !info.isvalid {
+        bail!(
+            "invalid address {address}{}",
+            info.error
+                .as_deref()
+                .map(|e| format!(": {e}"))
+                .unwrap_or_default()
+        );
+    }
+    Ok(info)
+}
+
+/// Call `getdescriptorinfo`.
+pub async fn get_descriptor_info(auth: &RpcAuth, descriptor: &str) -> Result<DescriptorInfo> {
+    let v = call(auth, "getdescriptorinfo", json!([descriptor])).await?;
+    serde_json::from_value(v).context("parse getdescriptorinfo")
+}
+
+/// Call `deriveaddresses`.  `range` is required (and only allowed) for
+/// ranged descriptors; both ends are inclusive.
+pub async fn derive_addresses(
+    auth: &RpcAuth,
+    descriptor: &str,
+    range: Option<(u32, u32)>,
+) -> Result<Vec<String>> {
+    let params = match range {
+        Some((start, end)) => json!([descriptor, [start, end]]),
+        None => json!([descriptor]),
+    };
+    let v = call(auth, "deriveaddresses", params).await?;
+    serde_json::from_value(v).context("parse deriveaddresses")
+}
@@ -0,0 +1,265 @@
+//! Typed wrappers for wallet management: `listwallets`, `listwalletdir`,
+//! `createwallet`, `loadwallet`, `unloadwallet`, `getwalletinfo`,
+//! `getbalances`, `backupwallet` and `rescanblockchain`.
+//!
+//! Per-wallet calls go to the `/wallet/<name>` endpoint so they work with
+//! several wallets loaded.
+
+use std::{path::Path, time::Duration};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{call, call_at, call_void_at, RpcAuth};
+
+/// Creating, loading (which may rescan) and backing up wallets can take far
+/// longer than a polling call.
+const WALLET_OP_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// A rescan from genesis reads every block on disk.
+const RESCAN_TIMEOUT: Duration = Duration::from_secs(24 * 3600);
+
+/// Options for `createwallet`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreateWallet {
+    pub name: String,
+    /// Descriptor wallet (the only kind Core 26+ can create).
+    pub descriptors: bool,
+    /// Start without keys or descriptors.
+    pub blank: bool,
+    /// Watch-only: never holds private keys.
+    pub disable_private_keys: bool,
+    /// Encrypt with this passphrase; empty for none.
+    pub passphrase: String,
+}
+
+impl Default for CreateWallet {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            descriptors: true,
+            blank: false,
+            disable_private_keys: false,
+            passphrase: String::new(),
+        }
+    }
+}
+
+impl CreateWallet {
+    /// Positional `createwallet` parameters.
+    fn params(&self) -> Value {
+        json!([
+            self.name,
+            self.disable_private_keys,
+            self.blank,
+            self.passphrase,
+            false, // avoid_reuse
+            self.descriptors,
+        ])
+    }
+}
+
+/// Parsed result of `getwalletinfo`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WalletInfo {
+    pub walletname: String,
+    #[serde(default)]
+    pub format: String,
+    pub txcount: u64,
+    #[serde(default)]
+    pub keypoolsize: u64,
+    /// Present only for encrypted wallets; `0` while locked.
+    #[serde(default)]
+    pub unlocked_until: Option<u64>,
+    #[serde(default = "default_true")]
+    pub private_keys_enabled: bool,
+    #[serde(default)]
+    pub descriptors: bool,
+    /// `false`, or `{ duration, progress }` while rescanning.
+    #[serde(default)]
+    pub scanning: Value,
+}
+
+const fn default_true() -> bool {
+    true
+}
+
+impl WalletInfo {
+    /// Rescan progress (0–1) while the wallet is scanning.
+    pub fn scan_progress(&self) -> Option<f64> {
+        self.scanning.get("progress").and_then(Value::as_f64)
+    }
+}
+
+/// One group of balances in `getbalances`, in BTC.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct BalanceSet {
+    pub trusted: f64,
+    pub untrusted_pending: f64,
+    pub immature: f64,
+}
+
+/// Parsed result of `getbalances`.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Balances {
+    pub mine: BalanceSet,
+    /// Only for legacy wallets holding watch-only addresses.
+    #[serde(default)]
+    pub watchonly: Option<BalanceSet>,
+}
+
+/// Call `listwallets`: names of the loaded wallets.
+pub async fn list_wallets(auth: &RpcAuth) -> Result<Vec<String>> {
+    let v = call(auth, "listwallets", json!([])).await?;
+    serde_json::from_value(v).context("parse listwallets")
+}
+
+/// Call `listwalletdir`: names of every wallet in the wallets directory.
+pub async fn list_wallet_dir(auth: &RpcAuth) -> Result<Vec<String>> {
+    #[derive(Deserialize)]
+    struct Entry {
+        name: String,
+    }
+    #[derive(Deserialize)]
+    struct Dir {
+        wallets: Vec<Entry>,
+    }
+    let v = call(auth, "listwalletdir", json!([])).await?;
+    let dir: Dir = serde_json::from_value(v).context("parse listwalletdir")?;
+    Ok(dir.wallets.into_iter().map(|w| w.name).collect())
+}
+
+/// Call `createwallet`; returns any warnings.
+pub async fn create_wallet(auth: &RpcAuth, opts: &CreateWallet) -> Result<Vec<String>> {
+    let v = call_at(auth, None, "createwallet", opts.params(), WALLET_OP_TIMEOUT).await?;
+    Ok(warnings(&v))
+}
+
+/// Call `loadwallet`; returns any warnings.
+pub async fn load_wallet(auth: &RpcAuth, name: &str) -> Result<Vec<String>> {
+    let v = call_at(auth, None, "loadwallet", json!([name]), WALLET_OP_TIMEOUT).await?;
+    Ok(warnings(&v))
+}
+
+/// Call `unloadwallet` (older nodes answer `null`).
+pub async fn unload_wallet(auth: &RpcAuth, name: &str) -> Result<()> {
+    call_void_at(auth, None, "unloadwallet", json!([name]), WALLET_OP_TIMEOUT).await
+}
+
+/// Call `getwalletinfo` on `wallet`.
+pub async fn get_wallet_info(auth: &RpcAuth, wallet: &str) -> Result<WalletInfo> {
+    let v = call_at(
+        auth,
+        Some(wallet),
+        "getwalletinfo",
+        json!([]),
+        Duration::from_secs(5),
+    )
+    .await?;
+    serde_json::from_value(v).context("parse getwalletinfo")
+}
+
+/// Call `getbalances` on `wallet`.
+pub async fn get_balances(auth: &RpcAuth, wallet: &str) -> Result<Balances> {
+    let v = call_at(
+        auth,
+        Some(wallet),
+        "getbalances",
+        json!([]),
+        Duration::from_secs(5),
+    )
+    .await?;
+    serde_json::from_value(v).context("parse getbalances")
+}
+
+/// Call `backupwallet`, writing `wallet` to `destination` on the node's
+/// filesystem.
+pub async fn backup_wallet(auth: &RpcAuth, wallet: &str, destination: &Path) -> Result<()> {
+    let dest = destination.to_string_lossy();
+    call_void_at(
+        auth,
+        Some(wallet),
+        "backupwallet",
+        json!([dest]),
+        WALLET_OP_TIMEOUT,
+    )
+    .await
+}
+
+/// Call `rescanblockchain` on `wallet` from `start_height`; returns the
+/// `(start, stop)` heights actually scanned.
+pub async fn rescan_blockchain(
+    auth: &RpcAuth,
+    wallet: &str,
+    start_height: u64,
+) -> Result<(u64, u64)> {
+    #[derive(Deserialize)]
+    struct Scanned {
+        start_height: u64,
+        stop_height: u64,
+    }
+    let v = call_at(
+        auth,
+        Some(wallet),
+        "rescanblockchain",
+        json!([start_height]),
+        RESCAN_TIMEOUT,
+    )
+    .await?;
+    let scanned: Scanned = serde_json::from_value(v).context("parse rescanblockchain")?;
+    Ok((scanned.start_height, scanned.stop_height))
+}
+
+/// `warning` (string, up to Core 24) or `warnings` (array, Core 25+).
+fn warnings(v: &Value) -> Vec<String> {
+    let mut out: Vec<String> = v["warnings"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(str::to_owned)
+        .collect();
+    if let Some(w) = v["warning"].as_str().filter(|w| !w.is_empty()) {
+        out.push(w.to_owned());
+    }
+    out
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn createwallet_params_are_positional() {
+        let opts = CreateWallet {
+            name: "cold".to_owned(),
+            disable_private_keys: true,
+            ..CreateWallet::default()
+        };
+        assert_eq!(opts.params(), json!(["cold", true, false, "", false, true]));
+    }
+
+    #[test]
+    fn parses_wallet_info_and_warnings() {
+        let info: WalletInfo = serde_json::from_value(json!({
+            "walletname": "", "walletversion": 169_900, "format": "sqlite", "txcount": 4,
+            "keypoolsize": 3000, "private_keys_enabled": true, "descriptors": true,
+            "scanning": { "duration": 12, "progress": 0.25 }
+        }))
+        .unwrap();
+        assert_eq!(info.scan_progress(), Some(0.25));
+        assert!(info.unlocked_until.is_none());
+
+        assert_eq!(
+            warnings(&json!({ "name": "w", "warnings": ["a", "b"] })),
+            vec!["a", "b"]
+        );
+        assert_eq!(
+            warnings(&json!({ "name": "w", "warning": "" })),
+            Vec::<String>::new()
+        );
+    }
+}
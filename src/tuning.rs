@@ -0,0 +1,225 @@
+//! Resource presets for bitcoind: `dbcache`, `maxmempool`, `par`, `prune`
+//! and `blocksonly` computed from this machine's RAM, cores and free space
+//! on the data volume.
+//!
+//! The result is passed as launch arguments, which take precedence over
+//! `bitcoin.conf`, so switching presets never rewrites the user's file and
+//! the values in use are visible in the terminal's `$ bitcoind …` line.
+
+use std::{fmt, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{metrics, resources};
+
+const MIB: u64 = 1 << 20;
+
+/// Core's own `dbcache` default, in MiB.
+const DEFAULT_DBCACHE: u64 = 450;
+
+/// Smallest prune target bitcoind accepts, in MiB.
+pub const MIN_PRUNE_MIB: u64 = 550;
+
+/// Script verification threads bitcoind will use at most.
+const MAX_PAR: u64 = 15;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Preset {
+    /// No overrides: whatever bitcoin.conf and Core's defaults say.
+    #[default]
+    CoreDefaults,
+    InitialSync,
+    LowMemory,
+    PrunedLaptop,
+    Archival,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 5] = [
+        Preset::CoreDefaults,
+        Preset::InitialSync,
+        Preset::LowMemory,
+        Preset::PrunedLaptop,
+        Preset::Archival,
+    ];
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::CoreDefaults => "Core defaults",
+            Self::InitialSync => "Initial sync (fast)",
+            Self::LowMemory => "Low memory",
+            Self::PrunedLaptop => "Pruned laptop",
+            Self::Archival => "Archival server",
+        })
+    }
+}
+
+/// What the presets are sized against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Machine {
+    pub ram_bytes: u64,
+    pub cpus: u64,
+    /// Free space on the bitcoin data volume.
+    pub free_disk_bytes: u64,
+}
+
+impl Machine {
+    pub fn detect(data_dir: &Path) -> Self {
+        let pages = resources::sysconf(libc::_SC_PHYS_PAGES, 0);
+        let page_size = resources::sysconf(libc::_SC_PAGESIZE, 4096);
+        Self {
+            ram_bytes: pages * page_size,
+            cpus: std::thread::available_parallelism().map_or(1, |n| n.get() as u64),
+            free_disk_bytes: metrics::disk_free_bytes(data_dir).unwrap_or(0),
+        }
+    }
+}
+
+/// bitcoind options a preset sets; `None` leaves Core's value alone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Settings {
+    pub dbcache_mib: Option<u64>,
+    pub maxmempool_mib: Option<u64>,
+    pub par: Option<u64>,
+    pub prune_mib: Option<u64>,
+    pub blocksonly: bool,
+}
+
+impl Settings {
+    /// Values for `preset` on `m`.  `ibd_boost` raises `dbcache` to the
+    /// initial-sync size whatever the preset.
+    pub fn compute(preset: Preset, m: &Machine, ibd_boost: bool) -> Self {
+        let ram_mib = m.ram_bytes / MIB;
+        let mut s = match preset {
+            Preset::CoreDefaults => Self::default(),
+            Preset::InitialSync => Self {
+                dbcache_mib: Some(ibd_dbcache(ram_mib)),
+                par: Some(m.cpus.min(MAX_PAR)),
+                // Relaying transactions only slows a node that is catching up.
+                blocksonly: true,
+                ..Self::default()
+            },
+            Preset::LowMemory => Self {
+                dbcache_mib: Some((ram_mib / 16).clamp(100, 300)),
+                maxmempool_mib: Some(50),
+                par: Some(m.cpus.min(2)),
+                blocksonly: ram_mib < 2048,
+                ..Self::default()
+            },
+            Preset::PrunedLaptop => Self {
+                dbcache_mib: Some((ram_mib / 8).clamp(300, 2048)),
+                maxmempool_mib: Some(100),
+                prune_mib: Some(prune_target(m.free_disk_bytes)),
+                ..Self::default()
+            },
+            Preset::Archival => Self {
+                dbcache_mib: Some((ram_mib / 4).clamp(DEFAULT_DBCACHE, 8192)),
+                maxmempool_mib: Some(1000),
+                par: Some(m.cpus.min(MAX_PAR)),
+                ..Self::default()
+            },
+        };
+        if ibd_boost {
+            let boosted = ibd_dbcache(ram_mib).max(s.dbcache_mib.unwrap_or(DEFAULT_DBCACHE));
+            s.dbcache_mib = Some(boosted);
+        }
+        s
+    }
+
+    /// bitcoind command-line arguments.
+    pub fn args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(n) = self.dbcache_mib {
+            args.push(format!("-dbcache={n}"));
+        }
+        if let Some(n) = self.maxmempool_mib {
+            args.push(format!("-maxmempool={n}"));
+        }
+        if let Some(n) = self.par {
+            args.push(format!("-par={n}"));
+        }
+        if let Some(n) = self.prune_mib {
+            // The generated bitcoin.conf enables txindex, which pruning refuses.
+            args.push(format!("-prune={n}"));
+            args.push("-txindex=0".into());
+        }
+        if self.blocksonly {
+            args.push("-blocksonly=1".into());
+        }
+        args
+    }
+}
+
+/// Half the RAM, leaving the rest for electrs and the OS.
+fn ibd_dbcache(ram_mib: u64) -> u64 {
+    (ram_mib / 2).clamp(DEFAULT_DBCACHE, 16_384)
+}
+
+/// Half the free space, within what is sensible to keep.
+fn prune_target(free_disk_bytes: u64) -> u64 {
+    (free_disk_bytes / MIB / 2).clamp(MIN_PRUNE_MIB, 100_000)
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIB: u64 = 1 << 30;
+
+    #[test]
+    fn presets_scale_with_the_machine() {
+        let small = Machine {
+            ram_bytes: GIB,
+            cpus: 2,
+            free_disk_bytes: 20 * GIB,
+        };
+        let big = Machine {
+            ram_bytes: 64 * GIB,
+            cpus: 32,
+            free_disk_bytes: 4096 * GIB,
+        };
+        assert!(Settings::compute(Preset::CoreDefaults, &big, false)
+            .args()
+            .is_empty());
+        assert_eq!(
+            Settings::compute(Preset::LowMemory, &small, false).args(),
+            ["-dbcache=100", "-maxmempool=50", "-par=2", "-blocksonly=1"]
+        );
+        assert_eq!(
+            Settings::compute(Preset::InitialSync, &big, false).args(),
+            ["-dbcache=16384", "-par=15", "-blocksonly=1"]
+        );
+        assert_eq!(
+            Settings::compute(Preset::PrunedLaptop, &small, false).args(),
+            [
+                "-dbcache=300",
+                "-maxmempool=100",
+                "-prune=10240",
+                "-txindex=0"
+            ]
+        );
+        assert_eq!(
+            Settings::compute(Preset::PrunedLaptop, &big, false).prune_mib,
+            Some(100_000)
+        );
+    }
+
+    #[test]
+    fn ibd_boost_only_raises_dbcache() {
+        let m = Machine {
+            ram_bytes: 16 * GIB,
+            cpus: 8,
+            free_disk_bytes: 0,
+        };
+        let plain = Settings::compute(Preset::CoreDefaults, &m, true);
+        assert_eq!(plain.args(), ["-dbcache=8192"]);
+        let archival = Settings::compute(Preset::Archival, &m, true);
+        assert_eq!(archival.dbcache_mib, Some(8192));
+        assert_eq!(archival.maxmempool_mib, Some(1000));
+    }
+}
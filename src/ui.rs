@@ -21,23 +21,55 @@
 //!
 //! This keeps the UI thread non-blocking at all times.
 
-use std::{path::PathBuf, sync::Arc, time::Duration};
+use std::{
+    fmt::Write as _,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use iced::widget::scrollable::{Direction, Id as ScrollId, Scrollbar};
 use iced::{
     font::Font,
+    keyboard::{self, key, Key},
     time,
-    widget::{button, column, container, row, scrollable, text, text_input, Space},
+    widget::{
+        button, checkbox, column, container, pick_list, progress_bar, row, scrollable, text,
+        text_input, Space,
+    },
     Alignment, Color, Element, Length, Padding, Subscription, Task,
 };
 
 use crate::{
-    config::Config,
+    alerts::{self, Alert},
+    api,
+    binary_arch::{self, BinaryInfo, Compatibility},
+    chain_monitor::{self, Reorg, Tip, TipMonitor},
+    config::{AlertsConfig, Config, HooksConfig, PruneConfig, UpdateSourceConfig},
+    history, hooks,
+    launch_mode::{self, OneShot},
+    metrics,
     process_manager::{self, is_electrs_synced_line, new_queue, OutputQueue, ProcessHandle},
+    prune, resources,
     rpc::{self, BlockchainInfo, RpcAuth},
-    updater::{self, UpdateResult},
+    tuning::{self, Preset},
+    updater::{self, Component, UpdatePlan, UpdateResult},
+    zmq,
 };
 
+mod broadcast;
+mod console;
+mod discover;
+mod events;
+mod explorer;
+mod lookup;
+mod mempool;
+mod migrate;
+mod peers;
+mod psbt;
+mod snapshots;
+mod wallets;
+
 // ── Colour palette ────────────────────────────────────────────────────────────
 
 const BG: Color = Color {
@@ -131,6 +163,9 @@ const TEXT_TER: Color = Color {
     a: 1.0,
 }; // #8e8e93
 
+/// Binaries whose architecture is checked in the paths panel.
+const CHECKED_BINARIES: [&str; 3] = ["bitcoind", "bitcoin-cli", "electrs"];
+
 // ── Scrollable IDs for programmatic scroll-to-bottom ─────────────────────────
 
 fn bitcoin_scroll_id() -> ScrollId {
@@ -140,8 +175,65 @@ fn electrs_scroll_id() -> ScrollId {
     ScrollId::new("electrs_terminal")
 }
 
+// ── Tabs ──────────────────────────────────────────────────────────────────────
+
+/// Top-level page selected in the toolbar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tab {
+    /// Paths, node launch controls and terminals.
+    Nodes,
+    Peers,
+    Mempool,
+    Explorer,
+    Events,
+    Lookup,
+    Wallets,
+    Psbt,
+    Broadcast,
+    Snapshots,
+    Console,
+}
+
+impl Tab {
+    const ALL: [Tab; 11] = [
+        Tab::Nodes,
+        Tab::Peers,
+        Tab::Mempool,
+        Tab::Explorer,
+        Tab::Events,
+        Tab::Lookup,
+        Tab::Wallets,
+        Tab::Psbt,
+        Tab::Broadcast,
+        Tab::Snapshots,
+        Tab::Console,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Tab::Nodes => "Nodes",
+            Tab::Peers => "Peers",
+            Tab::Mempool => "Mempool",
+            Tab::Explorer => "Explorer",
+            Tab::Events => "Events",
+            Tab::Lookup => "Lookup",
+            Tab::Wallets => "Wallets",
+            Tab::Psbt => "PSBT",
+            Tab::Broadcast => "Broadcast",
+            Tab::Snapshots => "Snapshots",
+            Tab::Console => "Console",
+        }
+    }
+}
+
 // ── Message ───────────────────────────────────────────────────────────────────
 
+/// What each updated component copied, or why it failed.
+type Applied = Vec<(Component, Result<String, String>)>;
+
+/// Header check of each binary in a folder; `None` when the file doesn't exist.
+type BinaryChecks = Vec<(&'static str, Option<Result<BinaryInfo, String>>)>;
+
 #[derive(Debug, Clone)]
 pub enum Message {
     // ── Timer ticks ──────────────────────────────────────────────────────────
@@ -149,6 +241,8 @@ pub enum Message {
     OutputTick,
     /// 5 s — poll Bitcoin RPC for chain state.
     RpcTick,
+    /// 2 s while a node runs — sample CPU, memory and I/O of each node.
+    ResourceTick,
 
     // ── Path editing ─────────────────────────────────────────────────────────
     BinariesPathChanged(String),
@@ -158,6 +252,8 @@ pub enum Message {
     BrowseBitcoinData,
     BrowseElectrsData,
     BinariesBrowsed(Option<String>),
+    /// Headers of the binaries in this folder were inspected.
+    BinariesChecked(String, BinaryChecks),
     BitcoinDataBrowsed(Option<String>),
     ElectrsDataBrowsed(Option<String>),
     SavePaths,
@@ -169,11 +265,117 @@ pub enum Message {
     LaunchElectrs,
     ShutdownBoth,
     ShutdownElectrsOnly,
+    /// A `stop` RPC was sent from the console.
+    BitcoindStopRequested,
 
     // ── Async results ─────────────────────────────────────────────────────────
     BlockchainInfoReceived(Result<BlockchainInfo, String>),
+    /// The HTTP API started, failed, or received a request.
+    Api(api::Event),
+    /// A ZMQ notification or connection change.
+    Zmq(zmq::Event),
+    /// Whether a replaced tip was reorganised away.
+    ReorgChecked(Result<Option<Reorg>, String>),
+    /// A chain event was appended to the history (or failed to be).
+    EventRecorded(Result<history::Entry, String>),
     UpdateBinaries,
-    UpdateResult(String), // human-readable outcome message
+    /// User confirmed the pending plan (stopping/restarting processes as needed).
+    ApplyUpdate,
+    /// Plan applied; restart flags say which processes were stopped for it.
+    UpdateApplied {
+        /// `Err` if the update task itself died.
+        results: Result<Applied, String>,
+        restart_bitcoin: bool,
+        restart_electrs: bool,
+    },
+    UpdateResult(UpdateResult),
+    /// Figures sampled for `GET /metrics` and peer alerts.
+    MetricsPolled(metrics::Polled),
+    /// An alert's webhook / script finished (or failed).
+    AlertDelivered(Result<(), String>),
+    /// Send a test notification with these actions.
+    TestAlert {
+        webhook_url: String,
+        command: String,
+    },
+    TestAlertSent(Result<(), String>),
+    /// Toggle the local HTTP API.
+    ApiToggled(bool),
+    CopyApiToken,
+    /// Toggle ZMQ notifications (written to bitcoin.conf on the next launch).
+    ZmqToggled(bool),
+    /// Choose the bitcoind resource preset (applies on the next launch).
+    TuningPresetSelected(Preset),
+    IbdBoostToggled(bool),
+    /// Ask before restarting bitcoind with the current tuning arguments.
+    ReviewTuningRestart,
+    ConfirmTuningRestart,
+    CancelTuningRestart,
+    /// bitcoind (and electrs, when `true`) stopped for a tuning restart.
+    TuningRestartStopped(bool),
+    /// Show or hide the Bitcoin panel's "Launch with…" menu.
+    ToggleLaunchWith,
+    LaunchModeSelected(launch_mode::Mode),
+    RescanFromEdited(String),
+    AssumeValidEdited(String),
+    /// Check the one-shot options and show the confirmation.
+    ReviewLaunchWith,
+    ConfirmLaunchWith,
+    CancelLaunchWith,
+    RescanFinished(Result<Vec<String>, String>),
+    PruneToggled(bool),
+    PruneTargetEdited(String),
+    /// Enter in the target field: check the value and apply or confirm it.
+    PruneTargetSubmitted,
+    /// Apply the prune change awaiting confirmation.
+    ConfirmPrune,
+    CancelPrune,
+    PruneScanned(prune::Scan),
+    DeleteTxindex,
+    TxindexDeleted(Result<(), String>),
+    /// Toggle whether the updater considers release candidates / betas.
+    IncludePrereleasesToggled(bool),
+    UpdateSourceInputChanged(String),
+    /// Add the typed folder path or `http(s)://` mirror URL as a source.
+    AddUpdateSource,
+    BrowseUpdateSource,
+    UpdateSourceBrowsed(Option<String>),
+    RemoveUpdateSource(usize),
+
+    // ── Tabs ──────────────────────────────────────────────────────────────────
+    SelectTab(Tab),
+    Peers(peers::Msg),
+    Mempool(mempool::Msg),
+    Explorer(explorer::Msg),
+    Events(events::Msg),
+    /// Persist the stall period (minutes without a block).
+    StallMinutesSaved(u64),
+    /// Persist the alert rules and actions.
+    AlertsSaved(AlertsConfig),
+    /// Persist the event hook scripts.
+    HooksSaved(HooksConfig),
+    Lookup(lookup::Msg),
+    Wallets(wallets::Msg),
+    Psbt(psbt::Msg),
+    Broadcast(broadcast::Msg),
+    Snapshots(snapshots::Msg),
+    /// Paths panel migration wizard.
+    Migrate(migrate::Msg),
+    /// A directory was copied and verified: switch the config to `PathBuf`.
+    DataMigrated(migrate::Which, PathBuf),
+    /// Existing installations found on this machine.
+    Discover(discover::Msg),
+    /// Use an existing directory instead of the configured one.
+    InstallationAdopted(crate::discover::Kind, PathBuf),
+    /// Open a raw transaction in the Broadcast tab and run its preflight.
+    BroadcastHex(String),
+    /// Persist the broadcast max fee rate (sat/vB).
+    MaxFeeRateSaved(f64),
+    Console(console::Msg),
+    /// Open a transaction in the Explorer tab (links from other tabs).
+    ExploreTx(String),
+    /// Open a block by hash in the Explorer tab.
+    ExploreBlock(String),
 
     // ── Modal / overlay ───────────────────────────────────────────────────────
     /// Dismiss the info/error overlay.
@@ -196,6 +398,13 @@ pub struct App {
     binaries_path_edit: String,
     bitcoin_data_path_edit: String,
     electrs_data_path_edit: String,
+    /// New update source being typed (folder path or mirror URL).
+    update_source_edit: String,
+    /// Whether each update source looked reachable when last checked
+    /// (local folders only; see `check_update_sources`).
+    update_sources_available: Vec<bool>,
+    /// Header check of each binary in the edited binaries folder.
+    binary_checks: BinaryChecks,
 
     // ── Process handles ───────────────────────────────────────────────────────
     bitcoin_handle: Option<ProcessHandle>,
@@ -214,22 +423,110 @@ pub struct App {
     bitcoin_synced: bool,
     electrs_running: bool,
     electrs_synced: bool,
+    /// The process stopped on its own since it was last launched.
+    bitcoin_exited: bool,
+    /// bitcoind was asked to `stop` over RPC; its exit is not a crash.
+    bitcoin_stop_requested: bool,
+    electrs_exited: bool,
+    /// Recent CPU / memory / I/O of each process, for the sparklines.
+    bitcoin_usage: resources::Monitor,
+    electrs_usage: resources::Monitor,
     block_height: u64,
+    /// Last `getblockchaininfo` of the running bitcoind.
+    chain_info: Option<BlockchainInfo>,
+    /// Best block over time, for reorg and stall detection.
+    tip_monitor: TipMonitor,
+    /// Which alert rules are firing.
+    alert_engine: alerts::Engine,
+    /// ZMQ endpoints bitcoind was launched with, and their topics.
+    zmq_endpoints: Vec<(String, Vec<zmq::Topic>)>,
+    /// Endpoints currently subscribed.
+    zmq_connected: Vec<String>,
+    /// Last block announced over ZMQ (`hashblock` and `sequence` both
+    /// announce each block).
+    zmq_last_block: Option<String>,
+
+    // ── Tuning ────────────────────────────────────────────────────────────────
+    /// RAM, cores and free space the presets are sized against.
+    machine: tuning::Machine,
+    /// Tuning arguments bitcoind was last launched with.
+    launched_tuning: Vec<String>,
+    /// A restart to apply changed tuning arguments awaits confirmation.
+    tuning_restart_confirm: bool,
+    // ── Launch with… ──────────────────────────────────────────────────────────
+    launch_with_open: bool,
+    /// One-shot options being edited in the menu.
+    launch_with: OneShot,
+    rescan_from_edit: String,
+    /// The options passed the checks and await confirmation.
+    launch_with_confirm: bool,
+    /// Options for the next bitcoind launch only; taken by it.
+    next_launch: Option<OneShot>,
+    /// Rescan wallets from this height once RPC answers.
+    pending_rescan: Option<u64>,
+    /// Relaunch electrs once bitcoind answers RPC after a restart.
+    pending_electrs_launch: bool,
+
+    /// Sizes and `prune=` found in the bitcoin data directory.
+    prune_scan: prune::Scan,
+    prune_target_edit: String,
+    /// A prune change that deletes data or needs a re-download, shown for
+    /// confirmation before it is saved.
+    prune_confirm: Option<PruneConfig>,
+    /// A tuning preset that turns pruning on or off, held for the same
+    /// confirmation.
+    preset_confirm: Option<Preset>,
+    /// Whether bitcoind last reported its blocks as pruned.
+    bitcoin_pruned: bool,
+
+    // ── HTTP API ──────────────────────────────────────────────────────────────
+    /// URL the API listens on, or why it could not start.
+    api_state: Option<Result<String, String>>,
+    /// API request waiting for an update check or apply to finish.
+    api_update_waiter: Option<api::Responder>,
+    /// Latest sample for the metrics exporter and peer alerts.
+    metrics_polled: metrics::Polled,
+    /// Successful launches since the manager started, per process.
+    bitcoin_launches: u64,
+    electrs_launches: u64,
 
     // ── UI state ──────────────────────────────────────────────────────────────
     paths_visible: bool,
+    tab: Tab,
+    peers: peers::State,
+    mempool: mempool::State,
+    explorer: explorer::State,
+    events: events::State,
+    lookup: lookup::State,
+    wallets: wallets::State,
+    psbt: psbt::State,
+    broadcast: broadcast::State,
+    snapshots: snapshots::State,
+    migrate: migrate::State,
+    discover: discover::State,
+    console: console::State,
 
     /// Non-empty ⇒ display an overlay dialog with this message.
     overlay_message: Option<String>,
     /// When `overlay_message` is set, this optional path allows a "Open `BitForge`" button.
     bitforge_path: Option<PathBuf>,
+    /// Update plan shown in the overlay, awaiting confirmation.
+    pending_update: Option<UpdatePlan>,
 }
 
 impl App {
-    pub fn new(ssd_root: &PathBuf) -> Self {
+    #[allow(clippy::too_many_lines)]
+    pub fn new(ssd_root: &Path) -> Self {
         let config = Config::load(ssd_root);
 
+        let max_fee_rate = config.max_fee_rate;
+        let stall_minutes = config.stall_minutes;
+        let prune_target_edit = config.prune.target_gib.to_string();
+        let alerts_config = config.alerts.clone();
+        let hooks_config = config.hooks.clone();
         let binaries_edit = config.binaries_path.to_string_lossy().into_owned();
+        let config_binaries_path = config.binaries_path.clone();
+        let config_bitcoin_data_path = config.bitcoin_data_path.clone();
         let bitcoin_data_edit = config.bitcoin_data_path.to_string_lossy().into_owned();
         let electrs_data_edit = config.electrs_data_path.to_string_lossy().into_owned();
 
@@ -260,11 +557,14 @@ impl App {
             &format!("DB dir   : {}", config.electrs_data_path.display()),
         );
 
-        Self {
+        let mut app = Self {
             config,
             binaries_path_edit: binaries_edit,
             bitcoin_data_path_edit: bitcoin_data_edit,
             electrs_data_path_edit: electrs_data_edit,
+            update_source_edit: String::new(),
+            update_sources_available: Vec::new(),
+            binary_checks: inspect_binaries(&config_binaries_path),
             bitcoin_handle: None,
             electrs_handle: None,
             bitcoin_queue,
@@ -275,11 +575,58 @@ impl App {
             bitcoin_synced: false,
             electrs_running: false,
             electrs_synced: false,
+            bitcoin_exited: false,
+            bitcoin_stop_requested: false,
+            electrs_exited: false,
+            bitcoin_usage: resources::Monitor::default(),
+            electrs_usage: resources::Monitor::default(),
             block_height: 0,
+            chain_info: None,
+            tip_monitor: TipMonitor::default(),
+            alert_engine: alerts::Engine::default(),
+            zmq_endpoints: Vec::new(),
+            zmq_connected: Vec::new(),
+            zmq_last_block: None,
+            machine: tuning::Machine::detect(&config_bitcoin_data_path),
+            launched_tuning: Vec::new(),
+            tuning_restart_confirm: false,
+            launch_with_open: false,
+            launch_with: OneShot::default(),
+            rescan_from_edit: "0".into(),
+            launch_with_confirm: false,
+            next_launch: None,
+            pending_rescan: None,
+            pending_electrs_launch: false,
+            prune_scan: prune::Scan::default(),
+            prune_target_edit,
+            prune_confirm: None,
+            preset_confirm: None,
+            bitcoin_pruned: false,
+            api_state: None,
+            api_update_waiter: None,
+            metrics_polled: metrics::Polled::default(),
+            bitcoin_launches: 0,
+            electrs_launches: 0,
             paths_visible: true,
+            tab: Tab::Nodes,
+            peers: peers::State::default(),
+            mempool: mempool::State::default(),
+            explorer: explorer::State::default(),
+            events: events::State::new(stall_minutes, &alerts_config, hooks_config),
+            lookup: lookup::State::default(),
+            wallets: wallets::State::default(),
+            psbt: psbt::State::default(),
+            broadcast: broadcast::State::new(max_fee_rate),
+            snapshots: snapshots::State::new(&ssd_root.join("Snapshots")),
+            migrate: migrate::State::default(),
+            discover: discover::State::new(ssd_root),
+            console: console::State::default(),
             overlay_message: None,
             bitforge_path: None,
-        }
+            pending_update: None,
+        };
+        app.check_update_sources();
+        app
     }
 
     // ── update ────────────────────────────────────────────────────────────────
@@ -301,11 +648,16 @@ impl App {
                     }
                 }
                 // Electrs queue
+                let mut tasks: Vec<Task<Message>> = Vec::new();
                 if let Ok(mut q) = self.electrs_queue.lock() {
                     while let Some(line) = q.pop_front() {
                         // Check for electrs sync signals
-                        if is_electrs_synced_line(&line) {
+                        if !self.electrs_synced && is_electrs_synced_line(&line) {
                             self.electrs_synced = true;
+                            tasks.push(self.run_hook(
+                                hooks::Invocation::new(hooks::Event::NodeSynced).process("electrs"),
+                                &self.electrs_queue,
+                            ));
                         }
                         self.electrs_lines.push(line);
                         els_new = true;
@@ -326,12 +678,24 @@ impl App {
                 if self.bitcoin_running {
                     if let Some(h) = &mut self.bitcoin_handle {
                         if !h.is_running() {
+                            let requested = std::mem::take(&mut self.bitcoin_stop_requested);
                             self.bitcoin_running = false;
                             self.bitcoin_synced = false;
+                            self.bitcoin_exited = !requested;
                             self.block_height = 0;
                             // If bitcoin died, electrs status is also invalid
                             self.electrs_synced = false;
+                            self.pending_electrs_launch = false;
                             push_msg(&self.bitcoin_queue, "bitcoind has stopped.");
+                            let event = if requested {
+                                hooks::Event::NodeStopped
+                            } else {
+                                hooks::Event::NodeCrashed
+                            };
+                            tasks.push(self.run_hook(
+                                hooks::Invocation::new(event).process("bitcoind"),
+                                &self.bitcoin_queue,
+                            ));
                         }
                     }
                 }
@@ -340,13 +704,20 @@ impl App {
                         if !h.is_running() {
                             self.electrs_running = false;
                             self.electrs_synced = false;
+                            self.electrs_exited = true;
                             push_msg(&self.electrs_queue, "electrs has stopped.");
+                            tasks.push(
+                                self.run_hook(
+                                    hooks::Invocation::new(hooks::Event::NodeCrashed)
+                                        .process("electrs"),
+                                    &self.electrs_queue,
+                                ),
+                            );
                         }
                     }
                 }
 
                 // Scroll terminals to bottom if new content arrived.
-                let mut tasks: Vec<Task<Message>> = Vec::new();
                 if btc_new {
                     tasks.push(
                         scrollable::scroll_to(
@@ -378,37 +749,346 @@ impl App {
                 }
             }
 
+            // ── Timer: resource sampling ──────────────────────────────────────
+            // A few small syscalls or /proc reads per process; cheap enough to do inline.
+            Message::ResourceTick => {
+                if let Some(h) = self
+                    .bitcoin_handle
+                    .as_ref()
+                    .filter(|_| self.bitcoin_running)
+                {
+                    self.bitcoin_usage.record(resources::sample(h.pid()));
+                }
+                if let Some(h) = self
+                    .electrs_handle
+                    .as_ref()
+                    .filter(|_| self.electrs_running)
+                {
+                    self.electrs_usage.record(resources::sample(h.pid()));
+                }
+                Task::none()
+            }
+
             // ── Timer: RPC poll ───────────────────────────────────────────────
             Message::RpcTick => {
+                let alerts = self.check_alerts();
                 if !self.bitcoin_running {
+                    return alerts;
+                }
+                let mut tasks = vec![alerts, self.poll_chain(), self.refresh_tab()];
+                let peer_rule = self
+                    .config
+                    .alerts
+                    .rules
+                    .iter()
+                    .any(|r| matches!(r, alerts::Rule::PeersBelow { .. }));
+                if self.config.api.enabled || peer_rule {
+                    let auth = self.rpc_auth();
+                    let electrs = self.electrs_running;
+                    tasks.push(Task::perform(
+                        metrics::poll(auth, electrs),
+                        Message::MetricsPolled,
+                    ));
+                }
+                Task::batch(tasks)
+            }
+
+            // ── ZMQ notifications ─────────────────────────────────────────────
+            Message::Zmq(event) => self.on_zmq(event),
+
+            // ── HTTP API ──────────────────────────────────────────────────────
+            Message::Api(api::Event::Listening(url)) => {
+                self.api_state = Some(Ok(url));
+                Task::none()
+            }
+            Message::Api(api::Event::Failed(e)) => {
+                self.api_state = Some(Err(e));
+                Task::none()
+            }
+            Message::Api(api::Event::Request(req)) => self.on_api_request(req),
+            Message::MetricsPolled(polled) => {
+                self.metrics_polled = polled;
+                Task::none()
+            }
+
+            // ── Alerts ────────────────────────────────────────────────────────
+            Message::AlertDelivered(result) => {
+                if let Err(e) = result {
+                    push_msg(&self.bitcoin_queue, &format!("Alert delivery failed: {e}"));
+                }
+                Task::none()
+            }
+            Message::TestAlert {
+                webhook_url,
+                command,
+            } => {
+                if webhook_url.is_empty() && command.is_empty() {
+                    self.overlay_message = Some("Enter a webhook URL or a script to test.".into());
                     return Task::none();
                 }
-                let auth = RpcAuth::from_data_dir(&self.config.bitcoin_data_path);
                 Task::perform(
                     async move {
-                        rpc::get_blockchain_info(&auth)
+                        alerts::deliver(Alert::test(unix_now()), webhook_url, command)
                             .await
-                            .map_err(|e| e.to_string())
+                            .map_err(|e| format!("{e:#}"))
                     },
-                    Message::BlockchainInfoReceived,
+                    Message::TestAlertSent,
                 )
             }
+            Message::TestAlertSent(result) => {
+                self.overlay_message = Some(match result {
+                    Ok(()) => "Test notification sent.".into(),
+                    Err(e) => format!("Test notification failed:\n{e}"),
+                });
+                Task::none()
+            }
+            Message::AlertsSaved(alerts) => {
+                if self.config.alerts != alerts {
+                    self.config.alerts = alerts;
+                    self.save_config_or_report();
+                }
+                Task::none()
+            }
+            Message::HooksSaved(hooks) => {
+                if self.config.hooks != hooks {
+                    self.config.hooks = hooks;
+                    self.save_config_or_report();
+                }
+                Task::none()
+            }
+            Message::ApiToggled(enabled) => {
+                if self.config.api.token.is_empty() {
+                    match api::new_token() {
+                        Ok(token) => self.config.api.token = token,
+                        Err(e) => {
+                            self.overlay_message = Some(format!("Cannot enable the API:\n{e:#}"));
+                            return Task::none();
+                        }
+                    }
+                }
+                self.config.api.enabled = enabled;
+                self.api_state = None;
+                self.save_config_or_report();
+                Task::none()
+            }
+            Message::CopyApiToken => iced::clipboard::write(self.config.api.token.clone()),
 
             // ── RPC result ────────────────────────────────────────────────────
             Message::BlockchainInfoReceived(result) => {
-                if let Ok(info) = result {
-                    self.block_height = info.blocks;
-                    self.bitcoin_synced = info.headers > 0
-                        && info.blocks >= info.headers.saturating_sub(1)
-                        && info.verification_progress > 0.9999;
+                let Ok(info) = result else {
+                    return Task::none();
+                };
+                self.block_height = info.blocks;
+                let was_synced = self.bitcoin_synced;
+                self.bitcoin_synced = info.headers > 0
+                    && info.blocks >= info.headers.saturating_sub(1)
+                    && info.verification_progress > 0.9999;
+                let mut tasks = vec![self.track_tip(&info)];
+                if self.bitcoin_synced && !was_synced && self.config.tuning.ibd_boost {
+                    self.config.tuning.ibd_boost = false;
+                    self.save_config_or_report();
+                    // The running node keeps the boosted dbcache until it is
+                    // restarted: offer that in the paths panel.
+                    if self.tuning_settings().args() != self.launched_tuning {
+                        self.tuning_restart_confirm = true;
+                        self.paths_visible = true;
+                    }
+                    push_msg(
+                        &self.bitcoin_queue,
+                        "Synced: IBD boost switched off. Confirm the restart in the paths \
+                         panel to return dbcache to the preset's size.",
+                    );
+                }
+                if self.bitcoin_synced && !was_synced {
+                    tasks.push(
+                        self.run_hook(
+                            hooks::Invocation::new(hooks::Event::NodeSynced)
+                                .process("bitcoind")
+                                .env("BNM_BLOCK_HEIGHT", info.blocks.to_string()),
+                            &self.bitcoin_queue,
+                        ),
+                    );
+                }
+                if let Some(from) = self.pending_rescan.take() {
+                    tasks.push(self.rescan_wallets(from));
+                }
+                if std::mem::take(&mut self.pending_electrs_launch) {
+                    tasks.push(Task::done(Message::LaunchElectrs));
+                }
+                self.bitcoin_pruned = info.pruned;
+                self.chain_info = Some(info);
+                Task::batch(tasks)
+            }
+            Message::ReorgChecked(result) => match result {
+                Ok(Some(reorg)) => {
+                    let alert = alerts::Engine::reorg(
+                        &self.config.alerts.rules,
+                        reorg.depth,
+                        reorg.describe(),
+                        unix_now(),
+                    );
+                    let event = self
+                        .record_event(history::Entry::now(history::Kind::Reorg, reorg.describe()));
+                    match alert {
+                        Some(alert) => Task::batch([event, self.send_alert(alert)]),
+                        None => event,
+                    }
+                }
+                Ok(None) => Task::none(),
+                Err(e) => {
+                    push_msg(&self.bitcoin_queue, &format!("Reorg check failed: {e}"));
+                    Task::none()
+                }
+            },
+            Message::EventRecorded(result) => match result {
+                Ok(entry) => self
+                    .events
+                    .update(events::Msg::Recorded(entry), self.rpc_auth()),
+                Err(e) => {
+                    push_msg(&self.bitcoin_queue, &format!("Event log write failed: {e}"));
+                    Task::none()
+                }
+            },
+
+            // ── Tabs ──────────────────────────────────────────────────────────
+            Message::SelectTab(tab) => {
+                self.tab = tab;
+                // Work that does not need the node.
+                let local = match tab {
+                    Tab::Console => console::State::focus(),
+                    Tab::Broadcast => Task::done(Message::Broadcast(broadcast::Msg::LoadHistory)),
+                    Tab::Events => Task::done(Message::Events(events::Msg::LoadLog)),
+                    _ => Task::none(),
+                };
+                if self.bitcoin_running {
+                    Task::batch([local, self.refresh_tab()])
+                } else {
+                    local
+                }
+            }
+            Message::Peers(msg) => {
+                let auth = self.rpc_auth();
+                self.peers.update(msg, auth)
+            }
+            Message::Mempool(msg) => {
+                let auth = self.rpc_auth();
+                self.mempool.update(msg, auth)
+            }
+            Message::Explorer(msg) => {
+                let auth = self.rpc_auth();
+                self.explorer.update(msg, auth)
+            }
+            Message::Lookup(msg) => {
+                let auth = self.rpc_auth();
+                self.lookup.update(msg, auth)
+            }
+            Message::Wallets(msg) => {
+                let auth = self.rpc_auth();
+                self.wallets.update(msg, auth)
+            }
+            Message::Psbt(msg) => {
+                let auth = self.rpc_auth();
+                self.psbt.update(msg, auth)
+            }
+            Message::Broadcast(msg) => {
+                let auth = self.rpc_auth();
+                self.broadcast.update(msg, auth)
+            }
+            Message::Snapshots(msg) => {
+                let auth = self.rpc_auth();
+                self.snapshots.update(msg, auth, self.bitcoin_pruned)
+            }
+            Message::Migrate(msg) => {
+                let ctx = migrate::Context {
+                    bitcoin_data: self.config.bitcoin_data_path.clone(),
+                    electrs_data: self.config.electrs_data_path.clone(),
+                    bitcoin_running: self.bitcoin_running,
+                    electrs_running: self.electrs_running,
+                };
+                self.migrate.update(msg, &ctx)
+            }
+            Message::DataMigrated(which, dst) => {
+                self.switch_data_path(which, dst, &format!("{which} moved to"))
+            }
+            Message::Discover(msg) => {
+                let dismissed = matches!(msg, discover::Msg::Dismiss);
+                let task = self.discover.update(msg);
+                if self.discover.is_open() {
+                    self.paths_visible = true;
+                }
+                // Closing the first-run list keeps the defaults: save them
+                // so the next start does not ask again.
+                if dismissed && !Config::config_file_path().exists() {
+                    self.save_config_or_report();
+                }
+                task
+            }
+            Message::InstallationAdopted(kind, dst) => {
+                let (which, running) = match kind {
+                    crate::discover::Kind::Bitcoin => {
+                        (migrate::Which::BitcoinData, self.bitcoin_running)
+                    }
+                    crate::discover::Kind::Electrs => {
+                        (migrate::Which::ElectrsDb, self.electrs_running)
+                    }
+                };
+                if running || self.migrate.copying() == Some(which) {
+                    self.overlay_message = Some(format!(
+                        "Stop {} before switching its directory.",
+                        which.process()
+                    ));
+                    return Task::none();
+                }
+                self.switch_data_path(which, dst, &format!("{which} switched to existing"))
+            }
+            Message::BroadcastHex(hex) => {
+                self.tab = Tab::Broadcast;
+                let auth = self.rpc_auth();
+                Task::batch([
+                    self.broadcast.open_hex(hex, auth),
+                    Task::done(Message::Broadcast(broadcast::Msg::LoadHistory)),
+                ])
+            }
+            Message::MaxFeeRateSaved(rate) => {
+                #[allow(clippy::float_cmp)]
+                if self.config.max_fee_rate != rate {
+                    self.config.max_fee_rate = rate;
+                    self.save_config_or_report();
+                }
+                Task::none()
+            }
+            Message::Console(msg) => {
+                let auth = self.rpc_auth();
+                self.console.update(msg, auth)
+            }
+            Message::Events(msg) => {
+                let auth = self.rpc_auth();
+                self.events.update(msg, auth)
+            }
+            Message::StallMinutesSaved(minutes) => {
+                if self.config.stall_minutes != minutes {
+                    self.config.stall_minutes = minutes;
+                    self.save_config_or_report();
                 }
                 Task::none()
             }
+            Message::ExploreTx(txid) => {
+                self.tab = Tab::Explorer;
+                let auth = self.rpc_auth();
+                self.explorer
+                    .update(explorer::Msg::Open(explorer::Target::Tx(txid)), auth)
+            }
+            Message::ExploreBlock(hash) => {
+                self.tab = Tab::Explorer;
+                let auth = self.rpc_auth();
+                self.explorer
+                    .update(explorer::Msg::Open(explorer::Target::Block(hash)), auth)
+            }
 
             // ── Path editing ──────────────────────────────────────────────────
             Message::BinariesPathChanged(s) => {
                 self.binaries_path_edit = s;
-                Task::none()
+                self.check_binaries()
             }
             Message::BitcoinDataPathChanged(s) => {
                 self.bitcoin_data_path_edit = s;
@@ -432,9 +1112,17 @@ impl App {
                 Message::ElectrsDataBrowsed,
             ),
 
-            Message::BinariesBrowsed(p) => {
-                if let Some(s) = p {
+            Message::BinariesBrowsed(p) => match p {
+                Some(s) => {
                     self.binaries_path_edit = s;
+                    self.check_binaries()
+                }
+                None => Task::none(),
+            },
+            Message::BinariesChecked(dir, checks) => {
+                // Typing moves on faster than the checks; keep the latest.
+                if dir == self.binaries_path_edit {
+                    self.binary_checks = checks;
                 }
                 Task::none()
             }
@@ -486,236 +1174,1215 @@ impl App {
                 )
             }
 
-            Message::PathsSaved(result) => {
-                match result {
-                    Ok(()) => {
-                        self.overlay_message = Some(format!(
-                            "Paths saved.\nChanges take effect on the next node launch.\n\nConfig: {}",
-                            Config::config_file_path().display()
-                        ));
-                    }
-                    Err(e) => {
-                        self.overlay_message = Some(format!("Failed to save paths:\n{e}"));
-                    }
+            Message::PathsSaved(result) => match result {
+                Ok(()) => {
+                    self.overlay_message = Some(format!(
+                        "Paths saved.\nChanges take effect on the next node launch.\n\nConfig: {}",
+                        Config::config_file_path().display()
+                    ));
+                    self.scan_prune()
                 }
-                Task::none()
-            }
+                Err(e) => {
+                    self.overlay_message = Some(format!("Failed to save paths:\n{e}"));
+                    Task::none()
+                }
+            },
 
             Message::TogglePathsPanel => {
                 self.paths_visible = !self.paths_visible;
+                if self.paths_visible {
+                    self.check_update_sources();
+                    return self.scan_prune();
+                }
                 Task::none()
             }
 
             // ── Launch nodes ──────────────────────────────────────────────────
             Message::LaunchBitcoin => {
-                if self.bitcoin_running {
-                    self.overlay_message = Some("Bitcoin is already running.".into());
+                let result = self.launch_bitcoin();
+                self.or_overlay(result)
+            }
+            Message::LaunchElectrs => {
+                let result = self.launch_electrs();
+                self.or_overlay(result)
+            }
+
+            // ── Shutdown ──────────────────────────────────────────────────────
+            Message::ShutdownBoth => {
+                let result = self.shutdown_both();
+                self.or_overlay(result)
+            }
+            Message::ShutdownElectrsOnly => {
+                let result = self.shutdown_electrs();
+                self.or_overlay(result)
+            }
+            Message::BitcoindStopRequested => {
+                self.bitcoin_stop_requested = self.bitcoin_running;
+                Task::none()
+            }
+
+            // ── Binary update ─────────────────────────────────────────────────
+            Message::UpdateBinaries => {
+                let sources = self.config.update_sources.clone();
+                let bitforge_app = self.config.bitforge_app_path.clone();
+                let binaries_dst = self.config.binaries_path.clone();
+                let include_prereleases = self.config.include_prereleases;
+                Task::perform(
+                    async move {
+                        // Sources may block on disk or HTTP; keep that off the executor.
+                        tokio::task::spawn_blocking(move || {
+                            updater::plan_update(
+                                &sources,
+                                &bitforge_app,
+                                &binaries_dst,
+                                include_prereleases,
+                            )
+                        })
+                        .await
+                        .unwrap_or_else(|e| {
+                            UpdateResult::SourcesUnavailable(vec![format!(
+                                "update task failed: {e}"
+                            )])
+                        })
+                    },
+                    Message::UpdateResult,
+                )
+            }
+
+            Message::ApplyUpdate => {
+                let Some(plan) = self.pending_update.take() else {
                     return Task::none();
+                };
+                self.overlay_message = None;
+
+                let (restart_bitcoin, restart_electrs) = self.processes_affected_by(&plan);
+                let mut tasks = Vec::new();
+                let electrs_handle = if restart_electrs {
+                    push_msg(&self.electrs_queue, "Stopping electrs for update…");
+                    self.electrs_running = false;
+                    self.electrs_synced = false;
+                    tasks.push(self.run_hook(
+                        hooks::Invocation::new(hooks::Event::NodeStopped).process("electrs"),
+                        &self.electrs_queue,
+                    ));
+                    self.electrs_handle.take()
+                } else {
+                    None
+                };
+                let bitcoin_handle = if restart_bitcoin {
+                    push_msg(&self.bitcoin_queue, "Stopping bitcoind for update…");
+                    self.bitcoin_running = false;
+                    self.bitcoin_synced = false;
+                    tasks.push(self.run_hook(
+                        hooks::Invocation::new(hooks::Event::NodeStopped).process("bitcoind"),
+                        &self.bitcoin_queue,
+                    ));
+                    self.bitcoin_handle.take()
+                } else {
+                    None
+                };
+
+                let auth = RpcAuth::from_data_dir(&self.config.bitcoin_data_path);
+                let binaries_dst = self.config.binaries_path.clone();
+                let btc_q = Arc::clone(&self.bitcoin_queue);
+                let els_q = Arc::clone(&self.electrs_queue);
+                tasks.push(Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            if let Some(mut handle) = electrs_handle {
+                                handle.terminate();
+                                push_msg(&els_q, "electrs stopped.");
+                            }
+                            if let Some(handle) = bitcoin_handle {
+                                stop_bitcoind_blocking(handle, &auth, &btc_q);
+                            }
+                            updater::apply_plan(&plan, &binaries_dst)
+                                .into_iter()
+                                .map(|(c, result)| (c, result.map_err(|e| format!("{e:#}"))))
+                                .collect()
+                        })
+                        .await
+                        .map_err(|e| format!("update task failed: {e}"))
+                    },
+                    move |results| Message::UpdateApplied {
+                        results,
+                        restart_bitcoin,
+                        restart_electrs,
+                    },
+                ));
+                Task::batch(tasks)
+            }
+
+            Message::UpdateApplied {
+                results,
+                restart_bitcoin: stopped_bitcoin,
+                restart_electrs: stopped_electrs,
+            } => {
+                self.bitforge_path = None;
+                let check = self.check_binaries();
+                // Which binaries changed is unknown if the task died.
+                let results = results.unwrap_or_else(|e| {
+                    Component::ALL
+                        .iter()
+                        .map(|&c| (c, Err(e.clone())))
+                        .collect()
+                });
+                let failed = |c| results.iter().any(|(rc, r)| *rc == c && r.is_err());
+                let mut installed = Vec::new();
+                let mut errors = Vec::new();
+                for (c, result) in &results {
+                    match result {
+                        Ok(copied) => installed.push(format!("{} ({copied})", c.display_name())),
+                        Err(e) => errors.push(format!("{}: {e}", c.display_name())),
+                    }
+                }
+                for line in &installed {
+                    push_msg(&self.bitcoin_queue, &format!("Update complete: {line}"));
+                }
+                for line in &errors {
+                    push_msg(&self.bitcoin_queue, &format!("Update failed: {line}"));
                 }
-                // Ensure bitcoin.conf exists
-                let _ = rpc::ensure_bitcoin_conf(&self.config.bitcoin_data_path);
-
-                match process_manager::launch_bitcoind(
-                    &self.config.binaries_path,
-                    &self.config.bitcoin_data_path,
-                    Arc::clone(&self.bitcoin_queue),
-                ) {
-                    Ok(handle) => {
-                        self.bitcoin_handle = Some(handle);
-                        self.bitcoin_running = true;
-                        self.bitcoin_synced = false;
+
+                // A process whose update failed stays stopped rather than
+                // run a half-replaced install; electrs also needs bitcoind.
+                let restart_bitcoin = stopped_bitcoin && !failed(Component::Bitcoin);
+                let restart_electrs = stopped_electrs
+                    && !failed(Component::Electrs)
+                    && (restart_bitcoin || self.bitcoin_running);
+
+                let mut message = match (installed.is_empty(), errors.is_empty()) {
+                    (true, true) => "No binaries were copied.".to_owned(),
+                    (false, true) => format!("Successfully updated:\n\n{}", installed.join("\n")),
+                    (true, false) => format!("Update failed:\n\n{}", errors.join("\n")),
+                    (false, false) => format!(
+                        "Update failed:\n\n{}\n\nUpdated:\n\n{}",
+                        errors.join("\n"),
+                        installed.join("\n")
+                    ),
+                };
+                let left: Vec<&str> = [
+                    (stopped_bitcoin && !restart_bitcoin, "bitcoind"),
+                    (stopped_electrs && !restart_electrs, "electrs"),
+                ]
+                .into_iter()
+                .filter_map(|(stopped, name)| stopped.then_some(name))
+                .collect();
+                if !left.is_empty() {
+                    let _ = write!(message, "\n\n{} left stopped.", left.join(" and "));
+                }
+                self.overlay_message = Some(message);
+                self.answer_api_update(errors.is_empty());
+
+                let mut task = if installed.is_empty() {
+                    Task::none()
+                } else {
+                    self.run_hook(
+                        hooks::Invocation::new(hooks::Event::UpdateInstalled)
+                            .env("BNM_UPDATE_SUMMARY", installed.join("\n")),
+                        &self.bitcoin_queue,
+                    )
+                };
+                // Electrs needs bitcoind's RPC, so it follows once that answers.
+                if restart_bitcoin {
+                    task = task.chain(Task::done(Message::LaunchBitcoin));
+                    self.pending_electrs_launch = restart_electrs;
+                } else if restart_electrs {
+                    task = task.chain(Task::done(Message::LaunchElectrs));
+                }
+                Task::batch([check, task])
+            }
+
+            Message::UpdateResult(result) => {
+                self.bitforge_path = None;
+                self.pending_update = None;
+                self.overlay_message = Some(match result {
+                    UpdateResult::Planned(plan) => self.describe_update_plan(plan),
+                    UpdateResult::BitForgeFound(path) => {
+                        self.bitforge_path = Some(path);
+                        "No update source is available.\n\n\
+                         BitForge.app is installed — open it to build binaries?"
+                            .into()
                     }
-                    Err(e) => {
-                        push_msg(&self.bitcoin_queue, &format!("Launch error: {e}"));
-                        self.overlay_message = Some(format!("Failed to launch Bitcoin:\n{e}"));
+                    UpdateResult::SourcesUnavailable(errors) => format!(
+                        "No update source is available:\n\n{}\n\n\
+                         Add a source in the paths panel, or download BitForge from:\n\
+                         https://github.com/csd113/BitForge-Python",
+                        errors.join("\n")
+                    ),
+                    UpdateResult::NothingToUpdate => {
+                        "No bitcoin-X.Y.Z or electrs-X.Y.Z folders found in the update sources."
+                            .into()
                     }
-                }
+                });
+                self.answer_api_update(true);
                 Task::none()
             }
 
-            Message::LaunchElectrs => {
-                if self.electrs_running {
-                    self.overlay_message = Some("Electrs is already running.".into());
-                    return Task::none();
-                }
-                if !self.bitcoin_running {
-                    self.overlay_message = Some(
-                        "Bitcoin must be running before starting Electrs.\n\
-                         Launch Bitcoin first and wait for the Running indicator."
-                            .into(),
-                    );
+            // ── Update sources ────────────────────────────────────────────────
+            Message::UpdateSourceInputChanged(s) => {
+                self.update_source_edit = s;
+                Task::none()
+            }
+            Message::AddUpdateSource => {
+                let input = self.update_source_edit.trim();
+                if input.is_empty() {
                     return Task::none();
                 }
-                match process_manager::launch_electrs(
-                    &self.config.binaries_path,
-                    &self.config.bitcoin_data_path,
-                    &self.config.electrs_data_path,
-                    Arc::clone(&self.electrs_queue),
-                ) {
-                    Ok(handle) => {
-                        self.electrs_handle = Some(handle);
-                        self.electrs_running = true;
-                        self.electrs_synced = false;
+                let source = if input.starts_with("http://") || input.starts_with("https://") {
+                    UpdateSourceConfig::HttpMirror {
+                        url: input.to_owned(),
                     }
-                    Err(e) => {
-                        push_msg(&self.electrs_queue, &format!("Launch error: {e}"));
-                        self.overlay_message = Some(format!("Failed to launch Electrs:\n{e}"));
+                } else {
+                    UpdateSourceConfig::LocalDir {
+                        path: PathBuf::from(input),
                     }
+                };
+                self.update_source_edit.clear();
+                self.add_update_source(source);
+                Task::none()
+            }
+            Message::BrowseUpdateSource => Task::perform(
+                async { browse_folder("Select Update Source Folder").await },
+                Message::UpdateSourceBrowsed,
+            ),
+            Message::UpdateSourceBrowsed(p) => {
+                if let Some(path) = p {
+                    self.add_update_source(UpdateSourceConfig::LocalDir {
+                        path: PathBuf::from(path),
+                    });
                 }
                 Task::none()
             }
-
-            // ── Shutdown ──────────────────────────────────────────────────────
-            Message::ShutdownBoth => {
-                self.terminate_electrs_internal();
-
-                if self.bitcoin_running {
-                    let auth = RpcAuth::from_data_dir(&self.config.bitcoin_data_path);
-                    let btc_q = Arc::clone(&self.bitcoin_queue);
-                    push_msg(&btc_q, "Sending stop via RPC…");
-
-                    // Move child out so we can wait on it in a background thread
-                    if let Some(mut handle) = self.bitcoin_handle.take() {
-                        self.bitcoin_running = false;
-                        self.bitcoin_synced = false;
-                        std::thread::spawn(move || {
-                            let rt = tokio::runtime::Handle::try_current();
-                            // Stop via RPC; if that fails, SIGTERM the process
-                            let stopped_via_rpc = rt.map_or_else(
-                                |_| {
-                                    tokio::runtime::Builder::new_current_thread()
-                                        .enable_all()
-                                        .build()
-                                        .map(|r| r.block_on(rpc::stop_bitcoind(&auth)).is_ok())
-                                        .unwrap_or(false)
-                                },
-                                |rt| rt.block_on(rpc::stop_bitcoind(&auth)).is_ok(),
-                            );
-                            if stopped_via_rpc {
-                                // Wait up to 60 s for graceful shutdown
-                                let deadline =
-                                    std::time::Instant::now() + std::time::Duration::from_secs(60);
-                                loop {
-                                    if std::time::Instant::now() >= deadline {
-                                        handle.terminate();
-                                        break;
-                                    }
-                                    if !handle.is_running() {
-                                        break;
-                                    }
-                                    std::thread::sleep(std::time::Duration::from_millis(500));
-                                }
-                            } else {
-                                handle.terminate();
-                            }
-                            push_msg(&btc_q, "bitcoind stopped.");
-                        });
-                    }
+            Message::RemoveUpdateSource(index) => {
+                if index < self.config.update_sources.len() {
+                    self.config.update_sources.remove(index);
+                    self.check_update_sources();
+                    self.save_config_or_report();
                 }
                 Task::none()
             }
 
-            Message::ShutdownElectrsOnly => {
-                self.terminate_electrs_internal();
+            Message::TuningPresetSelected(preset) => {
+                self.machine = tuning::Machine::detect(&self.config.bitcoin_data_path);
+                let from = self.tuning_settings().prune_mib;
+                let to = self.tuning_settings_for(preset).prune_mib;
+                self.prune_confirm = None;
+                self.preset_confirm = None;
+                if self.prune_needs_review(from, to) {
+                    self.preset_confirm = Some(preset);
+                    return Task::none();
+                }
+                self.config.tuning.preset = preset;
+                self.save_config_or_report();
                 Task::none()
             }
-
-            // ── Binary update ─────────────────────────────────────────────────
-            Message::UpdateBinaries => {
-                let binaries_dst = self.config.binaries_path.clone();
+            Message::IbdBoostToggled(on) => {
+                self.config.tuning.ibd_boost = on;
+                self.save_config_or_report();
+                Task::none()
+            }
+            Message::ReviewTuningRestart => {
+                self.tuning_restart_confirm = self.bitcoin_running;
+                Task::none()
+            }
+            Message::CancelTuningRestart => {
+                self.tuning_restart_confirm = false;
+                Task::none()
+            }
+            Message::ConfirmTuningRestart => {
+                self.tuning_restart_confirm = false;
+                let Some(bitcoin_handle) = self.bitcoin_handle.take() else {
+                    return Task::none();
+                };
+                push_msg(&self.bitcoin_queue, "Restarting bitcoind with new tuning…");
+                self.bitcoin_running = false;
+                self.bitcoin_synced = false;
+                // Electrs loses its backend meanwhile; relaunch it after.
+                let electrs_handle = self.electrs_handle.take();
+                let restart_electrs = electrs_handle.is_some();
+                if restart_electrs {
+                    self.electrs_running = false;
+                    self.electrs_synced = false;
+                }
+                let auth = self.rpc_auth();
                 let btc_q = Arc::clone(&self.bitcoin_queue);
+                let els_q = Arc::clone(&self.electrs_queue);
                 Task::perform(
                     async move {
-                        let result = updater::run_update(&binaries_dst);
-                        match result {
-                            UpdateResult::Updated(msg) => {
-                                push_msg(&btc_q, &format!("Update complete: {msg}"));
-                                format!("Successfully updated:\n\n{msg}")
-                            }
-                            UpdateResult::BitForgeFound(path) => {
-                                format!("__BITFORGE_FOUND__{}", path.display())
-                            }
-                            UpdateResult::BitForgeNotFound => {
-                                "No bitcoin_builds folder found.\n\n\
-                                 Download BitForge from:\n\
-                                 https://github.com/csd113/BitForge-Python".into()
-                            }
-                            UpdateResult::BinariesSubfolderMissing => {
-                                "Found ~/Downloads/bitcoin_builds but no 'binaries/' sub-folder inside it.".into()
-                            }
-                            UpdateResult::NothingToUpdate => {
-                                "No bitcoin-X.Y.Z or electrs-X.Y.Z folders found in the binaries folder.".into()
+                        tokio::task::spawn_blocking(move || {
+                            if let Some(mut handle) = electrs_handle {
+                                handle.terminate();
+                                push_msg(&els_q, "electrs stopped.");
                             }
-                        }
+                            stop_bitcoind_blocking(bitcoin_handle, &auth, &btc_q);
+                        })
+                        .await
+                        .ok();
+                        restart_electrs
                     },
-                    Message::UpdateResult,
+                    Message::TuningRestartStopped,
                 )
             }
-
-            Message::UpdateResult(msg) => {
-                // Special sentinel: BitForge was found
-                if let Some(path_str) = msg.strip_prefix("__BITFORGE_FOUND__") {
-                    self.bitforge_path = Some(PathBuf::from(path_str));
-                    self.overlay_message = Some(
-                        "No bitcoin_builds folder found.\n\nBitForge.app is installed — open it to build binaries?".into()
-                    );
-                } else {
-                    self.bitforge_path = None;
-                    self.overlay_message = Some(msg);
+            Message::TuningRestartStopped(restart_electrs) => {
+                // Electrs needs bitcoind's RPC: relaunch it once that answers.
+                self.pending_electrs_launch = restart_electrs;
+                self.update(Message::LaunchBitcoin)
+            }
+            Message::ToggleLaunchWith => {
+                self.launch_with_open = !self.launch_with_open;
+                self.launch_with_confirm = false;
+                Task::none()
+            }
+            Message::LaunchModeSelected(mode) => {
+                self.launch_with.mode = mode;
+                Task::none()
+            }
+            Message::RescanFromEdited(s) => {
+                if let Ok(height) = s.trim().parse() {
+                    self.launch_with.rescan_from = height;
                 }
+                self.rescan_from_edit = s;
                 Task::none()
             }
-
-            Message::DismissOverlay => {
-                self.overlay_message = None;
-                self.bitforge_path = None;
+            Message::AssumeValidEdited(s) => {
+                self.launch_with.assumevalid = s;
                 Task::none()
             }
-
-            Message::OpenBitForge(path) => {
-                let _ = std::process::Command::new("open").arg(&path).spawn();
-                self.overlay_message = None;
-                self.bitforge_path = None;
+            Message::ReviewLaunchWith => {
+                match self.launch_with.check(self.prune_active()) {
+                    Ok(()) => self.launch_with_confirm = true,
+                    Err(e) => self.overlay_message = Some(format!("Cannot launch:\n{e}")),
+                }
                 Task::none()
             }
-
-            Message::Noop => Task::none(),
-        }
-    }
-
-    // ── Internal helpers ──────────────────────────────────────────────────────
-
-    fn terminate_electrs_internal(&mut self) {
-        if let Some(mut handle) = self.electrs_handle.take() {
-            push_msg(&self.electrs_queue, "Terminating electrs…");
-            let els_q = Arc::clone(&self.electrs_queue);
-            std::thread::spawn(move || {
-                handle.terminate();
-                push_msg(&els_q, "electrs stopped.");
-            });
-        }
-        self.electrs_running = false;
-        self.electrs_synced = false;
-    }
-
-    // ── subscription ──────────────────────────────────────────────────────────
-
-    #[allow(clippy::unused_self)]
+            Message::CancelLaunchWith => {
+                self.launch_with_confirm = false;
+                Task::none()
+            }
+            Message::ConfirmLaunchWith => {
+                self.launch_with_open = false;
+                self.launch_with_confirm = false;
+                self.next_launch = Some(std::mem::take(&mut self.launch_with));
+                self.rescan_from_edit = "0".into();
+                self.update(Message::LaunchBitcoin)
+            }
+            Message::RescanFinished(result) => {
+                match result {
+                    Ok(lines) if lines.is_empty() => {
+                        push_msg(&self.bitcoin_queue, "Rescan: no wallets loaded.");
+                    }
+                    Ok(lines) => {
+                        for line in lines {
+                            push_msg(&self.bitcoin_queue, &line);
+                        }
+                    }
+                    Err(e) => push_msg(&self.bitcoin_queue, &format!("Rescan failed: {e}")),
+                }
+                Task::none()
+            }
+            Message::PruneToggled(enabled) => self.request_prune(PruneConfig {
+                enabled,
+                ..self.config.prune
+            }),
+            Message::PruneTargetEdited(s) => {
+                self.prune_target_edit = s;
+                Task::none()
+            }
+            Message::PruneTargetSubmitted => match self.prune_target_edit.trim().parse::<u64>() {
+                Ok(gib) if gib > 0 => self.request_prune(PruneConfig {
+                    target_gib: gib,
+                    ..self.config.prune
+                }),
+                _ => {
+                    self.overlay_message =
+                        Some("The prune target must be a whole number of GiB above 0.".into());
+                    Task::none()
+                }
+            },
+            Message::ConfirmPrune => {
+                if let Some(preset) = self.preset_confirm.take() {
+                    self.config.tuning.preset = preset;
+                    self.save_config_or_report();
+                    return self.scan_prune();
+                }
+                match self.prune_confirm.take() {
+                    Some(change) => self.apply_prune(change),
+                    None => Task::none(),
+                }
+            }
+            Message::CancelPrune => {
+                self.prune_confirm = None;
+                self.preset_confirm = None;
+                self.prune_target_edit = self.config.prune.target_gib.to_string();
+                Task::none()
+            }
+            Message::PruneScanned(scan) => {
+                self.prune_scan = scan;
+                Task::none()
+            }
+            Message::DeleteTxindex => {
+                if self.bitcoin_running {
+                    self.overlay_message =
+                        Some("Stop Bitcoin before deleting its transaction index.".into());
+                    return Task::none();
+                }
+                let dir = self.config.bitcoin_data_path.clone();
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || prune::delete_txindex(&dir))
+                            .await
+                            .map_err(|e| e.to_string())?
+                            .map_err(|e| format!("{e:#}"))
+                    },
+                    Message::TxindexDeleted,
+                )
+            }
+            Message::TxindexDeleted(result) => {
+                match result {
+                    Ok(()) => push_msg(&self.bitcoin_queue, "Transaction index deleted."),
+                    Err(e) => {
+                        self.overlay_message =
+                            Some(format!("Failed to delete the transaction index:\n{e}"));
+                    }
+                }
+                self.scan_prune()
+            }
+            Message::ZmqToggled(enabled) => {
+                self.config.zmq.enabled = enabled;
+                self.save_config_or_report();
+                Task::none()
+            }
+
+            Message::IncludePrereleasesToggled(enabled) => {
+                self.config.include_prereleases = enabled;
+                self.save_config_or_report();
+                Task::none()
+            }
+
+            Message::DismissOverlay => {
+                self.overlay_message = None;
+                self.bitforge_path = None;
+                self.pending_update = None;
+                Task::none()
+            }
+
+            Message::OpenBitForge(path) => {
+                let _ = std::process::Command::new("open").arg(&path).spawn();
+                self.overlay_message = None;
+                self.bitforge_path = None;
+                Task::none()
+            }
+
+            Message::Noop => Task::none(),
+        }
+    }
+
+    // ── Internal helpers ──────────────────────────────────────────────────────
+
+    /// Refresh the open tab's data.  Tab contents are only polled while
+    /// visible.
+    fn refresh_tab(&self) -> Task<Message> {
+        match self.tab {
+            Tab::Nodes | Tab::Explorer | Tab::Lookup | Tab::Psbt | Tab::Broadcast => Task::none(),
+            Tab::Peers => Task::done(Message::Peers(peers::Msg::Refresh)),
+            Tab::Mempool => Task::done(Message::Mempool(mempool::Msg::Refresh)),
+            Tab::Events => Task::done(Message::Events(events::Msg::Refresh)),
+            Tab::Wallets => Task::done(Message::Wallets(wallets::Msg::Refresh)),
+            Tab::Snapshots => Task::done(Message::Snapshots(snapshots::Msg::Refresh)),
+            // Loads the completion list once; a no-op afterwards.
+            Tab::Console => Task::done(Message::Console(console::Msg::LoadCommands)),
+        }
+    }
+
+    /// Fetch chain state now (on `RpcTick`, or when ZMQ announces a block).
+    fn poll_chain(&self) -> Task<Message> {
+        let auth = self.rpc_auth();
+        Task::perform(
+            async move {
+                rpc::get_blockchain_info(&auth)
+                    .await
+                    .map_err(|e| e.to_string())
+            },
+            Message::BlockchainInfoReceived,
+        )
+    }
+
+    /// Apply a ZMQ notification.  Blocks refresh chain state at once instead
+    /// of on the next poll; mempool changes feed the Mempool tab.
+    fn on_zmq(&mut self, event: zmq::Event) -> Task<Message> {
+        let auth = self.rpc_auth();
+        match event {
+            zmq::Event::Connected(endpoint) => {
+                push_msg(
+                    &self.bitcoin_queue,
+                    &format!("ZMQ: subscribed to {endpoint}"),
+                );
+                if !self.zmq_connected.contains(&endpoint) {
+                    self.zmq_connected.push(endpoint);
+                }
+                Task::none()
+            }
+            zmq::Event::Disconnected { endpoint, error } => {
+                push_msg(
+                    &self.bitcoin_queue,
+                    &format!("ZMQ: {endpoint} unavailable ({error}); polling continues"),
+                );
+                self.zmq_connected.retain(|e| *e != endpoint);
+                Task::none()
+            }
+            zmq::Event::BlockConnected(hash) | zmq::Event::BlockDisconnected(hash) => {
+                if !self.bitcoin_running || self.zmq_last_block.as_ref() == Some(&hash) {
+                    return Task::none();
+                }
+                self.zmq_last_block = Some(hash);
+                let tab = match self.tab {
+                    Tab::Mempool | Tab::Events => self.refresh_tab(),
+                    _ => Task::none(),
+                };
+                Task::batch([self.poll_chain(), tab])
+            }
+            zmq::Event::TxAdded(txid) => self.mempool.update(mempool::Msg::TxAdded(txid), auth),
+            zmq::Event::TxRemoved(_) => self.mempool.update(mempool::Msg::TxRemoved, auth),
+            zmq::Event::RawTx(size) => self.mempool.update(mempool::Msg::RawTx(size), auth),
+        }
+    }
+
+    /// Answer an API request from the app's state, or by running the same
+    /// message a button would.
+    fn on_api_request(&mut self, req: api::Request) -> Task<Message> {
+        use api::{Process, Response, Route};
+        let (response, task) = match req.route {
+            Route::Status => (Response::json(200, &self.api_status()), Task::none()),
+            Route::Logs { process, lines } => {
+                let buf = match process {
+                    Process::Bitcoind => &self.bitcoin_lines,
+                    Process::Electrs => &self.electrs_lines,
+                };
+                let tail = &buf[buf.len().saturating_sub(lines)..];
+                (
+                    Response::json(200, &serde_json::json!({ "lines": tail })),
+                    Task::none(),
+                )
+            }
+            Route::Metrics => (Response::metrics(self.render_metrics()), Task::none()),
+            Route::Launch(Process::Bitcoind) => self.api_action(Self::launch_bitcoin),
+            Route::Launch(Process::Electrs) => self.api_action(Self::launch_electrs),
+            Route::Shutdown(Process::Bitcoind) => self.api_action(Self::shutdown_both),
+            Route::Shutdown(Process::Electrs) => self.api_action(Self::shutdown_electrs),
+            Route::UpdateCheck | Route::UpdateApply if self.api_update_waiter.is_some() => (
+                Response::error(409, "an update request is already in progress"),
+                Task::none(),
+            ),
+            Route::UpdateApply if self.pending_update.is_none() => (
+                Response::error(409, "no update plan is pending; POST /update/check first"),
+                Task::none(),
+            ),
+            // Answered by `answer_api_update` once the result arrives.
+            Route::UpdateCheck => {
+                self.api_update_waiter = Some(req.responder);
+                return self.update(Message::UpdateBinaries);
+            }
+            Route::UpdateApply => {
+                self.api_update_waiter = Some(req.responder);
+                return self.update(Message::ApplyUpdate);
+            }
+        };
+        req.responder.send(response);
+        task
+    }
+
+    /// Run a launch / shutdown; a refusal is a `409` with the reason, and
+    /// shows in the overlay as it would for the button.
+    fn api_action(
+        &mut self,
+        action: fn(&mut Self) -> Result<Task<Message>, String>,
+    ) -> (api::Response, Task<Message>) {
+        match action(self) {
+            Ok(task) => (api::Response::json(200, &self.api_status()), task),
+            Err(e) => {
+                let response = api::Response::error(409, &e);
+                self.overlay_message = Some(e);
+                (response, Task::none())
+            }
+        }
+    }
+
+    /// Reply to a pending `/update/*` request with the overlay text; a
+    /// failed update is a `500`.
+    fn answer_api_update(&mut self, ok: bool) {
+        if let Some(responder) = self.api_update_waiter.take() {
+            responder.send(api::Response::json(
+                if ok { 200 } else { 500 },
+                &serde_json::json!({
+                    "message": self.overlay_message,
+                    "plan_pending": self.pending_update.is_some(),
+                }),
+            ));
+        }
+    }
+
+    /// `GET /status` body.
+    fn api_status(&self) -> serde_json::Value {
+        let chain = self.chain_info.as_ref().filter(|_| self.bitcoin_running);
+        serde_json::json!({
+            "bitcoind": {
+                "running": self.bitcoin_running,
+                "synced": self.bitcoin_synced,
+                "ready": self.bitcoin_running && self.bitcoin_synced,
+                "chain": chain.map(|c| &c.chain),
+                "blocks": chain.map(|c| c.blocks),
+                "headers": chain.map(|c| c.headers),
+                "verification_progress": chain.map(|c| c.verification_progress),
+                "initial_block_download": chain.map(|c| c.initial_block_download),
+                "best_block_hash": chain.map(|c| &c.best_block_hash),
+            },
+            "electrs": {
+                "running": self.electrs_running,
+                "synced": self.electrs_synced,
+                "ready": self.electrs_running && self.electrs_synced,
+            },
+            "update_plan_pending": self.pending_update.is_some(),
+        })
+    }
+
+    /// `GET /metrics` body.
+    fn render_metrics(&self) -> String {
+        let chain = self.chain_info.as_ref().filter(|_| self.bitcoin_running);
+        let polled = if self.bitcoin_running {
+            self.metrics_polled.clone()
+        } else {
+            metrics::Polled::default()
+        };
+        metrics::render(&metrics::NodeState {
+            bitcoind_up: self.bitcoin_running,
+            electrs_up: self.electrs_running,
+            blocks: chain.map(|c| c.blocks),
+            headers: chain.map(|c| c.headers),
+            verification_progress: chain.map(|c| c.verification_progress),
+            polled,
+            bitcoind_restarts: self.bitcoin_launches.saturating_sub(1),
+            electrs_restarts: self.electrs_launches.saturating_sub(1),
+            disks: vec![
+                ("bitcoin", self.config.bitcoin_data_path.clone()),
+                ("electrs", self.config.electrs_data_path.clone()),
+            ],
+        })
+    }
+
+    /// Feed the best block to the tip monitor: check a replaced tip for a
+    /// reorg and log stalls and resumptions.
+    fn track_tip(&mut self, info: &BlockchainInfo) -> Task<Message> {
+        if info.best_block_hash.is_empty() {
+            return Task::none();
+        }
+        let now = unix_now();
+        let tip = Tip {
+            height: info.blocks,
+            hash: info.best_block_hash.clone(),
+        };
+        let obs = self.tip_monitor.observe(tip.clone(), now);
+        let mut tasks = Vec::new();
+        if let Some(secs) = obs.resumed_after {
+            tasks.push(self.record_event(history::Entry::now(
+                history::Kind::Resumed,
+                format!(
+                    "New block at height {} after {} without one",
+                    tip.height,
+                    format_duration(secs)
+                ),
+            )));
+        }
+        if let Some(old) = obs.replaced {
+            // Polling sees one new tip per tick, however many blocks arrived.
+            // During IBD that would be a script every poll for days, so the
+            // hook waits until the node has caught up.
+            if !info.initial_block_download {
+                tasks.push(
+                    self.run_hook(
+                        hooks::Invocation::new(hooks::Event::NewBlock)
+                            .env("BNM_BLOCK_HEIGHT", tip.height.to_string())
+                            .env("BNM_BLOCK_HASH", tip.hash.clone())
+                            .arg(tip.hash.clone()),
+                        &self.bitcoin_queue,
+                    ),
+                );
+            }
+            let auth = self.rpc_auth();
+            tasks.push(Task::perform(
+                async move {
+                    chain_monitor::detect_reorg(&auth, old, tip)
+                        .await
+                        .map_err(|e| e.to_string())
+                },
+                Message::ReorgChecked,
+            ));
+        }
+        // Header pre-sync holds the tip still for a long time; only a synced
+        // node is expected to keep up with the network.
+        if !info.initial_block_download {
+            let stall_secs = self.config.stall_minutes * 60;
+            if let Some(secs) = self.tip_monitor.check_stall(now, stall_secs) {
+                let tip = self.tip_monitor.tip().map_or(0, |t| t.height);
+                tasks.push(self.record_event(history::Entry::now(
+                    history::Kind::Stall,
+                    format!(
+                        "No new block for {} (tip at height {tip})",
+                        format_duration(secs)
+                    ),
+                )));
+            }
+        }
+        Task::batch(tasks)
+    }
+
+    /// Show a chain event in the bitcoind terminal and append it to the
+    /// history.
+    fn record_event(&self, entry: history::Entry) -> Task<Message> {
+        push_msg(
+            &self.bitcoin_queue,
+            &format!("{}: {}", entry.kind, entry.message),
+        );
+        Task::perform(
+            async move {
+                let saved = entry.clone();
+                tokio::task::spawn_blocking(move || history::append(&saved))
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .map_err(|e| format!("{e:#}"))?;
+                Ok(entry)
+            },
+            Message::EventRecorded,
+        )
+    }
+
+    fn rpc_auth(&self) -> RpcAuth {
+        RpcAuth::from_data_dir(&self.config.bitcoin_data_path)
+    }
+
+    /// Evaluate the alert rules against the current state.
+    fn check_alerts(&mut self) -> Task<Message> {
+        if self.config.alerts.rules.is_empty() {
+            return Task::none();
+        }
+        let now = unix_now();
+        let chain = self.chain_info.as_ref().filter(|_| self.bitcoin_running);
+        let snapshot = alerts::Snapshot {
+            now,
+            bitcoind_running: self.bitcoin_running,
+            bitcoind_exited: self.bitcoin_exited,
+            electrs_exited: self.electrs_exited,
+            synced: self.bitcoin_synced,
+            tip_age: chain
+                .filter(|c| !c.initial_block_download)
+                .and_then(|_| self.tip_monitor.age(now)),
+            peers: self.metrics_polled.peers.filter(|_| self.bitcoin_running),
+            disks: vec![
+                (
+                    "bitcoin",
+                    metrics::disk_free_bytes(&self.config.bitcoin_data_path),
+                ),
+                (
+                    "electrs",
+                    metrics::disk_free_bytes(&self.config.electrs_data_path),
+                ),
+            ],
+        };
+        let fired = self
+            .alert_engine
+            .evaluate(&self.config.alerts.rules, &snapshot);
+        Task::batch(fired.into_iter().map(|alert| self.send_alert(alert)))
+    }
+
+    /// Log an alert and run its actions.
+    fn send_alert(&self, alert: Alert) -> Task<Message> {
+        let event = self.record_event(history::Entry::now(history::Kind::Alert, alert.summary()));
+        let AlertsConfig {
+            webhook_url,
+            command,
+            ..
+        } = self.config.alerts.clone();
+        if webhook_url.is_empty() && command.is_empty() {
+            return event;
+        }
+        let deliver = Task::perform(
+            async move {
+                alerts::deliver(alert, webhook_url, command)
+                    .await
+                    .map_err(|e| format!("{e:#}"))
+            },
+            Message::AlertDelivered,
+        );
+        Task::batch([event, deliver])
+    }
+
+    /// Point the config at `dst` for `which` and log `what` with the path.
+    fn switch_data_path(
+        &mut self,
+        which: migrate::Which,
+        dst: PathBuf,
+        what: &str,
+    ) -> Task<Message> {
+        let shown = dst.to_string_lossy().into_owned();
+        let (queue, task) = match which {
+            migrate::Which::BitcoinData => {
+                self.config.bitcoin_data_path = dst;
+                self.bitcoin_data_path_edit.clone_from(&shown);
+                (&self.bitcoin_queue, self.scan_prune())
+            }
+            migrate::Which::ElectrsDb => {
+                self.config.electrs_data_path = dst;
+                self.electrs_data_path_edit.clone_from(&shown);
+                (&self.electrs_queue, Task::none())
+            }
+        };
+        push_msg(queue, &format!("{what} {shown}"));
+        self.save_config_or_report();
+        task
+    }
+
+    /// Persist the config, surfacing failures in the overlay.
+    fn save_config_or_report(&mut self) {
+        if let Err(e) = self.config.save() {
+            self.overlay_message = Some(format!("Failed to save settings:\n{e}"));
+        }
+    }
+
+    fn add_update_source(&mut self, source: UpdateSourceConfig) {
+        if !self.config.update_sources.contains(&source) {
+            self.config.update_sources.push(source);
+            self.check_update_sources();
+            self.save_config_or_report();
+        }
+    }
+
+    /// Inspect the binaries in the edited folder off the UI thread.
+    fn check_binaries(&self) -> Task<Message> {
+        let dir = self.binaries_path_edit.clone();
+        Task::perform(
+            async move {
+                let path = PathBuf::from(&dir);
+                let checks = tokio::task::spawn_blocking(move || inspect_binaries(&path))
+                    .await
+                    .unwrap_or_default();
+                (dir, checks)
+            },
+            |(dir, checks)| Message::BinariesChecked(dir, checks),
+        )
+    }
+
+    /// Refresh `update_sources_available` for the sources panel.
+    fn check_update_sources(&mut self) {
+        self.update_sources_available = self
+            .config
+            .update_sources
+            .iter()
+            .map(|source| match source {
+                UpdateSourceConfig::LocalDir { path } => path.is_dir(),
+                UpdateSourceConfig::HttpMirror { .. } => true,
+            })
+            .collect();
+    }
+
+    /// Overlay text for a freshly computed plan; keeps it pending if it
+    /// would change anything.
+    fn describe_update_plan(&mut self, plan: UpdatePlan) -> String {
+        let mut msg = format!("Update plan\n\n{}", plan.summary());
+        if plan.has_changes() {
+            let (stop_btc, stop_els) = self.processes_affected_by(&plan);
+            if stop_btc || stop_els {
+                let names = match (stop_btc, stop_els) {
+                    (true, true) => "bitcoind and electrs",
+                    (true, false) => "bitcoind",
+                    _ => "electrs",
+                };
+                let _ = write!(
+                    msg,
+                    "\n\n{names} will be stopped, updated and restarted automatically."
+                );
+            }
+            self.pending_update = Some(plan);
+        } else {
+            msg.push_str("\n\nNothing would change.");
+        }
+        msg
+    }
+
+    /// Which running processes must be stopped to apply `plan`, as
+    /// `(bitcoind, electrs)`.  Replacing bitcoind also takes electrs down with it.
+    fn processes_affected_by(&self, plan: &UpdatePlan) -> (bool, bool) {
+        let bitcoin = self.bitcoin_running && plan.changes(Component::Bitcoin);
+        let electrs = self.electrs_running && (bitcoin || plan.changes(Component::Electrs));
+        (bitcoin, electrs)
+    }
+
+    // ── Launch / shutdown ─────────────────────────────────────────────────────
+    //
+    // Each returns why it refused, for the overlay or a `409` from the API.
+
+    /// Launch bitcoind with the tuning, hook and one-shot arguments.
+    fn launch_bitcoin(&mut self) -> Result<Task<Message>, String> {
+        // One-shot options never outlive the launch they were meant for.
+        let one_shot = self.next_launch.take();
+        if self.bitcoin_running {
+            return Err("Bitcoin is already running.".into());
+        }
+        if self.migrate.copying() == Some(migrate::Which::BitcoinData) {
+            return Err("The Bitcoin data directory is being copied.".into());
+        }
+        // Ensure bitcoin.conf exists
+        let _ = rpc::ensure_bitcoin_conf(&self.config.bitcoin_data_path);
+        if let Err(e) = zmq::write_conf(&self.config.bitcoin_data_path, &self.config.zmq) {
+            push_msg(
+                &self.bitcoin_queue,
+                &format!("ZMQ settings not written: {e:#}"),
+            );
+        }
+
+        self.machine = tuning::Machine::detect(&self.config.bitcoin_data_path);
+        let tuning_args = self.tuning_settings().args();
+        let mut args = tuning_args.clone();
+        args.extend(hooks::bitcoind_args(&self.config.hooks));
+        if let Some(shot) = &one_shot {
+            args.extend(shot.args());
+            push_msg(
+                &self.bitcoin_queue,
+                &format!(
+                    "One-shot launch: {} (not kept for later launches)",
+                    shot.mode
+                ),
+            );
+        }
+        self.pending_rescan = one_shot
+            .filter(|shot| shot.mode == launch_mode::Mode::Rescan)
+            .map(|shot| shot.rescan_from);
+        match process_manager::launch_bitcoind(
+            &self.config.binaries_path,
+            &self.config.bitcoin_data_path,
+            &args,
+            Arc::clone(&self.bitcoin_queue),
+        ) {
+            Ok(handle) => {
+                self.launched_tuning = tuning_args;
+                self.bitcoin_handle = Some(handle);
+                self.bitcoin_running = true;
+                self.bitcoin_synced = false;
+                self.bitcoin_exited = false;
+                self.bitcoin_stop_requested = false;
+                self.bitcoin_launches += 1;
+                self.bitcoin_usage.reset();
+                self.tip_monitor.reset();
+                self.chain_info = None;
+                self.metrics_polled = metrics::Polled::default();
+                self.zmq_endpoints = zmq::subscriptions(&self.config.zmq);
+                self.zmq_connected.clear();
+                self.zmq_last_block = None;
+                self.mempool.reset_live();
+                Ok(self.run_hook(
+                    hooks::Invocation::new(hooks::Event::NodeStarted).process("bitcoind"),
+                    &self.bitcoin_queue,
+                ))
+            }
+            Err(e) => {
+                push_msg(&self.bitcoin_queue, &format!("Launch error: {e}"));
+                Err(format!("Failed to launch Bitcoin:\n{e}"))
+            }
+        }
+    }
+
+    /// Launch electrs against the running bitcoind.
+    fn launch_electrs(&mut self) -> Result<Task<Message>, String> {
+        if self.electrs_running {
+            return Err("Electrs is already running.".into());
+        }
+        if self.migrate.copying() == Some(migrate::Which::ElectrsDb) {
+            return Err("The Electrs DB directory is being copied.".into());
+        }
+        if !self.bitcoin_running {
+            return Err("Bitcoin must be running before starting Electrs.\n\
+                 Launch Bitcoin first and wait for the Running indicator."
+                .into());
+        }
+        if self.prune_active() {
+            return Err("Electrs needs an unpruned node: it indexes every block.\n\
+                 Turn pruning off (bitcoind then re-downloads the chain with \
+                 -reindex) or point Electrs at a full node."
+                .into());
+        }
+        match process_manager::launch_electrs(
+            &self.config.binaries_path,
+            &self.config.bitcoin_data_path,
+            &self.config.electrs_data_path,
+            Arc::clone(&self.electrs_queue),
+        ) {
+            Ok(handle) => {
+                self.electrs_handle = Some(handle);
+                self.electrs_running = true;
+                self.electrs_synced = false;
+                self.electrs_exited = false;
+                self.electrs_launches += 1;
+                self.electrs_usage.reset();
+                Ok(self.run_hook(
+                    hooks::Invocation::new(hooks::Event::NodeStarted).process("electrs"),
+                    &self.electrs_queue,
+                ))
+            }
+            Err(e) => {
+                push_msg(&self.electrs_queue, &format!("Launch error: {e}"));
+                Err(format!("Failed to launch Electrs:\n{e}"))
+            }
+        }
+    }
+
+    /// Stop electrs, then bitcoind over RPC.
+    fn shutdown_both(&mut self) -> Result<Task<Message>, String> {
+        if !self.bitcoin_running && self.electrs_handle.is_none() {
+            return Err("Neither bitcoind nor electrs is running.".into());
+        }
+        self.pending_electrs_launch = false;
+        let mut tasks = vec![self.terminate_electrs_internal()];
+
+        if self.bitcoin_running {
+            let auth = RpcAuth::from_data_dir(&self.config.bitcoin_data_path);
+            let btc_q = Arc::clone(&self.bitcoin_queue);
+            push_msg(&btc_q, "Sending stop via RPC…");
+
+            // Move child out so we can wait on it in a background thread
+            if let Some(handle) = self.bitcoin_handle.take() {
+                self.bitcoin_running = false;
+                self.bitcoin_synced = false;
+                std::thread::spawn(move || stop_bitcoind_blocking(handle, &auth, &btc_q));
+                tasks.push(self.run_hook(
+                    hooks::Invocation::new(hooks::Event::NodeStopped).process("bitcoind"),
+                    &self.bitcoin_queue,
+                ));
+            }
+        }
+        Ok(Task::batch(tasks))
+    }
+
+    fn shutdown_electrs(&mut self) -> Result<Task<Message>, String> {
+        if self.electrs_handle.is_none() {
+            return Err("Electrs is not running.".into());
+        }
+        Ok(self.terminate_electrs_internal())
+    }
+
+    /// Show a refusal from one of the above in the overlay.
+    fn or_overlay(&mut self, result: Result<Task<Message>, String>) -> Task<Message> {
+        result.unwrap_or_else(|e| {
+            self.overlay_message = Some(e);
+            Task::none()
+        })
+    }
+
+    /// Stop electrs in the background; returns its stop hook.
+    fn terminate_electrs_internal(&mut self) -> Task<Message> {
+        let mut task = Task::none();
+        if let Some(mut handle) = self.electrs_handle.take() {
+            push_msg(&self.electrs_queue, "Terminating electrs…");
+            let els_q = Arc::clone(&self.electrs_queue);
+            std::thread::spawn(move || {
+                handle.terminate();
+                push_msg(&els_q, "electrs stopped.");
+            });
+            if self.electrs_running {
+                task = self.run_hook(
+                    hooks::Invocation::new(hooks::Event::NodeStopped).process("electrs"),
+                    &self.electrs_queue,
+                );
+            }
+        }
+        self.electrs_running = false;
+        self.electrs_synced = false;
+        task
+    }
+
+    /// Run the script configured for the hook's event, if any.
+    fn run_hook(&self, hook: hooks::Invocation, queue: &OutputQueue) -> Task<Message> {
+        let Some(script) = hooks::script_for(&self.config.hooks, hook.event) else {
+            return Task::none();
+        };
+        let hook = hook.env(
+            "BNM_DATA_DIR",
+            self.config.bitcoin_data_path.to_string_lossy(),
+        );
+        Task::perform(
+            hooks::run(script.to_owned(), hook, Arc::clone(queue)),
+            |()| Message::Noop,
+        )
+    }
+
+    // ── subscription ──────────────────────────────────────────────────────────
+
     pub fn subscription(&self) -> Subscription<Message> {
-        Subscription::batch([
+        let mut subs = vec![
             time::every(Duration::from_millis(100)).map(|_| Message::OutputTick),
             time::every(Duration::from_secs(5)).map(|_| Message::RpcTick),
-        ])
+        ];
+        if self.bitcoin_running || self.electrs_running {
+            subs.push(time::every(Duration::from_secs(2)).map(|_| Message::ResourceTick));
+        }
+        let api = &self.config.api;
+        if api.enabled && !api.token.is_empty() {
+            subs.push(
+                Subscription::run_with_id(
+                    ("api", api.port, api.token.clone()),
+                    api::serve(api.port, api.token.clone()),
+                )
+                .map(Message::Api),
+            );
+        }
+        if self.bitcoin_running {
+            for (endpoint, topics) in &self.zmq_endpoints {
+                subs.push(
+                    Subscription::run_with_id(
+                        (endpoint.clone(), topics.clone()),
+                        zmq::events(endpoint.clone(), topics.clone()),
+                    )
+                    .map(Message::Zmq),
+                );
+            }
+        }
+        if self.tab == Tab::Console {
+            // The command line ignores these keys, so they arrive here.
+            subs.push(keyboard::on_key_press(|key, _| match key {
+                Key::Named(key::Named::ArrowUp) => {
+                    Some(Message::Console(console::Msg::HistoryPrev))
+                }
+                Key::Named(key::Named::ArrowDown) => {
+                    Some(Message::Console(console::Msg::HistoryNext))
+                }
+                Key::Named(key::Named::Tab) => Some(Message::Console(console::Msg::Complete)),
+                _ => None,
+            }));
+        }
+        Subscription::batch(subs)
     }
 
     // ── view ──────────────────────────────────────────────────────────────────
 
     pub fn view(&self) -> Element<'_, Message> {
+        let page: Element<'_, Message> = match self.tab {
+            Tab::Nodes => column![self.view_paths_panel(), self.view_node_panels()]
+                .height(Length::Fill)
+                .into(),
+            Tab::Peers => self.peers.view(self.bitcoin_running),
+            Tab::Mempool => self.mempool.view(self.bitcoin_running),
+            Tab::Explorer => self.explorer.view(self.bitcoin_running, self.block_height),
+            Tab::Events => self.events.view(self.bitcoin_running),
+            Tab::Lookup => self.lookup.view(self.electrs_running),
+            Tab::Wallets => self.wallets.view(self.bitcoin_running),
+            Tab::Psbt => self.psbt.view(self.bitcoin_running),
+            Tab::Broadcast => self.broadcast.view(self.bitcoin_running),
+            Tab::Snapshots => self
+                .snapshots
+                .view(self.bitcoin_running, self.bitcoin_pruned),
+            Tab::Console => self.console.view(self.bitcoin_running),
+        };
         let content = column![
             self.view_toolbar(),
             horizontal_rule(),
-            self.view_paths_panel(),
-            self.view_node_panels(),
+            page,
             horizontal_rule(),
             self.view_bottom_bar(),
         ]
@@ -733,24 +2400,41 @@ impl App {
                     })
                     .into()
             },
-            |msg| view_overlay(msg, self.bitforge_path.clone()),
+            |msg| view_overlay(msg, self.overlay_actions()),
         )
     }
 
+    /// Extra buttons shown next to the overlay's dismiss button.
+    fn overlay_actions(&self) -> Vec<Element<'_, Message>> {
+        let mut actions = Vec::new();
+        if let Some(path) = &self.bitforge_path {
+            actions.push(
+                styled_button("Open BitForge", ButtonStyle::Confirm)
+                    .on_press(Message::OpenBitForge(path.clone()))
+                    .into(),
+            );
+        }
+        if let Some(plan) = &self.pending_update {
+            let (stop_btc, stop_els) = self.processes_affected_by(plan);
+            let label = if stop_btc || stop_els {
+                "Stop, Update & Restart"
+            } else {
+                "Apply Update"
+            };
+            actions.push(
+                styled_button(label, ButtonStyle::Confirm)
+                    .on_press(Message::ApplyUpdate)
+                    .into(),
+            );
+        }
+        actions
+    }
+
     // ── Toolbar ───────────────────────────────────────────────────────────────
 
     fn view_toolbar(&self) -> Element<'_, Message> {
         let height_text: String = if self.block_height > 0 {
-            // Format with thousands separators: 895234 → "895,234"
-            let s = self.block_height.to_string();
-            let mut out = String::with_capacity(s.len() + s.len() / 3);
-            for (i, ch) in s.chars().rev().enumerate() {
-                if i > 0 && i % 3 == 0 {
-                    out.push(',');
-                }
-                out.push(ch);
-            }
-            out.chars().rev().collect::<String>()
+            format_thousands(self.block_height)
         } else {
             "Connecting…".to_owned()
         };
@@ -767,12 +2451,31 @@ impl App {
         ]
         .spacing(2);
 
+        let prerelease_toggle =
+            styled_checkbox("Include pre-releases", self.config.include_prereleases)
+                .on_toggle(Message::IncludePrereleasesToggled);
+
         let update_btn = styled_button("Update Binaries…", ButtonStyle::Secondary)
             .on_press(Message::UpdateBinaries);
 
-        let toolbar_row = row![block_stat, Space::with_width(Length::Fill), update_btn,]
-            .align_y(Alignment::Center)
-            .padding(Padding::from([0, 16]));
+        let tabs = row(Tab::ALL.map(|tab| {
+            tab_button(tab.label(), tab == self.tab)
+                .on_press(Message::SelectTab(tab))
+                .into()
+        }))
+        .spacing(4);
+
+        let toolbar_row = row![
+            block_stat,
+            Space::with_width(24),
+            tabs,
+            Space::with_width(Length::Fill),
+            prerelease_toggle,
+            Space::with_width(12),
+            update_btn,
+        ]
+        .align_y(Alignment::Center)
+        .padding(Padding::from([0, 16]));
 
         container(toolbar_row)
             .width(Length::Fill)
@@ -814,41 +2517,57 @@ impl App {
         }
 
         let rows = column![
+            self.discover.view(
+                &self.config.bitcoin_data_path,
+                &self.config.electrs_data_path
+            ),
             path_row(
                 "Binaries Folder",
                 &self.binaries_path_edit,
                 Message::BinariesPathChanged,
                 Message::BrowseBinaries,
-                std::path::Path::new(&self.binaries_path_edit).exists(),
+                self.binaries_indicator(),
             ),
+            self.view_binary_checks(),
             path_row(
                 "Bitcoin Data Directory",
                 &self.bitcoin_data_path_edit,
                 Message::BitcoinDataPathChanged,
                 Message::BrowseBitcoinData,
-                std::path::Path::new(&self.bitcoin_data_path_edit).exists(),
+                exists_color(&self.bitcoin_data_path_edit),
             ),
             path_row(
                 "Electrs DB Directory",
                 &self.electrs_data_path_edit,
                 Message::ElectrsDataPathChanged,
                 Message::BrowseElectrsData,
-                std::path::Path::new(&self.electrs_data_path_edit).exists(),
+                exists_color(&self.electrs_data_path_edit),
             ),
+            self.view_zmq_row(),
+            self.view_api_row(),
+            self.view_tuning_row(),
+            self.view_prune_row(),
             row![
                 text("Changes take effect on the next node launch.")
                     .size(10)
                     .color(TEXT_TER),
                 Space::with_width(Length::Fill),
+                styled_button("Find Existing…", ButtonStyle::Secondary)
+                    .on_press(Message::Discover(discover::Msg::Scan)),
+                Space::with_width(6),
+                styled_button("Migrate Data…", ButtonStyle::Secondary)
+                    .on_press(Message::Migrate(migrate::Msg::Toggle)),
+                Space::with_width(6),
                 styled_button("Save Paths", ButtonStyle::Confirm).on_press(Message::SavePaths),
             ]
             .align_y(Alignment::Center)
             .padding(Padding::from([8, 0])),
+            self.migrate.view(),
         ]
         .spacing(4)
         .padding(Padding::from([0, 20]));
 
-        let body = column![header, rows].padding(Padding {
+        let body = column![header, rows, self.view_update_sources()].padding(Padding {
             top: 0.0,
             right: 0.0,
             bottom: 4.0,
@@ -864,26 +2583,579 @@ impl App {
             .into()
     }
 
+    /// ZMQ toggle with the configured endpoints and whether they are live.
+    fn view_zmq_row(&self) -> Element<'_, Message> {
+        let zmq = &self.config.zmq;
+        let endpoints: Vec<String> = [
+            ("hashblock", &zmq.hashblock),
+            ("rawtx", &zmq.rawtx),
+            ("sequence", &zmq.sequence),
+        ]
+        .iter()
+        .filter(|(_, e)| !e.trim().is_empty())
+        .map(|(topic, e)| format!("{topic} {e}"))
+        .collect();
+        let (status, color) = match (self.bitcoin_running, self.zmq_endpoints.len()) {
+            (false, _) | (true, 0) => (String::new(), OFF),
+            (true, n) if self.zmq_connected.len() == n => ("subscribed".to_owned(), GREEN),
+            (true, n) => (
+                format!("{} of {n} endpoints subscribed", self.zmq_connected.len()),
+                MAC_ORG,
+            ),
+        };
+        row![
+            styled_checkbox("ZMQ notifications", zmq.enabled).on_toggle(Message::ZmqToggled),
+            Space::with_width(12),
+            text(endpoints.join("  ·  "))
+                .size(10)
+                .font(Font::MONOSPACE)
+                .color(TEXT_TER),
+            Space::with_width(Length::Fill),
+            text(status).size(10).color(color),
+        ]
+        .align_y(Alignment::Center)
+        .padding(Padding::from([4, 0]))
+        .into()
+    }
+
+    /// HTTP API toggle, its URL (or bind error) and the token.
+    fn view_api_row(&self) -> Element<'_, Message> {
+        let api = &self.config.api;
+        let (status, color) = match (&self.api_state, api.enabled) {
+            (_, false) => (format!("127.0.0.1:{}", api.port), TEXT_TER),
+            (Some(Ok(url)), true) => (format!("{url}  ·  Authorization: Bearer <token>"), GREEN),
+            (Some(Err(e)), true) => (e.clone(), MAC_RED),
+            (None, true) => ("Starting…".to_owned(), TEXT_TER),
+        };
+        row![
+            styled_checkbox("HTTP API", api.enabled).on_toggle(Message::ApiToggled),
+            Space::with_width(12),
+            text(status).size(10).font(Font::MONOSPACE).color(color),
+            Space::with_width(Length::Fill),
+            styled_button("Copy Token", ButtonStyle::Secondary)
+                .on_press_maybe((!api.token.is_empty()).then_some(Message::CopyApiToken)),
+        ]
+        .align_y(Alignment::Center)
+        .padding(Padding::from([4, 0]))
+        .into()
+    }
+
+    /// Preset picker, IBD boost and the arguments they produce.
+    fn view_tuning_row(&self) -> Element<'_, Message> {
+        let args = self.tuning_settings().args();
+        let stale = self.bitcoin_running && args != self.launched_tuning;
+        let m = &self.machine;
+        let main = row![
+            text("Tuning").size(12).color(TEXT_SEC),
+            Space::with_width(8),
+            pick_list(
+                Preset::ALL,
+                Some(self.config.tuning.preset),
+                Message::TuningPresetSelected
+            )
+            .text_size(12),
+            Space::with_width(12),
+            styled_checkbox("IBD boost", self.config.tuning.ibd_boost)
+                .on_toggle(Message::IbdBoostToggled),
+            Space::with_width(12),
+            text(if args.is_empty() {
+                "no overrides".to_owned()
+            } else {
+                args.join(" ")
+            })
+            .size(10)
+            .font(Font::MONOSPACE)
+            .color(TEXT_TER),
+            Space::with_width(Length::Fill),
+            text(format!(
+                "{} RAM · {} cores · {} free",
+                format_bytes(m.ram_bytes),
+                m.cpus,
+                format_bytes(m.free_disk_bytes)
+            ))
+            .size(10)
+            .color(TEXT_TER),
+        ]
+        .push_maybe(stale.then(|| Space::with_width(12)))
+        .push_maybe(stale.then(|| {
+            styled_button("Restart to Apply", ButtonStyle::Warning)
+                .on_press(Message::ReviewTuningRestart)
+        }))
+        .align_y(Alignment::Center)
+        .padding(Padding::from([4, 0]));
+
+        if !(stale && self.tuning_restart_confirm) {
+            return main.into();
+        }
+        let electrs = if self.electrs_running {
+            " Electrs is stopped and launched again after it."
+        } else {
+            ""
+        };
+        column![
+            main,
+            row![
+                text(format!(
+                    "bitcoind stops, writing its cache to disk (this can take minutes), and \
+                     launches again with the arguments above.{electrs}"
+                ))
+                .size(11)
+                .color(MAC_ORG),
+                Space::with_width(Length::Fill),
+                styled_button("Cancel", ButtonStyle::Secondary)
+                    .on_press(Message::CancelTuningRestart),
+                Space::with_width(6),
+                styled_button("Restart", ButtonStyle::Warning)
+                    .on_press(Message::ConfirmTuningRestart),
+            ]
+            .align_y(Alignment::Center)
+            .padding(Padding::from([4, 0])),
+        ]
+        .into()
+    }
+
+    /// Preset values, with the prune setting taking over `prune`.
+    fn tuning_settings(&self) -> tuning::Settings {
+        self.tuning_settings_for(self.config.tuning.preset)
+    }
+
+    fn tuning_settings_for(&self, preset: Preset) -> tuning::Settings {
+        let mut settings =
+            tuning::Settings::compute(preset, &self.machine, self.config.tuning.ibd_boost);
+        if let Some(mib) = prune::target_mib(&self.config.prune) {
+            settings.prune_mib = Some(mib);
+        }
+        settings
+    }
+
+    /// Prune toggle and target, disk estimate, and the txindex left over
+    /// from a full node.  A change awaiting confirmation adds a second row.
+    fn view_prune_row(&self) -> Element<'_, Message> {
+        let scan = &self.prune_scan;
+        let settings = self.tuning_settings();
+        let (estimate, color) = match settings.prune_mib {
+            Some(mib) => {
+                let mut s = format!(
+                    "≈ {} on disk (full node ≈ {})",
+                    format_bytes(scan.pruned_total(mib)),
+                    format_bytes(prune::FULL_BLOCKS_ESTIMATE)
+                );
+                let freed = scan.reclaimable(mib);
+                if freed > 0 {
+                    let _ = write!(s, "  ·  ≈ {} can be freed", format_bytes(freed));
+                }
+                if !self.config.prune.enabled {
+                    s.insert_str(0, "set by the tuning preset  ·  ");
+                }
+                (s, TEXT_TER)
+            }
+            None if scan.conf_prune_mib.is_some() => (
+                "bitcoin.conf sets prune: Electrs cannot run".to_owned(),
+                MAC_ORG,
+            ),
+            None if self.bitcoin_pruned => (
+                "blocks on disk are pruned: bitcoind needs -reindex to run unpruned".to_owned(),
+                MAC_ORG,
+            ),
+            None => (
+                format!(
+                    "full node ≈ {} of blocks",
+                    format_bytes(prune::FULL_BLOCKS_ESTIMATE)
+                ),
+                TEXT_TER,
+            ),
+        };
+        let can_delete_txindex =
+            settings.prune_mib.is_some() && scan.txindex_bytes.is_some() && !self.bitcoin_running;
+        let edited = self.prune_target_edit.trim() != self.config.prune.target_gib.to_string();
+        let main = row![
+            styled_checkbox("Prune", self.config.prune.enabled).on_toggle(Message::PruneToggled),
+            Space::with_width(12),
+            text_input("10", &self.prune_target_edit)
+                .on_input(Message::PruneTargetEdited)
+                .on_submit(Message::PruneTargetSubmitted)
+                .padding(Padding::from([2, 6]))
+                .font(Font::MONOSPACE)
+                .size(11)
+                .width(60),
+            text(" GiB of blocks").size(11).color(TEXT_SEC),
+            Space::with_width(12),
+            text(estimate).size(10).color(color),
+        ]
+        .push_maybe(edited.then(|| {
+            text("  ·  press Enter to apply the target")
+                .size(10)
+                .color(MAC_ORG)
+        }))
+        .push(Space::with_width(Length::Fill))
+        .push(
+            styled_button("Delete txindex", ButtonStyle::Destructive)
+                .on_press_maybe(can_delete_txindex.then_some(Message::DeleteTxindex)),
+        )
+        .align_y(Alignment::Center)
+        .padding(Padding::from([4, 0]));
+
+        let pending = match (&self.prune_confirm, self.preset_confirm) {
+            (Some(change), _) => prune::target_mib(change),
+            (None, Some(preset)) => self.tuning_settings_for(preset).prune_mib,
+            (None, None) => return main.into(),
+        };
+        let warning = match pending {
+            Some(mib) => format!(
+                "The next bitcoind start deletes ≈ {} of old block files. Electrs stops \
+                 working, and going back to a full node downloads the whole chain again.",
+                format_bytes(scan.blocks_bytes.saturating_sub(mib * (1 << 20)))
+            ),
+            None => "The blocks on disk are pruned. bitcoind refuses to start unpruned \
+                     until it is relaunched with -reindex, which downloads the whole \
+                     chain again."
+                .to_owned(),
+        };
+        column![
+            main,
+            row![
+                text(warning).size(11).color(MAC_ORG),
+                Space::with_width(Length::Fill),
+                styled_button("Cancel", ButtonStyle::Secondary).on_press(Message::CancelPrune),
+                Space::with_width(6),
+                styled_button("Confirm", ButtonStyle::Warning).on_press(Message::ConfirmPrune),
+            ]
+            .align_y(Alignment::Center)
+            .padding(Padding::from([4, 0])),
+        ]
+        .into()
+    }
+
+    /// Apply `change`, or hold it for confirmation when it deletes block
+    /// files or leaves pruned blocks behind.
+    fn request_prune(&mut self, change: PruneConfig) -> Task<Message> {
+        self.preset_confirm = None;
+        let from = self.tuning_settings().prune_mib;
+        // With the setting off, the preset's own target applies again.
+        let to = prune::target_mib(&change).or_else(|| {
+            tuning::Settings::compute(
+                self.config.tuning.preset,
+                &self.machine,
+                self.config.tuning.ibd_boost,
+            )
+            .prune_mib
+        });
+        if change != self.config.prune && self.prune_needs_review(from, to) {
+            self.prune_confirm = Some(change);
+            return Task::none();
+        }
+        self.apply_prune(change)
+    }
+
+    /// Whether moving the prune target from `from` to `to` deletes block
+    /// files, or leaves pruned blocks that an unpruned bitcoind refuses.
+    fn prune_needs_review(&self, from: Option<u64>, to: Option<u64>) -> bool {
+        // Shrinking a full node deletes block files and growing a pruned
+        // one back needs a re-download: ask first.
+        let deletes = to.is_some_and(|mib| self.prune_scan.reclaimable(mib) > 0);
+        let unprunes = from.is_some() && to.is_none() && self.bitcoin_pruned;
+        from != to && (deletes || unprunes)
+    }
+
+    fn apply_prune(&mut self, change: PruneConfig) -> Task<Message> {
+        self.prune_target_edit = change.target_gib.to_string();
+        self.config.prune = change;
+        self.save_config_or_report();
+        self.scan_prune()
+    }
+
+    /// Work started with the window: measure the data directory and, on
+    /// first run, look for existing installations.
+    pub fn startup(&mut self) -> Task<Message> {
+        let discover = if Config::config_file_path().exists() {
+            Task::none()
+        } else {
+            self.discover.scan_quietly()
+        };
+        Task::batch([self.scan_prune(), discover])
+    }
+
+    /// Measure the bitcoin data directory in the background.
+    pub fn scan_prune(&self) -> Task<Message> {
+        let dir = self.config.bitcoin_data_path.clone();
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || prune::Scan::run(&dir))
+                    .await
+                    .unwrap_or_default()
+            },
+            Message::PruneScanned,
+        )
+    }
+
+    /// `rescanblockchain` on every loaded wallet, one after another.
+    fn rescan_wallets(&self, from: u64) -> Task<Message> {
+        push_msg(
+            &self.bitcoin_queue,
+            &format!("Rescanning loaded wallets from height {from}…"),
+        );
+        let auth = self.rpc_auth();
+        Task::perform(
+            async move {
+                let mut lines = Vec::new();
+                for wallet in rpc::list_wallets(&auth)
+                    .await
+                    .map_err(|e| format!("{e:#}"))?
+                {
+                    let label = if wallet.is_empty() {
+                        "(default)"
+                    } else {
+                        &wallet
+                    };
+                    lines.push(match rpc::rescan_blockchain(&auth, &wallet, from).await {
+                        Ok((start, stop)) => {
+                            format!("Rescan: {label} scanned blocks {start}–{stop}")
+                        }
+                        Err(e) => format!("Rescan: {label} failed: {e:#}"),
+                    });
+                }
+                Ok(lines)
+            },
+            Message::RescanFinished,
+        )
+    }
+
+    /// Mode, rescan height and assumevalid for one launch, then a
+    /// confirmation with the time it will likely take.
+    fn view_launch_with(&self) -> Element<'_, Message> {
+        let shot = &self.launch_with;
+        let pruned = self.prune_active();
+        let body: Element<'_, Message> = if self.launch_with_confirm {
+            let tip = self.chain_info.as_ref().map_or(0, |c| c.blocks);
+            let estimate =
+                match shot.estimate(&self.machine, self.prune_scan.blocks_bytes, tip, pruned) {
+                    Some((low, high)) => format!(
+                        "Estimated time: {} – {} on this machine ({} cores, {} of blocks on disk).",
+                        format_duration(low),
+                        format_duration(high),
+                        self.machine.cpus,
+                        format_bytes(self.prune_scan.blocks_bytes)
+                    ),
+                    None => "assumevalid only affects blocks not yet validated.".to_owned(),
+                };
+            column![
+                text(shot.describe(pruned)).size(11).color(Color::BLACK),
+                text(estimate).size(11).color(MAC_ORG),
+                row![
+                    Space::with_width(Length::Fill),
+                    styled_button("Back", ButtonStyle::Secondary)
+                        .on_press(Message::CancelLaunchWith),
+                    Space::with_width(6),
+                    styled_button("Launch", ButtonStyle::Warning)
+                        .on_press(Message::ConfirmLaunchWith),
+                ]
+                .align_y(Alignment::Center),
+            ]
+            .spacing(6)
+            .into()
+        } else {
+            let mut mode_row = row![pick_list(
+                launch_mode::Mode::ALL,
+                Some(shot.mode),
+                Message::LaunchModeSelected
+            )
+            .text_size(12)]
+            .align_y(Alignment::Center);
+            if shot.mode == launch_mode::Mode::Rescan {
+                mode_row = mode_row
+                    .push(Space::with_width(10))
+                    .push(text("from height").size(11).color(TEXT_SEC))
+                    .push(Space::with_width(6))
+                    .push(
+                        text_input("0", &self.rescan_from_edit)
+                            .on_input(Message::RescanFromEdited)
+                            .padding(Padding::from([2, 6]))
+                            .font(Font::MONOSPACE)
+                            .size(11)
+                            .width(90),
+                    );
+            }
+            column![
+                mode_row,
+                row![
+                    text("assumevalid").size(11).color(TEXT_SEC).width(90),
+                    text_input(
+                        "default · 0 checks every signature · block hash",
+                        &shot.assumevalid
+                    )
+                    .on_input(Message::AssumeValidEdited)
+                    .padding(Padding::from([2, 6]))
+                    .font(Font::MONOSPACE)
+                    .size(11),
+                ]
+                .align_y(Alignment::Center),
+                row![
+                    Space::with_width(Length::Fill),
+                    styled_button("Cancel", ButtonStyle::Secondary)
+                        .on_press(Message::ToggleLaunchWith),
+                    Space::with_width(6),
+                    styled_button("Review…", ButtonStyle::Primary)
+                        .on_press(Message::ReviewLaunchWith),
+                ]
+                .align_y(Alignment::Center),
+            ]
+            .spacing(6)
+            .into()
+        };
+        container(body)
+            .width(Length::Fill)
+            .padding(Padding::from([8, 20]))
+            .style(|_| container::Style {
+                background: Some(BAR.into()),
+                ..Default::default()
+            })
+            .into()
+    }
+
+    /// Electrs indexes every block, so a pruned bitcoind cannot serve it.
+    fn prune_active(&self) -> bool {
+        self.tuning_settings().prune_mib.is_some()
+            || self.prune_scan.conf_prune_mib.is_some()
+            || self.bitcoin_pruned
+    }
+
+    /// Binaries folder dot: grey if missing, red if any binary can't run
+    /// here, orange if one needs Rosetta, green otherwise.
+    fn binaries_indicator(&self) -> Color {
+        if !Path::new(&self.binaries_path_edit).exists() {
+            return OFF;
+        }
+        let compat: Vec<Compatibility> = self
+            .binary_checks
+            .iter()
+            .filter_map(|(_, check)| check.as_ref())
+            .map(|check| match check {
+                Ok(info) => info.compatibility(),
+                Err(e) => Compatibility::Incompatible(e.clone()),
+            })
+            .collect();
+        if compat
+            .iter()
+            .any(|c| matches!(c, Compatibility::Incompatible(_)))
+        {
+            MAC_RED
+        } else if compat.contains(&Compatibility::Rosetta) {
+            MAC_ORG
+        } else {
+            GREEN
+        }
+    }
+
+    /// One line per binary under the binaries path: architecture and
+    /// whether it can run on this machine.
+    fn view_binary_checks(&self) -> Element<'_, Message> {
+        let lines = self.binary_checks.iter().filter_map(|(name, check)| {
+            let (detail, color) = match check.as_ref()? {
+                Ok(info) => match info.compatibility() {
+                    Compatibility::Native => (info.to_string(), TEXT_TER),
+                    Compatibility::Rosetta => (format!("{info} — runs under Rosetta"), MAC_ORG),
+                    Compatibility::Incompatible(why) => (format!("{info} — {why}"), MAC_RED),
+                },
+                Err(e) => (e.clone(), MAC_RED),
+            };
+            Some(
+                text(format!("{name}: {detail}"))
+                    .size(10)
+                    .color(color)
+                    .into(),
+            )
+        });
+        row![
+            Space::with_width(184),
+            column(lines.collect::<Vec<Element<Message>>>()).spacing(1)
+        ]
+        .into()
+    }
+
+    fn view_update_sources(&self) -> Element<'_, Message> {
+        let mut list = column![text("UPDATE SOURCES").size(10).color(TEXT_TER)].spacing(4);
+
+        for (i, source) in self.config.update_sources.iter().enumerate() {
+            let available = self
+                .update_sources_available
+                .get(i)
+                .copied()
+                .unwrap_or(false);
+            list = list.push(
+                row![
+                    text("●")
+                        .size(13)
+                        .color(if available { GREEN } else { OFF }),
+                    Space::with_width(6),
+                    text(source.to_string())
+                        .size(11)
+                        .font(Font::MONOSPACE)
+                        .color(TEXT_SEC),
+                    Space::with_width(Length::Fill),
+                    styled_button("Remove", ButtonStyle::Secondary)
+                        .on_press(Message::RemoveUpdateSource(i)),
+                ]
+                .align_y(Alignment::Center),
+            );
+        }
+
+        list = list.push(
+            row![
+                text_input(
+                    "Folder path or http(s):// mirror URL",
+                    &self.update_source_edit
+                )
+                .on_input(Message::UpdateSourceInputChanged)
+                .on_submit(Message::AddUpdateSource)
+                .padding(Padding::from([4, 6]))
+                .font(Font::MONOSPACE)
+                .size(11),
+                Space::with_width(6),
+                styled_button("Browse…", ButtonStyle::Secondary)
+                    .on_press(Message::BrowseUpdateSource),
+                styled_button("Add Source", ButtonStyle::Secondary)
+                    .on_press(Message::AddUpdateSource),
+            ]
+            .align_y(Alignment::Center)
+            .spacing(4),
+        );
+
+        container(list)
+            .padding(Padding::from([4, 20]))
+            .width(Length::Fill)
+            .into()
+    }
+
     // ── Dual node panels ──────────────────────────────────────────────────────
 
     fn view_node_panels(&self) -> Element<'_, Message> {
         let bitcoin_panel = self.view_node_panel(
             "Bitcoin",
             BTC_ACC,
-            Message::LaunchBitcoin,
+            Ok(Message::LaunchBitcoin),
+            Some(Message::ToggleLaunchWith),
+            self.launch_with_open.then(|| self.view_launch_with()),
             self.bitcoin_running,
             self.bitcoin_synced,
             self.bitcoin_running && self.bitcoin_synced,
+            &self.bitcoin_usage,
             &self.bitcoin_lines,
             bitcoin_scroll_id(),
         );
         let electrs_panel = self.view_node_panel(
             "Electrs",
             ELS_ACC,
-            Message::LaunchElectrs,
+            if self.prune_active() {
+                Err("Needs an unpruned node")
+            } else {
+                Ok(Message::LaunchElectrs)
+            },
+            None,
+            None,
             self.electrs_running,
             self.electrs_synced,
             self.electrs_running && self.electrs_synced,
+            &self.electrs_usage,
             &self.electrs_lines,
             electrs_scroll_id(),
         );
@@ -901,10 +3173,13 @@ impl App {
         &'a self,
         title: &'a str,
         accent: Color,
-        launch_msg: Message,
+        launch: Result<Message, &'a str>,
+        menu_toggle: Option<Message>,
+        menu: Option<Element<'a, Message>>,
         running: bool,
         synced: bool,
         ready: bool,
+        usage: &'a resources::Monitor,
         lines: &'a [String],
         scroll_id: ScrollId,
     ) -> Element<'a, Message> {
@@ -930,7 +3205,8 @@ impl App {
         .style(move |_, status| button::Style {
             background: Some(match status {
                 button::Status::Hovered | button::Status::Pressed => darken(accent).into(),
-                _ => accent.into(),
+                button::Status::Disabled => OFF.into(),
+                button::Status::Active => accent.into(),
             }),
             text_color: Color::WHITE,
             border: iced::Border {
@@ -940,9 +3216,9 @@ impl App {
             },
             shadow: iced::Shadow::default(),
         })
-        .on_press(launch_msg);
+        .on_press_maybe(launch.as_ref().ok().cloned());
 
-        let header = row![
+        let mut header = row![
             text(title)
                 .size(20)
                 .font(Font {
@@ -951,15 +3227,28 @@ impl App {
                 })
                 .color(Color::BLACK),
             Space::with_width(Length::Fill),
-            launch_btn,
-        ]
-        .align_y(Alignment::Center)
-        .padding(Padding {
-            top: 14.0,
-            right: 20.0,
-            bottom: 10.0,
-            left: 20.0,
-        });
+            text(launch.err().unwrap_or_default())
+                .size(11)
+                .color(TEXT_TER),
+            Space::with_width(10),
+        ];
+        if let Some(msg) = menu_toggle {
+            header = header
+                .push(
+                    styled_button("Launch with…", ButtonStyle::Secondary)
+                        .on_press_maybe((!running).then_some(msg)),
+                )
+                .push(Space::with_width(6));
+        }
+        let header = header
+            .push(launch_btn)
+            .align_y(Alignment::Center)
+            .padding(Padding {
+                top: 14.0,
+                right: 20.0,
+                bottom: 10.0,
+                left: 20.0,
+            });
 
         // Indicators
         let indicators = row![
@@ -1003,16 +3292,15 @@ impl App {
                 ..Default::default()
             });
 
-        let panel = column![
-            accent_bar,
-            header,
-            horizontal_rule(),
-            indicators,
-            horizontal_rule(),
-            terminal_container,
-        ]
-        .width(Length::Fill)
-        .height(Length::Fill);
+        let panel = column![accent_bar, header]
+            .push_maybe(menu)
+            .push(horizontal_rule())
+            .push(indicators)
+            .push(view_resources(usage, running, accent))
+            .push(horizontal_rule())
+            .push(terminal_container)
+            .width(Length::Fill)
+            .height(Length::Fill);
 
         container(panel)
             .width(Length::FillPortion(1))
@@ -1055,19 +3343,14 @@ impl App {
 
 // ── Overlay (modal dialog) ────────────────────────────────────────────────────
 
-fn view_overlay(message: &str, bitforge_path: Option<PathBuf>) -> Element<'_, Message> {
-    let mut buttons: Vec<Element<Message>> = vec![styled_button("OK", ButtonStyle::Primary)
-        .on_press(Message::DismissOverlay)
-        .into()];
-
-    if let Some(path) = bitforge_path {
-        buttons.insert(
-            0,
-            styled_button("Open BitForge", ButtonStyle::Confirm)
-                .on_press(Message::OpenBitForge(path))
-                .into(),
-        );
-    }
+fn view_overlay<'a>(message: &'a str, actions: Vec<Element<'a, Message>>) -> Element<'a, Message> {
+    let dismiss_label = if actions.is_empty() { "OK" } else { "Cancel" };
+    let mut buttons = actions;
+    buttons.push(
+        styled_button(dismiss_label, ButtonStyle::Primary)
+            .on_press(Message::DismissOverlay)
+            .into(),
+    );
 
     let dialog = container(
         column![
@@ -1132,6 +3415,177 @@ fn horizontal_rule<'a>() -> Element<'a, Message> {
         .into()
 }
 
+/// Small caps heading used at the top of panels.
+fn section_label(label: &str) -> Element<'_, Message> {
+    text(label).size(10).color(TEXT_TER).into()
+}
+
+/// Full-height panel body used by every tab other than Nodes.
+fn page<'a>(content: impl Into<Element<'a, Message>>) -> Element<'a, Message> {
+    container(content)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .padding(Padding::from([12, 20]))
+        .style(|_| container::Style {
+            background: Some(PANEL.into()),
+            ..Default::default()
+        })
+        .into()
+}
+
+/// Horizontal bar filled to `fraction` (clamped to 0‥1).
+fn meter<'a>(fraction: f32, color: Color) -> Element<'a, Message> {
+    progress_bar(0.0..=1.0, fraction.clamp(0.0, 1.0))
+        .height(8)
+        .style(move |_| progress_bar::Style {
+            background: BORDER.into(),
+            bar: color.into(),
+            border: iced::Border {
+                radius: 4.0.into(),
+                ..Default::default()
+            },
+        })
+        .into()
+}
+
+/// Borderless blue text that acts as a hyperlink.
+/// CPU, memory, descriptors, disk and threads with a sparkline each.
+fn view_resources(
+    usage: &resources::Monitor,
+    running: bool,
+    accent: Color,
+) -> Element<'_, Message> {
+    let padding = Padding::from([0, 20]);
+    if !running {
+        return Space::with_height(0).into();
+    }
+    if let Some(e) = &usage.error {
+        return container(
+            text(format!("Resource usage unavailable: {e}"))
+                .size(10)
+                .color(TEXT_TER),
+        )
+        .padding(padding)
+        .into();
+    }
+    let Some(latest) = usage.latest() else {
+        return container(text("Sampling resource usage…").size(10).color(TEXT_TER))
+            .padding(padding)
+            .into();
+    };
+    #[allow(clippy::cast_precision_loss)]
+    let as_f64 = |n: u64| n as f64;
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let rate = |bytes_per_sec: f64| format!("{}/s", format_bytes(bytes_per_sec as u64));
+    let disk = latest.disk_rates.map_or_else(
+        || "—".to_owned(),
+        |(r, w)| format!("R {} · W {}", rate(r), rate(w)),
+    );
+    let rows = [
+        (
+            "CPU",
+            format!("{:.0}%", latest.cpu_percent),
+            usage.series(|u| u.cpu_percent),
+        ),
+        (
+            "Memory",
+            format_bytes(latest.rss_bytes),
+            usage.series(|u| as_f64(u.rss_bytes)),
+        ),
+        (
+            "Open files",
+            latest.fds.map_or_else(|| "—".to_owned(), |n| n.to_string()),
+            usage.series(|u| u.fds.map_or(0.0, as_f64)),
+        ),
+        (
+            "Disk",
+            disk,
+            usage.series(|u| u.disk_rates.map_or(0.0, |(r, w)| r + w)),
+        ),
+        (
+            "Threads",
+            latest.threads.to_string(),
+            usage.series(|u| as_f64(u.threads)),
+        ),
+    ];
+    let mut col = column![].spacing(2).padding(padding);
+    for (label, value, series) in rows {
+        col = col.push(
+            row![
+                text(label).size(10).color(TEXT_TER).width(70),
+                text(value).size(10).color(TEXT_SEC).width(160),
+                sparkline(&series, accent),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        );
+    }
+    col.into()
+}
+
+/// Bars scaled to the largest value, oldest on the left.
+fn sparkline<'a>(values: &[f64], color: Color) -> Element<'a, Message> {
+    const HEIGHT: f32 = 14.0;
+    let max = values.iter().copied().fold(0.0, f64::max);
+    let bars = values.iter().map(|&v| {
+        #[allow(clippy::cast_possible_truncation)]
+        let h = if max > 0.0 {
+            ((v / max) as f32 * HEIGHT).max(1.0)
+        } else {
+            1.0
+        };
+        container(Space::new(2, h))
+            .style(move |_| container::Style {
+                background: Some(color.into()),
+                ..Default::default()
+            })
+            .into()
+    });
+    container(row(bars).spacing(1).align_y(Alignment::End))
+        .height(HEIGHT)
+        .align_y(Alignment::End)
+        .into()
+}
+
+fn link_button<'a>(label: &str, on_press: Message) -> Element<'a, Message> {
+    button(text(label.to_owned()).size(11).color(MAC_BLUE))
+        .padding(0)
+        .style(|_, _| button::Style::default())
+        .on_press(on_press)
+        .into()
+}
+
+/// Label/value row used in detail views.
+fn field(label: &str, value: String) -> Element<'_, Message> {
+    row![
+        text(label).size(11).color(TEXT_TER).width(110),
+        text(value).size(11).color(TEXT_SEC),
+    ]
+    .spacing(8)
+    .into()
+}
+
+/// Toolbar tab: highlighted when `active`.
+fn tab_button(label: &str, active: bool) -> button::Button<'_, Message> {
+    styled_button(
+        label,
+        if active {
+            ButtonStyle::Primary
+        } else {
+            ButtonStyle::Secondary
+        },
+    )
+}
+
+/// Green dot colour if `path` exists, grey otherwise.
+fn exists_color(path: &str) -> Color {
+    if Path::new(path).exists() {
+        GREEN
+    } else {
+        OFF
+    }
+}
+
 fn indicator_badge(label: &str, active: bool) -> Element<'_, Message> {
     let dot_color = if active { GREEN } else { OFF };
     row![
@@ -1148,9 +3602,9 @@ fn path_row<'a>(
     value: &'a str,
     on_change: impl Fn(String) -> Message + 'a,
     browse_msg: Message,
-    exists: bool,
+    indicator: Color,
 ) -> Element<'a, Message> {
-    let exists_dot = text("●").size(13).color(if exists { GREEN } else { OFF });
+    let exists_dot = text("●").size(13).color(indicator);
 
     row![
         text(label).size(11).color(TEXT_SEC).width(180),
@@ -1221,6 +3675,31 @@ fn styled_button(label: &str, style: ButtonStyle) -> button::Button<'_, Message>
         })
 }
 
+// ── Checkbox styling ──────────────────────────────────────────────────────────
+
+fn styled_checkbox(label: &str, checked: bool) -> checkbox::Checkbox<'_, Message> {
+    checkbox(label, checked)
+        .size(14)
+        .text_size(11)
+        .style(|_, status| {
+            let is_checked = match status {
+                checkbox::Status::Active { is_checked }
+                | checkbox::Status::Hovered { is_checked }
+                | checkbox::Status::Disabled { is_checked } => is_checked,
+            };
+            checkbox::Style {
+                background: if is_checked { MAC_BLUE } else { Color::WHITE }.into(),
+                icon_color: Color::WHITE,
+                border: iced::Border {
+                    color: if is_checked { MAC_BLUE } else { BORDER },
+                    width: 1.0,
+                    radius: 4.0.into(),
+                },
+                text_color: Some(TEXT_SEC),
+            }
+        })
+}
+
 // ── Colour utilities ──────────────────────────────────────────────────────────
 
 fn darken(c: Color) -> Color {
@@ -1242,8 +3721,132 @@ async fn browse_folder(title: &str) -> Option<String> {
         .map(|f| f.path().to_string_lossy().into_owned())
 }
 
+// ── Process helpers ───────────────────────────────────────────────────────────
+
+/// Stop bitcoind via RPC (falling back to SIGTERM/SIGKILL) and wait for it to
+/// exit.  Blocks for up to ~70 s; call from a background thread.
+fn stop_bitcoind_blocking(mut handle: ProcessHandle, auth: &RpcAuth, queue: &OutputQueue) {
+    let rt = tokio::runtime::Handle::try_current();
+    // Stop via RPC; if that fails, SIGTERM the process
+    let stopped_via_rpc = rt.map_or_else(
+        |_| {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map(|r| r.block_on(rpc::stop_bitcoind(auth)).is_ok())
+                .unwrap_or(false)
+        },
+        |rt| rt.block_on(rpc::stop_bitcoind(auth)).is_ok(),
+    );
+    if stopped_via_rpc {
+        // Wait up to 60 s for graceful shutdown
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(60);
+        loop {
+            if std::time::Instant::now() >= deadline {
+                handle.terminate();
+                break;
+            }
+            if !handle.is_running() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(500));
+        }
+    } else {
+        handle.terminate();
+    }
+    push_msg(queue, "bitcoind stopped.");
+}
+
+// ── Binary checks ─────────────────────────────────────────────────────────────
+
+fn inspect_binaries(dir: &Path) -> BinaryChecks {
+    CHECKED_BINARIES
+        .iter()
+        .map(|&name| {
+            let path = dir.join(name);
+            let check = path
+                .exists()
+                .then(|| binary_arch::inspect(&path).map_err(|e| format!("{e:#}")));
+            (name, check)
+        })
+        .collect()
+}
+
 // ── Queue helper ──────────────────────────────────────────────────────────────
 
+/// Format with thousands separators: 895234 → "895,234".
+/// Current unix time in seconds.
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn format_thousands(n: u64) -> String {
+    let s = n.to_string();
+    let mut out = String::with_capacity(s.len() + s.len() / 3);
+    for (i, ch) in s.chars().rev().enumerate() {
+        if i > 0 && i % 3 == 0 {
+            out.push(',');
+        }
+        out.push(ch);
+    }
+    out.chars().rev().collect()
+}
+
+/// Compact duration: "45s", "12m", "3h 05m", "2d 4h".
+fn format_duration(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m", secs / 60),
+        3600..86_400 => format!("{}h {:02}m", secs / 3600, secs % 3600 / 60),
+        _ => format!("{}d {}h", secs / 86_400, secs % 86_400 / 3600),
+    }
+}
+
+/// Unix timestamp as `YYYY-MM-DD HH:MM:SS UTC`.
+fn format_unix_time(secs: u64) -> String {
+    let days = secs / 86_400;
+    let rem = secs % 86_400;
+    // Civil-from-days (Howard Hinnant), valid for any date after 1970.
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+fn format_btc(value: f64) -> String {
+    format!("{value:.8} BTC")
+}
+
+/// Human-readable byte count using binary units: 1536 → "1.5 KiB".
+#[allow(clippy::cast_precision_loss)]
+fn format_bytes(n: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = n as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{n} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
 fn push_msg(queue: &OutputQueue, msg: &str) {
     if let Ok(mut q) = queue.lock() {
         if q.len() > 10_000 {
@@ -1252,3 +3855,28 @@ fn push_msg(queue: &OutputQueue, msg: &str) {
         q.push_back(msg.to_owned());
     }
 }
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_numbers() {
+        assert_eq!(format_thousands(895_234), "895,234");
+        assert_eq!(format_thousands(999), "999");
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(300 * 1024 * 1024), "300.0 MiB");
+    }
+
+    #[test]
+    fn formats_unix_time() {
+        assert_eq!(format_unix_time(0), "1970-01-01 00:00:00 UTC");
+        // Genesis block.
+        assert_eq!(format_unix_time(1_231_006_505), "2009-01-03 18:15:05 UTC");
+        // Block 840,000 (leap year, after February).
+        assert_eq!(format_unix_time(1_713_571_767), "2024-04-20 00:09:27 UTC");
+    }
+}
@@ -0,0 +1,475 @@
+//! Broadcast tab — submit a raw transaction through the local node.
+//!
+//! The transaction is decoded (`decoderawtransaction`) and checked with
+//! `testmempoolaccept` first; only a transaction the mempool would accept at
+//! or below the configured max fee rate can be sent.  Sent txids are recorded
+//! in the manager's history.
+
+use iced::{
+    widget::{column, row, scrollable, text, text_input, Space},
+    Alignment, Color, Element, Length, Task,
+};
+
+use super::{
+    field, format_btc, format_unix_time, horizontal_rule, link_button, page, section_label,
+    styled_button, ButtonStyle, Message, GREEN, MAC_RED, TEXT_SEC, TEXT_TER,
+};
+use crate::{
+    history,
+    rpc::{self, MempoolAccept, RpcAuth, Transaction},
+};
+
+/// Broadcasts listed under the form.
+const HISTORY_SHOWN: usize = 20;
+
+/// Inputs / outputs listed in the decoded summary.
+const MAX_LISTED: usize = 50;
+
+/// Hex from a transaction file: hex text as-is, anything else is taken to
+/// be the raw binary transaction.
+fn tx_hex_from_file(bytes: &[u8]) -> String {
+    if let Ok(s) = std::str::from_utf8(bytes) {
+        let hex = normalize_hex(s);
+        if !hex.is_empty()
+            && hex.len().is_multiple_of(2)
+            && hex.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            return hex;
+        }
+    }
+    hex::encode(bytes)
+}
+
+/// Strip whitespace (pasted hex often wraps) and lowercase.
+fn normalize_hex(s: &str) -> String {
+    s.split_whitespace()
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+/// Result of decoding and `testmempoolaccept` for one transaction.
+#[derive(Debug, Clone)]
+pub struct Preflight {
+    /// The exact hex that was checked.
+    hex: String,
+    max_fee_rate: f64,
+    tx: Transaction,
+    accept: MempoolAccept,
+}
+
+#[derive(Debug, Clone)]
+pub enum Msg {
+    HexChanged(String),
+    OpenFile,
+    FileLoaded(Option<Result<String, String>>),
+    MaxFeeRateChanged(String),
+    Check,
+    Checked(Result<Box<Preflight>, String>),
+    Send,
+    /// The txid, and the history write error if recording it failed.
+    Sent(Result<(String, Option<String>), String>),
+    LoadHistory,
+    HistoryLoaded(Result<Vec<history::Entry>, String>),
+}
+
+pub struct State {
+    hex: String,
+    max_fee_edit: String,
+    preflight: Option<Preflight>,
+    checking: bool,
+    sending: bool,
+    error: Option<String>,
+    /// Txid of the last successful broadcast.
+    sent: Option<String>,
+    history: Option<Vec<history::Entry>>,
+}
+
+impl State {
+    pub fn new(max_fee_rate: f64) -> Self {
+        Self {
+            hex: String::new(),
+            max_fee_edit: max_fee_rate.to_string(),
+            preflight: None,
+            checking: false,
+            sending: false,
+            error: None,
+            sent: None,
+            history: None,
+        }
+    }
+
+    /// Load `hex` and run the preflight (hand-off from other tabs).
+    pub fn open_hex(&mut self, hex: String, auth: RpcAuth) -> Task<Message> {
+        self.hex = hex;
+        self.update(Msg::Check, auth)
+    }
+
+    /// The preflight, if it still matches the hex in the form.
+    fn current_preflight(&self) -> Option<&Preflight> {
+        self.preflight
+            .as_ref()
+            .filter(|p| p.hex == normalize_hex(&self.hex))
+    }
+
+    pub fn update(&mut self, msg: Msg, auth: RpcAuth) -> Task<Message> {
+        match msg {
+            Msg::HexChanged(s) => {
+                self.hex = s;
+                Task::none()
+            }
+            Msg::OpenFile => Task::perform(
+                async {
+                    let file = rfd::AsyncFileDialog::new()
+                        .set_title("Open Raw Transaction")
+                        .pick_file()
+                        .await?;
+                    Some(
+                        tokio::fs::read(file.path())
+                            .await
+                            .map(|bytes| tx_hex_from_file(&bytes))
+                            .map_err(|e| format!("read {}: {e}", file.path().display())),
+                    )
+                },
+                |r| Message::Broadcast(Msg::FileLoaded(r)),
+            ),
+            Msg::FileLoaded(None) => Task::none(),
+            Msg::FileLoaded(Some(Ok(hex))) => self.open_hex(hex, auth),
+            Msg::FileLoaded(Some(Err(e))) => {
+                self.error = Some(e);
+                Task::none()
+            }
+            Msg::MaxFeeRateChanged(s) => {
+                self.max_fee_edit = s;
+                Task::none()
+            }
+            Msg::Check => self.check(auth),
+            Msg::Checked(result) => {
+                self.checking = false;
+                match result {
+                    Ok(p) => self.preflight = Some(*p),
+                    Err(e) => {
+                        self.preflight = None;
+                        self.error = Some(e);
+                    }
+                }
+                Task::none()
+            }
+            Msg::Send => self.send(auth),
+            Msg::Sent(result) => {
+                self.sending = false;
+                match result {
+                    Ok((txid, history_error)) => {
+                        self.sent = Some(txid);
+                        self.error = history_error
+                            .map(|e| format!("Sent, but the history write failed: {e}"));
+                        self.hex.clear();
+                        self.preflight = None;
+                        self.history = None;
+                        Task::done(Message::Broadcast(Msg::LoadHistory))
+                    }
+                    Err(e) => {
+                        self.error = Some(e);
+                        Task::none()
+                    }
+                }
+            }
+            Msg::LoadHistory => {
+                if self.history.is_some() {
+                    return Task::none();
+                }
+                // Mark as loaded so ticks do not queue more reads.
+                self.history = Some(Vec::new());
+                Task::perform(
+                    async {
+                        tokio::task::spawn_blocking(|| history::recent(usize::MAX))
+                            .await
+                            .map_err(|e| e.to_string())?
+                            .map_err(|e| format!("{e:#}"))
+                    },
+                    |r| Message::Broadcast(Msg::HistoryLoaded(r)),
+                )
+            }
+            Msg::HistoryLoaded(result) => {
+                match result {
+                    Ok(entries) => {
+                        let mut sent: Vec<history::Entry> = entries
+                            .into_iter()
+                            .filter(|e| e.kind == history::Kind::Broadcast)
+                            .collect();
+                        sent.reverse();
+                        sent.truncate(HISTORY_SHOWN);
+                        self.history = Some(sent);
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+                Task::none()
+            }
+        }
+    }
+
+    /// Decode and `testmempoolaccept` the hex in the form.
+    fn check(&mut self, auth: RpcAuth) -> Task<Message> {
+        let hex = normalize_hex(&self.hex);
+        if hex.is_empty() {
+            self.error = Some("Paste a raw transaction or open a file.".to_owned());
+            return Task::none();
+        }
+        let max_fee_rate = match self.max_fee_edit.trim().parse::<f64>() {
+            Ok(r) if r > 0.0 && r.is_finite() => r,
+            _ => {
+                self.error = Some("Max fee rate must be a positive number.".to_owned());
+                return Task::none();
+            }
+        };
+        self.checking = true;
+        self.error = None;
+        self.sent = None;
+        Task::batch([
+            Task::done(Message::MaxFeeRateSaved(max_fee_rate)),
+            Task::perform(
+                async move {
+                    let (tx, accept) = tokio::try_join!(
+                        rpc::decode_raw_transaction(&auth, &hex),
+                        rpc::test_mempool_accept(&auth, &hex, max_fee_rate),
+                    )
+                    .map_err(|e| e.to_string())?;
+                    Ok(Box::new(Preflight {
+                        hex,
+                        max_fee_rate,
+                        tx,
+                        accept,
+                    }))
+                },
+                |r| Message::Broadcast(Msg::Checked(r)),
+            ),
+        ])
+    }
+
+    /// Broadcast the checked transaction and record it in the history.
+    fn send(&mut self, auth: RpcAuth) -> Task<Message> {
+        let Some(p) = self.current_preflight().filter(|p| p.accept.allowed) else {
+            return Task::none();
+        };
+        let (hex, max_fee_rate) = (p.hex.clone(), p.max_fee_rate);
+        let summary = format!(
+            "Sent {} vB, {} in / {} out",
+            p.tx.vsize,
+            p.tx.vin.len(),
+            p.tx.vout.len()
+        );
+        self.sending = true;
+        self.error = None;
+        Task::perform(
+            async move {
+                let txid = rpc::send_raw_transaction(&auth, &hex, max_fee_rate)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let entry = history::Entry::now(history::Kind::Broadcast, summary).with_txid(&txid);
+                // The broadcast happened either way; a log failure is shown
+                // next to the txid.
+                let history_error = history::append(&entry).err().map(|e| format!("{e:#}"));
+                Ok((txid, history_error))
+            },
+            |r| Message::Broadcast(Msg::Sent(r)),
+        )
+    }
+
+    pub fn view(&self, bitcoin_running: bool) -> Element<'_, Message> {
+        let hex_input = text_input("Raw transaction hex", &self.hex)
+            .on_input(|s| Message::Broadcast(Msg::HexChanged(s)))
+            .on_submit(Message::Broadcast(Msg::Check))
+            .size(12)
+            .padding(6);
+        let fee_input = text_input("10000", &self.max_fee_edit)
+            .on_input(|s| Message::Broadcast(Msg::MaxFeeRateChanged(s)))
+            .size(12)
+            .padding(6)
+            .width(90);
+
+        let can_send = bitcoin_running
+            && !self.sending
+            && self.current_preflight().is_some_and(|p| p.accept.allowed);
+
+        let header = row![
+            section_label("BROADCAST TRANSACTION"),
+            Space::with_width(12),
+            hex_input,
+            styled_button("Open File…", ButtonStyle::Secondary)
+                .on_press(Message::Broadcast(Msg::OpenFile)),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center);
+
+        let actions = row![
+            text("Max fee rate").size(11).color(TEXT_TER),
+            fee_input,
+            text("sat/vB").size(11).color(TEXT_TER),
+            Space::with_width(Length::Fill),
+            styled_button(
+                if self.checking {
+                    "Checking…"
+                } else {
+                    "Check"
+                },
+                ButtonStyle::Secondary
+            )
+            .on_press_maybe(
+                (bitcoin_running && !self.checking).then_some(Message::Broadcast(Msg::Check))
+            ),
+            styled_button(
+                if self.sending {
+                    "Broadcasting…"
+                } else {
+                    "Broadcast"
+                },
+                ButtonStyle::Confirm
+            )
+            .on_press_maybe(can_send.then_some(Message::Broadcast(Msg::Send))),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center);
+
+        let mut body = column![header, actions].spacing(10);
+        if !bitcoin_running {
+            body = body.push(text("Bitcoin is not running.").size(11).color(TEXT_TER));
+        }
+        if let Some(e) = &self.error {
+            body = body.push(text(e.as_str()).size(11).color(MAC_RED));
+        }
+        if let Some(txid) = &self.sent {
+            body = body.push(
+                row![
+                    text("Broadcast").size(12).color(GREEN),
+                    link_button(txid, Message::ExploreTx(txid.clone())),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center),
+            );
+        }
+        if let Some(p) = self.current_preflight() {
+            body = body.push(horizontal_rule()).push(view_preflight(p));
+        }
+        body = body.push(horizontal_rule()).push(self.view_history());
+
+        page(scrollable(body).height(Length::Fill))
+    }
+
+    fn view_history(&self) -> Element<'_, Message> {
+        let mut col = column![section_label("RECENT BROADCASTS")].spacing(4);
+        let entries = self.history.as_deref().unwrap_or_default();
+        if entries.is_empty() {
+            col = col.push(text("None yet.").size(11).color(TEXT_TER));
+        }
+        for e in entries {
+            let mut line = row![text(format_unix_time(e.time))
+                .size(11)
+                .color(TEXT_TER)
+                .width(170)]
+            .spacing(8)
+            .align_y(Alignment::Center);
+            if let Some(txid) = &e.txid {
+                line = line.push(link_button(txid, Message::ExploreTx(txid.clone())));
+            }
+            col = col.push(line.push(text(e.message.as_str()).size(11).color(TEXT_SEC)));
+        }
+        col.into()
+    }
+}
+
+fn view_preflight(p: &Preflight) -> Element<'_, Message> {
+    let tx = &p.tx;
+    let verdict = if p.accept.allowed {
+        let fee = p.accept.fees.as_ref().map_or(0.0, |f| f.base);
+        text(format!(
+            "Accepted by the mempool — fee {}  ({:.1} sat/vB)",
+            format_btc(fee),
+            p.accept.fee_rate().unwrap_or(0.0)
+        ))
+        .size(13)
+        .color(GREEN)
+    } else {
+        text(format!(
+            "Rejected: {}",
+            p.accept
+                .reject_reason
+                .as_deref()
+                .unwrap_or("unknown reason")
+        ))
+        .size(13)
+        .color(MAC_RED)
+    };
+
+    let mut col = column![
+        text("Decoded transaction").size(16).color(Color::BLACK),
+        verdict,
+        field("Txid", tx.txid.clone()),
+        field(
+            "Size",
+            format!("{} bytes  ·  {} vB  ·  {} WU", tx.size, tx.vsize, tx.weight)
+        ),
+        field("Outputs total", format_btc(tx.output_total())),
+        field(
+            "Version / locktime",
+            format!("{} / {}", tx.version, tx.locktime)
+        ),
+        field(
+            "Replaceable",
+            if tx.signals_rbf() { "yes" } else { "no" }.to_owned()
+        ),
+        section_label("INPUTS"),
+    ]
+    .spacing(4);
+
+    for input in tx.vin.iter().take(MAX_LISTED) {
+        col = col.push(match (&input.txid, input.vout) {
+            (Some(txid), Some(n)) => {
+                link_button(&format!("{txid}:{n}"), Message::ExploreTx(txid.clone()))
+            }
+            _ => text("coinbase").size(11).color(TEXT_SEC).into(),
+        });
+    }
+    col = col.push(section_label("OUTPUTS"));
+    for output in tx.vout.iter().take(MAX_LISTED) {
+        col = col.push(
+            row![
+                text(format!("#{}", output.n))
+                    .size(11)
+                    .color(TEXT_TER)
+                    .width(30),
+                text(output.script_pub_key.describe())
+                    .size(11)
+                    .color(TEXT_SEC)
+                    .width(Length::FillPortion(5)),
+                text(format_btc(output.value))
+                    .size(11)
+                    .color(TEXT_SEC)
+                    .width(Length::FillPortion(2)),
+            ]
+            .spacing(8),
+        );
+    }
+    let hidden = tx.vin.len().saturating_sub(MAX_LISTED) + tx.vout.len().saturating_sub(MAX_LISTED);
+    if hidden > 0 {
+        col = col.push(
+            text(format!("… {hidden} more inputs/outputs not shown"))
+                .size(11)
+                .color(TEXT_TER),
+        );
+    }
+    col.into()
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_hex_or_binary_files() {
+        assert_eq!(tx_hex_from_file(b"0200AB\n  cd01\n"), "0200abcd01");
+        assert_eq!(tx_hex_from_file(&[0x02, 0x00, 0xff]), "0200ff");
+        // Odd-length "hex" is not hex text.
+        assert_eq!(tx_hex_from_file(b"abc"), "616263");
+    }
+}
@@ -0,0 +1,653 @@
+//! Console tab — send arbitrary RPC commands the way `bitcoin-cli` would.
+//!
+//! Arguments are split like a shell (quotes and backslash escapes), except
+//! that whitespace inside `[...]` / `{...}` does not split, so JSON can be
+//! typed without quoting.  Each argument is sent as JSON when it parses as a
+//! number, boolean, null, array or object and as a string otherwise; quote
+//! it to force a string.  `-named` switches to `name=value` arguments, with
+//! any bare values collected into `args` as `bitcoin-cli` does.
+
+use iced::{
+    font::Font,
+    widget::{column, container, row, scrollable, text, text_input, Space},
+    Alignment, Element, Length, Padding, Task,
+};
+use serde_json::{Map, Value};
+
+use super::{
+    page, section_label, styled_button, ButtonStyle, Message, MAC_BLUE, MAC_ORG, MAC_RED, TERM_BG,
+    TERM_FG, TEXT_TER,
+};
+use crate::rpc::{self, RpcAuth};
+
+/// Methods that stop the node, rewrite chain state, drop peers, move funds,
+/// change or expose keys, or run for hours; they need a second click.
+const DESTRUCTIVE: &[&str] = &[
+    "abandontransaction",
+    "bumpfee",
+    "clearbanned",
+    "disconnectnode",
+    "dumpprivkey",
+    "dumptxoutset",
+    "dumpwallet",
+    "encryptwallet",
+    "importdescriptors",
+    "importprivkey",
+    "invalidateblock",
+    "migratewallet",
+    "pruneblockchain",
+    "removeprunedfunds",
+    "rescanblockchain",
+    "send",
+    "sendall",
+    "sendmany",
+    "sendrawtransaction",
+    "sendtoaddress",
+    "setban",
+    "setnetworkactive",
+    "stop",
+    "unloadwallet",
+    "walletpassphrasechange",
+];
+
+/// Lines kept in the console scrollback.
+const MAX_ENTRIES: usize = 500;
+
+fn scroll_id() -> scrollable::Id {
+    scrollable::Id::new("console")
+}
+
+fn input_id() -> text_input::Id {
+    text_input::Id::new("console-input")
+}
+
+/// A parsed command line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Command {
+    pub method: String,
+    pub params: Value,
+}
+
+impl Command {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut tokens = tokenize(line)?.into_iter();
+        let mut first = tokens.next().ok_or("Empty command.")?;
+        let named = !first.quoted && first.text == "-named";
+        if named {
+            first = tokens.next().ok_or("-named needs a method.")?;
+        }
+        let method = first.text;
+
+        let params = if named {
+            let mut map = Map::new();
+            let mut positional = Vec::new();
+            for tok in tokens {
+                match tok.text.split_once('=') {
+                    Some((name, value)) if is_param_name(name) => {
+                        let value = Token {
+                            text: value.to_owned(),
+                            quoted: tok.quoted,
+                        };
+                        map.insert(name.to_owned(), value.into_json());
+                    }
+                    _ => positional.push(tok.into_json()),
+                }
+            }
+            if !positional.is_empty() {
+                map.insert("args".to_owned(), Value::Array(positional));
+            }
+            Value::Object(map)
+        } else {
+            Value::Array(tokens.map(Token::into_json).collect())
+        };
+        Ok(Self { method, params })
+    }
+
+    fn is_destructive(&self) -> bool {
+        DESTRUCTIVE.contains(&self.method.as_str())
+    }
+}
+
+fn is_param_name(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// One shell-style word.
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    text: String,
+    /// Contained quotes outside brackets, so it is always a string.
+    quoted: bool,
+}
+
+impl Token {
+    fn into_json(self) -> Value {
+        if !self.quoted {
+            if let Ok(v) = serde_json::from_str::<Value>(&self.text) {
+                if !v.is_string() {
+                    return v;
+                }
+            }
+        }
+        Value::String(self.text)
+    }
+}
+
+fn tokenize(line: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut cur = String::new();
+    let mut in_token = false;
+    let mut quoted = false;
+    let mut quote: Option<char> = None;
+    let mut depth = 0usize;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+                if depth > 0 {
+                    cur.push(c);
+                }
+            } else if c == '\\' && q == '"' {
+                let next = chars.next().ok_or("Dangling backslash.")?;
+                if depth > 0 {
+                    cur.push('\\');
+                }
+                cur.push(next);
+            } else {
+                cur.push(c);
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => {
+                quote = Some(c);
+                in_token = true;
+                if depth > 0 {
+                    cur.push(c);
+                } else {
+                    quoted = true;
+                }
+            }
+            '[' | '{' => {
+                depth += 1;
+                in_token = true;
+                cur.push(c);
+            }
+            ']' | '}' => {
+                depth = depth.checked_sub(1).ok_or("Unbalanced brackets.")?;
+                cur.push(c);
+            }
+            '\\' if depth == 0 => {
+                cur.push(chars.next().ok_or("Dangling backslash.")?);
+                in_token = true;
+            }
+            c if c.is_whitespace() && depth == 0 => {
+                if in_token {
+                    tokens.push(Token {
+                        text: std::mem::take(&mut cur),
+                        quoted,
+                    });
+                    in_token = false;
+                    quoted = false;
+                }
+            }
+            c => {
+                cur.push(c);
+                in_token = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        return Err("Unterminated quote.".to_owned());
+    }
+    if depth > 0 {
+        return Err("Unbalanced brackets.".to_owned());
+    }
+    if in_token {
+        tokens.push(Token { text: cur, quoted });
+    }
+    Ok(tokens)
+}
+
+/// Method names from the output of `help`.
+fn parse_help_commands(help: &str) -> Vec<String> {
+    let mut commands: Vec<String> = help
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with("=="))
+        .filter_map(|l| l.split_whitespace().next())
+        .map(str::to_owned)
+        .collect();
+    commands.sort();
+    commands.dedup();
+    commands
+}
+
+/// Complete the method name being typed.  Returns the new input (if it
+/// changed) and the candidates when more than one matches.
+fn complete(input: &str, commands: &[String]) -> (Option<String>, Vec<String>) {
+    let (prefix_opt, word) = match input.strip_prefix("-named ") {
+        Some(rest) => ("-named ", rest.trim_start()),
+        None => ("", input.trim_start()),
+    };
+    if word.is_empty() || word.contains(char::is_whitespace) {
+        return (None, Vec::new());
+    }
+    let matches: Vec<&String> = commands.iter().filter(|c| c.starts_with(word)).collect();
+    match matches.as_slice() {
+        [] => (None, Vec::new()),
+        [only] => (Some(format!("{prefix_opt}{only} ")), Vec::new()),
+        many => {
+            let mut common = many[0].as_str();
+            for m in &many[1..] {
+                let len = common
+                    .chars()
+                    .zip(m.chars())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                common = &common[..len];
+            }
+            let new_input = (common.len() > word.len()).then(|| format!("{prefix_opt}{common}"));
+            (new_input, many.iter().map(|s| (*s).clone()).collect())
+        }
+    }
+}
+
+/// Result text: strings as-is (e.g. `help`), everything else pretty JSON.
+fn render_result(v: &Value) -> String {
+    match v {
+        Value::String(s) => s.clone(),
+        other => serde_json::to_string_pretty(other).unwrap_or_else(|_| other.to_string()),
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Entry {
+    Command(String),
+    Output(String),
+    Error(String),
+    Info(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum Msg {
+    InputChanged(String),
+    Submit,
+    Confirm,
+    CancelConfirm,
+    Done(Result<Value, String>),
+    HistoryPrev,
+    HistoryNext,
+    Complete,
+    LoadCommands,
+    CommandsLoaded(Result<Vec<String>, String>),
+    Clear,
+}
+
+#[derive(Default)]
+pub struct State {
+    input: String,
+    entries: Vec<Entry>,
+    history: Vec<String>,
+    /// Index into `history` while browsing with ↑/↓.
+    history_pos: Option<usize>,
+    /// Method names for tab completion, from `help`.
+    commands: Vec<String>,
+    commands_loading: bool,
+    /// Destructive command awaiting confirmation, with its original line.
+    pending_confirm: Option<(Command, String)>,
+    running: bool,
+}
+
+impl State {
+    pub fn update(&mut self, msg: Msg, auth: RpcAuth) -> Task<Message> {
+        match msg {
+            Msg::InputChanged(s) => {
+                self.input = s;
+                self.history_pos = None;
+                Task::none()
+            }
+            Msg::Submit => self.submit(auth),
+            Msg::Confirm => match self.pending_confirm.take() {
+                Some((cmd, _)) => self.run(cmd, auth),
+                None => Task::none(),
+            },
+            Msg::CancelConfirm => {
+                if let Some((cmd, _)) = self.pending_confirm.take() {
+                    self.push(Entry::Info(format!("`{}` cancelled.", cmd.method)));
+                }
+                self.scroll_to_end()
+            }
+            Msg::Done(result) => {
+                self.running = false;
+                match result {
+                    Ok(v) => self.push(Entry::Output(render_result(&v))),
+                    Err(e) => self.push(Entry::Error(e)),
+                }
+                self.scroll_to_end()
+            }
+            Msg::HistoryPrev => {
+                if self.history.is_empty() {
+                    return Task::none();
+                }
+                let pos = self
+                    .history_pos
+                    .map_or(self.history.len() - 1, |p| p.saturating_sub(1));
+                self.history_pos = Some(pos);
+                self.input.clone_from(&self.history[pos]);
+                text_input::move_cursor_to_end(input_id())
+            }
+            Msg::HistoryNext => {
+                let Some(pos) = self.history_pos else {
+                    return Task::none();
+                };
+                if pos + 1 < self.history.len() {
+                    self.history_pos = Some(pos + 1);
+                    self.input.clone_from(&self.history[pos + 1]);
+                } else {
+                    self.history_pos = None;
+                    self.input.clear();
+                }
+                text_input::move_cursor_to_end(input_id())
+            }
+            Msg::Complete => {
+                let (new_input, candidates) = complete(&self.input, &self.commands);
+                let mut tasks = Vec::new();
+                if candidates.len() > 1 {
+                    self.push(Entry::Info(candidates.join("  ")));
+                    tasks.push(self.scroll_to_end());
+                }
+                if let Some(s) = new_input {
+                    self.input = s;
+                    tasks.push(text_input::move_cursor_to_end(input_id()));
+                }
+                tasks.push(text_input::focus(input_id()));
+                Task::batch(tasks)
+            }
+            Msg::LoadCommands => {
+                if !self.commands.is_empty() || self.commands_loading {
+                    return Task::none();
+                }
+                self.commands_loading = true;
+                Task::perform(
+                    async move {
+                        let help = rpc::call(&auth, "help", Value::Array(vec![]))
+                            .await
+                            .map_err(|e| e.to_string())?;
+                        Ok(parse_help_commands(help.as_str().unwrap_or_default()))
+                    },
+                    |r| Message::Console(Msg::CommandsLoaded(r)),
+                )
+            }
+            Msg::CommandsLoaded(result) => {
+                self.commands_loading = false;
+                if let Ok(commands) = result {
+                    self.commands = commands;
+                }
+                Task::none()
+            }
+            Msg::Clear => {
+                self.entries.clear();
+                Task::none()
+            }
+        }
+    }
+
+    fn submit(&mut self, auth: RpcAuth) -> Task<Message> {
+        let line = self.input.trim().to_owned();
+        if line.is_empty() || self.running {
+            return Task::none();
+        }
+        if line == "clear" {
+            self.input.clear();
+            return Task::done(Message::Console(Msg::Clear));
+        }
+        self.input.clear();
+        self.history_pos = None;
+        if self.history.last() != Some(&line) {
+            self.history.push(line.clone());
+        }
+        match Command::parse(&line) {
+            Ok(cmd) if cmd.is_destructive() => {
+                self.push(Entry::Command(line.clone()));
+                self.push(Entry::Info(format!(
+                    "`{}` is destructive — confirm below to run it.",
+                    cmd.method
+                )));
+                self.pending_confirm = Some((cmd, line));
+                self.scroll_to_end()
+            }
+            Ok(cmd) => {
+                self.push(Entry::Command(line));
+                self.run(cmd, auth)
+            }
+            Err(e) => {
+                self.push(Entry::Command(line));
+                self.push(Entry::Error(e));
+                self.scroll_to_end()
+            }
+        }
+    }
+
+    fn run(&mut self, cmd: Command, auth: RpcAuth) -> Task<Message> {
+        self.running = true;
+        // The node's exit that follows is then a stop, not a crash.
+        let stopping = if cmd.method == "stop" {
+            Task::done(Message::BitcoindStopRequested)
+        } else {
+            Task::none()
+        };
+        Task::batch([
+            stopping,
+            self.scroll_to_end(),
+            Task::perform(
+                async move {
+                    // Void methods (`setban`, `walletlock`, …) answer null.
+                    rpc::call_optional(&auth, &cmd.method, cmd.params)
+                        .await
+                        .map(Option::unwrap_or_default)
+                        .map_err(|e| e.to_string())
+                },
+                |r| Message::Console(Msg::Done(r)),
+            ),
+        ])
+    }
+
+    fn push(&mut self, entry: Entry) {
+        self.entries.push(entry);
+        if self.entries.len() > MAX_ENTRIES {
+            let excess = self.entries.len() - MAX_ENTRIES;
+            self.entries.drain(..excess);
+        }
+    }
+
+    #[allow(clippy::unused_self)]
+    fn scroll_to_end(&self) -> Task<Message> {
+        scrollable::snap_to(scroll_id(), scrollable::RelativeOffset::END)
+    }
+
+    /// Focus the command line (when the tab is opened).
+    pub fn focus() -> Task<Message> {
+        text_input::focus(input_id())
+    }
+
+    pub fn view(&self, bitcoin_running: bool) -> Element<'_, Message> {
+        let header = row![
+            section_label("RPC CONSOLE"),
+            text("  ↑/↓ history · Tab completes · \"help <method>\" for usage")
+                .size(10)
+                .color(TEXT_TER),
+            Space::with_width(Length::Fill),
+            styled_button("Clear", ButtonStyle::Secondary).on_press(Message::Console(Msg::Clear)),
+        ]
+        .align_y(Alignment::Center);
+
+        let lines: Vec<Element<Message>> = self
+            .entries
+            .iter()
+            .map(|e| {
+                let (s, color) = match e {
+                    Entry::Command(c) => (format!("> {c}"), MAC_BLUE),
+                    Entry::Output(o) => (o.clone(), TERM_FG),
+                    Entry::Error(err) => (err.clone(), MAC_RED),
+                    Entry::Info(i) => (i.clone(), MAC_ORG),
+                };
+                text(s).size(11).font(Font::MONOSPACE).color(color).into()
+            })
+            .collect();
+
+        let output = container(
+            scrollable(
+                column(lines)
+                    .spacing(4)
+                    .width(Length::Fill)
+                    .padding(Padding::from([8, 10])),
+            )
+            .id(scroll_id())
+            .height(Length::Fill)
+            .width(Length::Fill),
+        )
+        .height(Length::Fill)
+        .style(|_| container::Style {
+            background: Some(TERM_BG.into()),
+            ..Default::default()
+        });
+
+        let mut input = text_input(
+            if bitcoin_running {
+                "getblockchaininfo"
+            } else {
+                "Bitcoin is not running"
+            },
+            &self.input,
+        )
+        .id(input_id())
+        .font(Font::MONOSPACE)
+        .size(12)
+        .padding(6);
+        if bitcoin_running && self.pending_confirm.is_none() {
+            input = input
+                .on_input(|s| Message::Console(Msg::InputChanged(s)))
+                .on_submit(Message::Console(Msg::Submit));
+        }
+
+        let mut body = column![header, output].spacing(8);
+        if let Some((cmd, line)) = &self.pending_confirm {
+            body = body.push(
+                row![
+                    text(format!("Run `{line}`?")).size(12).color(MAC_RED),
+                    Space::with_width(Length::Fill),
+                    styled_button("Cancel", ButtonStyle::Secondary)
+                        .on_press(Message::Console(Msg::CancelConfirm)),
+                    styled_button(&cmd.method, ButtonStyle::Destructive)
+                        .on_press(Message::Console(Msg::Confirm)),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center),
+            );
+        }
+        body = body.push(input);
+
+        page(body)
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn positional_args_become_json_where_possible() {
+        let cmd = Command::parse("getblock 00000000deadbeef 2").unwrap();
+        assert_eq!(cmd.method, "getblock");
+        assert_eq!(cmd.params, json!(["00000000deadbeef", 2]));
+
+        let cmd = Command::parse(r#"setlabel bc1qxyz "123""#).unwrap();
+        assert_eq!(cmd.params, json!(["bc1qxyz", "123"]));
+
+        let cmd = Command::parse(r#"createpsbt [] [{"bc1qxyz": 0.1}] 0 true"#).unwrap();
+        assert_eq!(cmd.params, json!([[], [{ "bc1qxyz": 0.1 }], 0, true]));
+
+        let cmd = Command::parse(r#"signmessage bc1q 'hello world' "say \"hi\"""#).unwrap();
+        assert_eq!(cmd.params, json!(["bc1q", "hello world", "say \"hi\""]));
+    }
+
+    #[test]
+    fn named_args() {
+        let cmd = Command::parse(r#"-named createwallet wallet_name="my wallet" descriptors=true"#)
+            .unwrap();
+        assert_eq!(cmd.method, "createwallet");
+        assert_eq!(
+            cmd.params,
+            json!({ "wallet_name": "my wallet", "descriptors": true })
+        );
+
+        let cmd = Command::parse("-named getblock abc verbosity=0").unwrap();
+        assert_eq!(cmd.params, json!({ "verbosity": 0, "args": ["abc"] }));
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        assert!(Command::parse("").is_err());
+        assert!(Command::parse(r#"echo "open"#).is_err());
+        assert!(Command::parse("createpsbt [[]").is_err());
+        assert!(Command::parse("-named").is_err());
+    }
+
+    #[test]
+    fn completes_from_help() {
+        let help = "== Blockchain ==\ngetbestblockhash\ngetblock \"blockhash\" ( verbosity )\n\
+                    getblockchaininfo\n\n== Control ==\nstop\n";
+        let commands = parse_help_commands(help);
+        assert_eq!(
+            commands,
+            vec!["getbestblockhash", "getblock", "getblockchaininfo", "stop"]
+        );
+
+        assert_eq!(
+            complete("st", &commands),
+            (Some("stop ".to_owned()), vec![])
+        );
+        // Ambiguous and nothing longer in common: list the candidates.
+        let (input, candidates) = complete("getb", &commands);
+        assert_eq!(input, None);
+        assert_eq!(candidates.len(), 3);
+        assert_eq!(
+            complete("getblockc", &commands).0.as_deref(),
+            Some("getblockchaininfo ")
+        );
+        assert_eq!(complete("getblock 1", &commands), (None, vec![]));
+        assert_eq!(
+            complete("-named sto", &commands).0.as_deref(),
+            Some("-named stop ")
+        );
+    }
+
+    #[test]
+    fn destructive_methods_need_confirmation() {
+        assert!(Command::parse("stop").unwrap().is_destructive());
+        assert!(Command::parse("invalidateblock 00ab")
+            .unwrap()
+            .is_destructive());
+        assert!(!Command::parse("getblockcount").unwrap().is_destructive());
+        for line in [
+            "encryptwallet hunter2",
+            "dumptxoutset utxo.dat rollback",
+            "setban 203.0.113.5 add",
+            "disconnectnode \"\" 7",
+        ] {
+            assert!(Command::parse(line).unwrap().is_destructive(), "{line}");
+        }
+    }
+
+    #[test]
+    fn null_results_render_as_null() {
+        assert_eq!(render_result(&Value::Null), "null");
+        assert_eq!(render_result(&Value::from("stopping")), "stopping");
+    }
+}
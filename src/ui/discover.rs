@@ -0,0 +1,183 @@
+//! Existing installations in the paths panel: node data found in the usual
+//! bitcoind and electrs locations or on the SSD, with a button to use each
+//! one instead of the default directories.
+//!
+//! On first run (no `config.json` yet) the scan starts by itself and the
+//! paths panel opens when it finds anything.
+
+use std::path::{Path, PathBuf};
+
+use iced::{
+    widget::{column, row, text, Space},
+    Alignment, Element, Font, Length, Padding, Task,
+};
+
+use super::{
+    format_bytes, format_thousands, section_label, styled_button, ButtonStyle, Message, GREEN,
+    MAC_ORG, TEXT_SEC, TEXT_TER,
+};
+use crate::discover::{self, Found, Kind, Network};
+
+#[derive(Debug, Clone)]
+pub enum Msg {
+    Scan,
+    Scanned(Vec<Found>),
+    /// Use the entry at this index.
+    Adopt(usize),
+    Dismiss,
+}
+
+pub struct State {
+    ssd_root: PathBuf,
+    open: bool,
+    scanning: bool,
+    /// The first-run scan: only open if it finds something.
+    quiet: bool,
+    found: Vec<Found>,
+}
+
+impl State {
+    pub fn new(ssd_root: &Path) -> Self {
+        Self {
+            ssd_root: ssd_root.to_owned(),
+            open: false,
+            scanning: false,
+            quiet: false,
+            found: Vec::new(),
+        }
+    }
+
+    /// The panel has something to show.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Scan without opening the panel unless something turns up.
+    pub fn scan_quietly(&mut self) -> Task<Message> {
+        self.quiet = true;
+        self.update(Msg::Scan)
+    }
+
+    pub fn update(&mut self, msg: Msg) -> Task<Message> {
+        match msg {
+            Msg::Scan => {
+                if self.scanning {
+                    return Task::none();
+                }
+                self.open = !self.quiet;
+                self.scanning = true;
+                let root = self.ssd_root.clone();
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || discover::scan(&root))
+                            .await
+                            .unwrap_or_default()
+                    },
+                    |found| Message::Discover(Msg::Scanned(found)),
+                )
+            }
+            Msg::Scanned(found) => {
+                self.scanning = false;
+                if self.quiet {
+                    self.quiet = false;
+                    self.open = !found.is_empty();
+                }
+                self.found = found;
+                Task::none()
+            }
+            Msg::Adopt(i) => match self.found.get(i) {
+                Some(found) => {
+                    Task::done(Message::InstallationAdopted(found.kind, found.path.clone()))
+                }
+                None => Task::none(),
+            },
+            Msg::Dismiss => {
+                self.open = false;
+                Task::none()
+            }
+        }
+    }
+
+    /// `bitcoin_data` and `electrs_data` are the configured directories,
+    /// marked as in use.
+    pub fn view(&self, bitcoin_data: &Path, electrs_data: &Path) -> Element<'_, Message> {
+        if !self.open {
+            return Space::with_height(0).into();
+        }
+        let mut col = column![row![
+            section_label("EXISTING INSTALLATIONS"),
+            Space::with_width(Length::Fill),
+            styled_button("Rescan", ButtonStyle::Secondary)
+                .on_press_maybe((!self.scanning).then_some(Message::Discover(Msg::Scan))),
+            Space::with_width(6),
+            styled_button("Close", ButtonStyle::Secondary)
+                .on_press(Message::Discover(Msg::Dismiss)),
+        ]
+        .align_y(Alignment::Center)]
+        .spacing(4);
+
+        if self.scanning {
+            col = col.push(text("Looking for node data…").size(11).color(TEXT_SEC));
+        } else if self.found.is_empty() {
+            col = col.push(
+                text("No existing node data found; the default directories will be used.")
+                    .size(11)
+                    .color(TEXT_SEC),
+            );
+        }
+
+        for (i, found) in self.found.iter().enumerate() {
+            let current = match found.kind {
+                Kind::Bitcoin => bitcoin_data,
+                Kind::Electrs => electrs_data,
+            };
+            let tip = found
+                .tip
+                .map_or_else(String::new, |h| format!(", tip {}", format_thousands(h)));
+            let action: Element<'_, Message> = if found.path == current {
+                text("In use").size(11).color(GREEN).into()
+            } else {
+                styled_button("Use This", ButtonStyle::Primary)
+                    .on_press(Message::Discover(Msg::Adopt(i)))
+                    .into()
+            };
+            col = col.push(
+                row![
+                    text(found.kind.to_string())
+                        .size(11)
+                        .color(TEXT_SEC)
+                        .width(Length::Fixed(90.0)),
+                    text(found.path.display().to_string())
+                        .size(11)
+                        .font(Font::MONOSPACE),
+                    Space::with_width(10),
+                    text(format!(
+                        "{}, {}{tip}",
+                        found.network,
+                        format_bytes(found.size_bytes)
+                    ))
+                    .size(11)
+                    .color(if found.network == Network::Main {
+                        TEXT_SEC
+                    } else {
+                        MAC_ORG
+                    }),
+                    Space::with_width(Length::Fill),
+                    action,
+                ]
+                .align_y(Alignment::Center),
+            );
+        }
+
+        col.push(
+            text(
+                "Using a directory only changes the config; nothing is copied or moved. \
+                 The manager monitors mainnet, so other networks are shown in orange.",
+            )
+            .size(10)
+            .color(TEXT_TER),
+        )
+        .padding(Padding::from([6, 0]))
+        .into()
+    }
+}
@@ -0,0 +1,544 @@
+//! Events tab — the node's chain tips (`getchaintips`), alert rules, event
+//! hook scripts, and the persistent event log: reorgs, stalls, alerts and
+//! broadcasts, newest first.
+//!
+//! Detection, alerts and hooks run in `App` so they work whichever tab is
+//! open; this tab only edits their settings and shows what was recorded.
+
+use std::fmt;
+
+use iced::{
+    widget::{column, container, pick_list, row, scrollable, text, text_input, Space},
+    Alignment, Color, Element, Length, Task,
+};
+
+use super::{
+    format_thousands, format_unix_time, horizontal_rule, link_button, page, section_label,
+    styled_button, styled_checkbox, ButtonStyle, Message, GREEN, MAC_ORG, MAC_RED, TEXT_SEC,
+    TEXT_TER,
+};
+use crate::{
+    alerts::Rule,
+    config::{AlertsConfig, HooksConfig},
+    history::{self, Kind},
+    hooks,
+    rpc::{self, ChainTip, RpcAuth},
+};
+
+/// Log entries kept on screen.
+const LOG_SHOWN: usize = 200;
+
+/// Chain tips listed; long-running nodes collect many old forks.
+const TIPS_SHOWN: usize = 50;
+
+#[derive(Debug, Clone)]
+pub enum Msg {
+    Refresh,
+    TipsLoaded(Result<Vec<ChainTip>, String>),
+    LoadLog,
+    LogLoaded(Result<Vec<history::Entry>, String>),
+    /// An entry `App` has just appended to the history.
+    Recorded(history::Entry),
+    StallMinutesChanged(String),
+    SaveStallMinutes,
+    AlertKindSelected(RuleKind),
+    AlertThresholdChanged(String),
+    AddAlertRule,
+    RemoveAlertRule(usize),
+    WebhookChanged(String),
+    CommandChanged(String),
+    SaveAlertActions,
+    TestAlert,
+    HookScriptChanged(hooks::Event, String),
+    BlocknotifyToggled(bool),
+    WalletnotifyChanged(String),
+    SaveHooks,
+}
+
+/// A rule kind in the picker, labelled without its threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleKind(Rule);
+
+impl fmt::Display for RuleKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0.kind_label())
+    }
+}
+
+pub struct State {
+    tips: Vec<ChainTip>,
+    loading: bool,
+    log: Option<Vec<history::Entry>>,
+    stall_edit: String,
+    alert_rules: Vec<Rule>,
+    new_kind: RuleKind,
+    threshold_edit: String,
+    webhook_edit: String,
+    command_edit: String,
+    hooks: HooksConfig,
+    error: Option<String>,
+}
+
+impl State {
+    pub fn new(stall_minutes: u64, alerts: &AlertsConfig, hooks: HooksConfig) -> Self {
+        let new_kind = RuleKind(Rule::KINDS[0]);
+        Self {
+            tips: Vec::new(),
+            loading: false,
+            log: None,
+            stall_edit: stall_minutes.to_string(),
+            alert_rules: alerts.rules.clone(),
+            new_kind,
+            threshold_edit: String::new(),
+            webhook_edit: alerts.webhook_url.clone(),
+            command_edit: alerts.command.clone(),
+            hooks,
+            error: None,
+        }
+    }
+
+    #[allow(clippy::too_many_lines)]
+    pub fn update(&mut self, msg: Msg, auth: RpcAuth) -> Task<Message> {
+        match msg {
+            Msg::Refresh => {
+                if self.loading {
+                    return Task::none();
+                }
+                self.loading = true;
+                Task::perform(
+                    async move { rpc::get_chain_tips(&auth).await.map_err(|e| e.to_string()) },
+                    |r| Message::Events(Msg::TipsLoaded(r)),
+                )
+            }
+            Msg::TipsLoaded(result) => {
+                self.loading = false;
+                match result {
+                    Ok(mut tips) => {
+                        tips.sort_by_key(|t| std::cmp::Reverse(t.height));
+                        tips.truncate(TIPS_SHOWN);
+                        self.tips = tips;
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+                Task::none()
+            }
+            // Re-read on every visit: broadcasts are appended elsewhere.
+            Msg::LoadLog => Task::perform(
+                async {
+                    tokio::task::spawn_blocking(|| history::recent(LOG_SHOWN))
+                        .await
+                        .map_err(|e| e.to_string())?
+                        .map_err(|e| format!("{e:#}"))
+                },
+                |r| Message::Events(Msg::LogLoaded(r)),
+            ),
+            Msg::LogLoaded(result) => {
+                match result {
+                    Ok(mut entries) => {
+                        entries.reverse();
+                        self.log = Some(entries);
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+                Task::none()
+            }
+            Msg::Recorded(entry) => {
+                // Before the first load the entry is picked up from disk.
+                if let Some(log) = &mut self.log {
+                    log.insert(0, entry);
+                    log.truncate(LOG_SHOWN);
+                }
+                Task::none()
+            }
+            Msg::StallMinutesChanged(s) => {
+                self.stall_edit = s;
+                Task::none()
+            }
+            Msg::SaveStallMinutes => {
+                let Ok(minutes) = self.stall_edit.trim().parse::<u64>() else {
+                    self.error = Some("Stall period must be a whole number of minutes.".into());
+                    return Task::none();
+                };
+                self.error = None;
+                Task::done(Message::StallMinutesSaved(minutes))
+            }
+            Msg::AlertKindSelected(kind) => {
+                self.new_kind = kind;
+                self.threshold_edit = kind
+                    .0
+                    .threshold()
+                    .map(|t| t.to_string())
+                    .unwrap_or_default();
+                Task::none()
+            }
+            Msg::AlertThresholdChanged(s) => {
+                self.threshold_edit = s;
+                Task::none()
+            }
+            Msg::AddAlertRule => self.add_alert_rule(),
+            Msg::RemoveAlertRule(i) => {
+                if i < self.alert_rules.len() {
+                    self.alert_rules.remove(i);
+                }
+                self.save_alerts()
+            }
+            Msg::WebhookChanged(s) => {
+                self.webhook_edit = s;
+                Task::none()
+            }
+            Msg::CommandChanged(s) => {
+                self.command_edit = s;
+                Task::none()
+            }
+            Msg::SaveAlertActions => self.save_alerts(),
+            Msg::TestAlert => Task::done(Message::TestAlert {
+                webhook_url: self.webhook_edit.trim().to_owned(),
+                command: self.command_edit.trim().to_owned(),
+            }),
+            Msg::HookScriptChanged(event, s) => {
+                self.hooks.scripts.insert(event, s);
+                Task::none()
+            }
+            Msg::BlocknotifyToggled(on) => {
+                self.hooks.blocknotify = on;
+                self.save_hooks()
+            }
+            Msg::WalletnotifyChanged(s) => {
+                self.hooks.walletnotify = s;
+                Task::none()
+            }
+            Msg::SaveHooks => self.save_hooks(),
+        }
+    }
+
+    fn add_alert_rule(&mut self) -> Task<Message> {
+        let kind = self.new_kind.0;
+        let rule = if kind.threshold().is_none() {
+            kind
+        } else {
+            let Ok(value) = self.threshold_edit.trim().parse::<u64>() else {
+                self.error = Some("Alert threshold must be a whole number.".into());
+                return Task::none();
+            };
+            kind.with_threshold(value)
+        };
+        self.error = None;
+        if !self.alert_rules.contains(&rule) {
+            self.alert_rules.push(rule);
+        }
+        self.save_alerts()
+    }
+
+    fn save_hooks(&self) -> Task<Message> {
+        let scripts = self
+            .hooks
+            .scripts
+            .iter()
+            .map(|(event, script)| (*event, script.trim().to_owned()))
+            .filter(|(_, script)| !script.is_empty())
+            .collect();
+        Task::done(Message::HooksSaved(HooksConfig {
+            scripts,
+            blocknotify: self.hooks.blocknotify,
+            walletnotify: self.hooks.walletnotify.trim().to_owned(),
+        }))
+    }
+
+    fn save_alerts(&self) -> Task<Message> {
+        Task::done(Message::AlertsSaved(AlertsConfig {
+            rules: self.alert_rules.clone(),
+            webhook_url: self.webhook_edit.trim().to_owned(),
+            command: self.command_edit.trim().to_owned(),
+        }))
+    }
+
+    pub fn view(&self, bitcoin_running: bool) -> Element<'_, Message> {
+        let header = row![
+            section_label("CHAIN TIPS"),
+            Space::with_width(Length::Fill),
+            styled_button("Refresh", ButtonStyle::Secondary)
+                .on_press_maybe(bitcoin_running.then_some(Message::Events(Msg::Refresh))),
+        ]
+        .align_y(Alignment::Center);
+
+        let mut body = column![header].spacing(8);
+        if !bitcoin_running {
+            body = body.push(text("Bitcoin is not running.").size(11).color(TEXT_TER));
+        }
+        if let Some(e) = &self.error {
+            body = body.push(text(e.as_str()).size(11).color(MAC_RED));
+        }
+        body = body
+            .push(self.view_tips())
+            .push(horizontal_rule())
+            .push(self.view_alerts())
+            .push(horizontal_rule())
+            .push(self.view_hooks())
+            .push(horizontal_rule())
+            .push(self.view_log());
+
+        page(scrollable(body).height(Length::Fill))
+    }
+
+    fn view_tips(&self) -> Element<'_, Message> {
+        let mut col = column![row![
+            cell("Height", 2, TEXT_TER),
+            cell("Status", 2, TEXT_TER),
+            cell("Forked after", 2, TEXT_TER),
+            container(text("Hash").size(11).color(TEXT_TER)).width(Length::FillPortion(8)),
+        ]
+        .spacing(4)]
+        .spacing(2);
+        for tip in &self.tips {
+            col = col.push(
+                row![
+                    cell(&format_thousands(tip.height), 2, TEXT_SEC),
+                    cell(&tip.status, 2, status_color(&tip.status)),
+                    cell(&fork_label(tip), 2, TEXT_SEC),
+                    container(link_button(
+                        &tip.hash,
+                        Message::ExploreBlock(tip.hash.clone())
+                    ))
+                    .width(Length::FillPortion(8)),
+                ]
+                .spacing(4)
+                .align_y(Alignment::Center),
+            );
+        }
+        col.into()
+    }
+
+    fn view_alerts(&self) -> Element<'_, Message> {
+        let mut col = column![
+            section_label("ALERTS"),
+            text(
+                "Checked every 5 s. Each alert is sent once when it fires and again \
+                 when it clears."
+            )
+            .size(11)
+            .color(TEXT_TER),
+        ]
+        .spacing(6);
+
+        if self.alert_rules.is_empty() {
+            col = col.push(text("No rules.").size(11).color(TEXT_TER));
+        }
+        for (i, rule) in self.alert_rules.iter().enumerate() {
+            col = col.push(
+                row![
+                    text(rule.to_string()).size(12).color(TEXT_SEC).width(240),
+                    styled_button("Remove", ButtonStyle::Secondary)
+                        .on_press(Message::Events(Msg::RemoveAlertRule(i))),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center),
+            );
+        }
+
+        let kinds = Rule::KINDS.map(RuleKind);
+        let mut add = row![pick_list(kinds, Some(self.new_kind), |k| {
+            Message::Events(Msg::AlertKindSelected(k))
+        })
+        .text_size(12)
+        .width(220)]
+        .spacing(8)
+        .align_y(Alignment::Center);
+        if let Some(default) = self.new_kind.0.threshold() {
+            add = add.push(
+                text_input(&default.to_string(), &self.threshold_edit)
+                    .on_input(|s| Message::Events(Msg::AlertThresholdChanged(s)))
+                    .on_submit(Message::Events(Msg::AddAlertRule))
+                    .size(12)
+                    .padding(6)
+                    .width(70),
+            );
+        }
+        col = col.push(
+            add.push(
+                styled_button("Add Rule", ButtonStyle::Secondary)
+                    .on_press(Message::Events(Msg::AddAlertRule)),
+            ),
+        );
+
+        let action = |label, placeholder, value: &str, on_input: fn(String) -> Msg| {
+            row![
+                text(label).size(11).color(TEXT_TER).width(90),
+                text_input(placeholder, value)
+                    .on_input(move |s| Message::Events(on_input(s)))
+                    .on_submit(Message::Events(Msg::SaveAlertActions))
+                    .size(12)
+                    .padding(6),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center)
+        };
+        col.push(action(
+            "Webhook URL",
+            "http://127.0.0.1:8080/topic (JSON POST)",
+            &self.webhook_edit,
+            Msg::WebhookChanged,
+        ))
+        .push(action(
+            "Script",
+            "/path/to/script (BNM_ALERT_* env, JSON on stdin)",
+            &self.command_edit,
+            Msg::CommandChanged,
+        ))
+        .push(
+            row![
+                styled_button("Save", ButtonStyle::Secondary)
+                    .on_press(Message::Events(Msg::SaveAlertActions)),
+                styled_button("Send Test", ButtonStyle::Secondary)
+                    .on_press(Message::Events(Msg::TestAlert)),
+            ]
+            .spacing(8),
+        )
+        .into()
+    }
+
+    fn view_hooks(&self) -> Element<'_, Message> {
+        let script_row = |label, value: &str, on_input: Box<dyn Fn(String) -> Msg>| {
+            row![
+                text(label).size(11).color(TEXT_TER).width(120),
+                text_input("/path/to/script", value)
+                    .on_input(move |s| Message::Events(on_input(s)))
+                    .on_submit(Message::Events(Msg::SaveHooks))
+                    .size(12)
+                    .padding(6),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center)
+        };
+
+        let mut col = column![
+            section_label("HOOKS"),
+            text(
+                "Scripts run on these events with BNM_EVENT, BNM_PROCESS, BNM_DATA_DIR and \
+                 event details in the environment; output goes to the terminal panel. \
+                 The new-block script also gets the block hash as its first argument."
+            )
+            .size(11)
+            .color(TEXT_TER),
+        ]
+        .spacing(6);
+        for event in hooks::Event::ALL {
+            let value = self.hooks.scripts.get(&event).map_or("", String::as_str);
+            col = col.push(script_row(
+                event.label(),
+                value,
+                Box::new(move |s| Msg::HookScriptChanged(event, s)),
+            ));
+        }
+        col.push(
+            styled_checkbox(
+                "Let bitcoind run the new-block script (-blocknotify, once per block)",
+                self.hooks.blocknotify,
+            )
+            .on_toggle(|on| Message::Events(Msg::BlocknotifyToggled(on))),
+        )
+        .push(script_row(
+            "Wallet tx",
+            &self.hooks.walletnotify,
+            Box::new(Msg::WalletnotifyChanged),
+        ))
+        .push(
+            row![
+                styled_button("Save", ButtonStyle::Secondary)
+                    .on_press(Message::Events(Msg::SaveHooks)),
+                text(
+                    "The wallet script runs as -walletnotify (txid, wallet name). \
+                     bitcoind picks up notify changes on its next launch."
+                )
+                .size(11)
+                .color(TEXT_TER),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        )
+        .into()
+    }
+
+    fn view_log(&self) -> Element<'_, Message> {
+        let stall_input = text_input("90", &self.stall_edit)
+            .on_input(|s| Message::Events(Msg::StallMinutesChanged(s)))
+            .on_submit(Message::Events(Msg::SaveStallMinutes))
+            .size(12)
+            .padding(6)
+            .width(70);
+        let header = row![
+            section_label("EVENT LOG"),
+            Space::with_width(Length::Fill),
+            text("Log a stall after").size(11).color(TEXT_TER),
+            stall_input,
+            text("minutes without a block (0 = off)")
+                .size(11)
+                .color(TEXT_TER),
+            styled_button("Save", ButtonStyle::Secondary)
+                .on_press(Message::Events(Msg::SaveStallMinutes)),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center);
+
+        let mut col = column![header].spacing(4);
+        let entries = self.log.as_deref().unwrap_or_default();
+        if entries.is_empty() {
+            col = col.push(text("Nothing recorded yet.").size(11).color(TEXT_TER));
+        }
+        for e in entries {
+            let mut line = row![
+                text(format_unix_time(e.time))
+                    .size(11)
+                    .color(TEXT_TER)
+                    .width(170),
+                text(e.kind.to_string())
+                    .size(11)
+                    .color(kind_color(e.kind))
+                    .width(70),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center);
+            if let Some(txid) = &e.txid {
+                line = line.push(link_button(txid, Message::ExploreTx(txid.clone())));
+            }
+            col = col.push(line.push(text(e.message.as_str()).size(11).color(TEXT_SEC)));
+        }
+        col.into()
+    }
+}
+
+fn cell<'a>(value: &str, portion: u16, color: Color) -> Element<'a, Message> {
+    container(text(value.to_owned()).size(11).color(color))
+        .width(Length::FillPortion(portion))
+        .into()
+}
+
+/// Fork point and branch length, e.g. `812,345 (+2)`; blank for the active tip.
+fn fork_label(tip: &ChainTip) -> String {
+    if tip.branchlen == 0 {
+        String::new()
+    } else {
+        format!(
+            "{} (+{})",
+            format_thousands(tip.fork_height()),
+            tip.branchlen
+        )
+    }
+}
+
+fn status_color(status: &str) -> Color {
+    match status {
+        "active" => GREEN,
+        "invalid" => MAC_RED,
+        "valid-fork" => MAC_ORG,
+        _ => TEXT_SEC,
+    }
+}
+
+fn kind_color(kind: Kind) -> Color {
+    match kind {
+        Kind::Reorg => MAC_RED,
+        Kind::Stall | Kind::Alert => MAC_ORG,
+        Kind::Resumed => GREEN,
+        Kind::Broadcast => TEXT_SEC,
+    }
+}
@@ -0,0 +1,478 @@
+//! Explorer tab — look up blocks by height or hash and transactions by txid
+//! against the local node, with links between them.
+
+use iced::{
+    widget::{column, row, scrollable, text, text_input, Space},
+    Alignment, Color, Element, Length, Task,
+};
+
+use super::{
+    field, format_btc, format_thousands, format_unix_time, horizontal_rule, link_button, page,
+    section_label, styled_button, ButtonStyle, Message, MAC_RED, TEXT_SEC, TEXT_TER,
+};
+use crate::rpc::{self, Block, RpcAuth, Transaction};
+
+/// Transactions listed per "page" of a block.
+const TX_PAGE: usize = 100;
+
+/// Something the explorer can open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    Height(u64),
+    Block(String),
+    Tx(String),
+    /// A 64-hex-digit string that may be either a block hash or a txid.
+    Hash(String),
+}
+
+impl Target {
+    /// Interpret the search box: a number is a height, 64 hex digits a hash
+    /// or txid.
+    pub fn parse(query: &str) -> Result<Self, String> {
+        let q = query.trim().replace(',', "");
+        if q.is_empty() {
+            return Err("Enter a block height, block hash or txid.".to_owned());
+        }
+        if let Ok(height) = q.parse::<u64>() {
+            return Ok(Self::Height(height));
+        }
+        if q.len() == 64 && q.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Ok(Self::Hash(q.to_ascii_lowercase()));
+        }
+        Err(format!(
+            "“{}” is not a height, block hash or txid.",
+            query.trim()
+        ))
+    }
+}
+
+/// What is currently displayed.
+#[derive(Debug, Clone)]
+pub enum Page {
+    Block(Box<Block>),
+    Tx(Box<Transaction>),
+}
+
+#[derive(Debug, Clone)]
+pub enum Msg {
+    QueryChanged(String),
+    Search,
+    Open(Target),
+    Loaded(Result<Page, String>),
+    Back,
+    ShowMoreTxs,
+}
+
+#[derive(Default)]
+pub struct State {
+    query: String,
+    current: Option<(Target, Page)>,
+    /// Previously shown targets, most recent last.
+    back: Vec<Target>,
+    /// Target being fetched, and whether to push the current page on `back`.
+    pending: Option<(Target, bool)>,
+    tx_limit: usize,
+    error: Option<String>,
+}
+
+impl State {
+    pub fn update(&mut self, msg: Msg, auth: RpcAuth) -> Task<Message> {
+        match msg {
+            Msg::QueryChanged(q) => {
+                self.query = q;
+                Task::none()
+            }
+            Msg::Search => match Target::parse(&self.query) {
+                Ok(target) => self.load(target, true, auth),
+                Err(e) => {
+                    self.error = Some(e);
+                    Task::none()
+                }
+            },
+            Msg::Open(target) => self.load(target, true, auth),
+            Msg::Back => match self.back.pop() {
+                Some(target) => self.load(target, false, auth),
+                None => Task::none(),
+            },
+            Msg::Loaded(result) => {
+                let Some((target, push)) = self.pending.take() else {
+                    return Task::none();
+                };
+                match result {
+                    Ok(page) => {
+                        if let Some((previous, _)) = self.current.take() {
+                            if push {
+                                self.back.push(previous);
+                            }
+                        }
+                        self.current = Some((target, page));
+                        self.tx_limit = TX_PAGE;
+                        self.error = None;
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+                Task::none()
+            }
+            Msg::ShowMoreTxs => {
+                self.tx_limit += TX_PAGE;
+                Task::none()
+            }
+        }
+    }
+
+    fn load(&mut self, target: Target, push: bool, auth: RpcAuth) -> Task<Message> {
+        self.pending = Some((target.clone(), push));
+        self.error = None;
+        Task::perform(
+            async move { fetch(&auth, target).await.map_err(|e| e.to_string()) },
+            |r| Message::Explorer(Msg::Loaded(r)),
+        )
+    }
+
+    pub fn view(&self, bitcoin_running: bool, tip: u64) -> Element<'_, Message> {
+        let search = text_input("Block height, block hash or txid", &self.query)
+            .on_input(|q| Message::Explorer(Msg::QueryChanged(q)))
+            .on_submit(Message::Explorer(Msg::Search))
+            .size(13)
+            .padding(6);
+
+        let header = row![
+            section_label("EXPLORER"),
+            Space::with_width(12),
+            search,
+            styled_button("Search", ButtonStyle::Primary)
+                .on_press_maybe(bitcoin_running.then_some(Message::Explorer(Msg::Search))),
+            styled_button("Latest Block", ButtonStyle::Secondary).on_press_maybe(
+                (bitcoin_running && tip > 0)
+                    .then_some(Message::Explorer(Msg::Open(Target::Height(tip)))),
+            ),
+            styled_button("Back", ButtonStyle::Secondary)
+                .on_press_maybe((!self.back.is_empty()).then_some(Message::Explorer(Msg::Back))),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center);
+
+        let mut body = column![header].spacing(10);
+        if self.pending.is_some() {
+            body = body.push(text("Loading…").size(11).color(TEXT_TER));
+        }
+        if let Some(e) = &self.error {
+            body = body.push(text(e.as_str()).size(11).color(MAC_RED));
+        }
+        if !bitcoin_running {
+            body = body.push(text("Bitcoin is not running.").size(11).color(TEXT_TER));
+        }
+        match &self.current {
+            Some((_, Page::Block(block))) => body = body.push(self.view_block(block)),
+            Some((_, Page::Tx(tx))) => body = body.push(view_tx(tx)),
+            None => {}
+        }
+
+        page(scrollable(body).height(Length::Fill))
+    }
+
+    fn view_block<'a>(&self, block: &'a Block) -> Element<'a, Message> {
+        let h = &block.header;
+        let nav = row![
+            h.previousblockhash.as_ref().map_or_else(
+                || text("◀ Previous").size(11).color(TEXT_TER).into(),
+                |prev| link_button("◀ Previous", open(Target::Block(prev.clone()))),
+            ),
+            Space::with_width(16),
+            h.nextblockhash.as_ref().map_or_else(
+                || text("Next ▶").size(11).color(TEXT_TER).into(),
+                |next| link_button("Next ▶", open(Target::Block(next.clone()))),
+            ),
+        ];
+
+        let fees = block
+            .total_fees()
+            .map_or_else(|| "—".to_owned(), format_btc);
+        let details = column![
+            text(format!("Block {}", format_thousands(h.height)))
+                .size(18)
+                .color(Color::BLACK),
+            field("Hash", h.hash.clone()),
+            field("Confirmations", confirmations(h.confirmations)),
+            field(
+                "Time",
+                format!(
+                    "{}  (median {})",
+                    format_unix_time(h.time),
+                    format_unix_time(h.mediantime)
+                )
+            ),
+            field("Transactions", format_thousands(h.n_tx)),
+            field(
+                "Size / weight",
+                format!(
+                    "{} bytes ({} stripped)  ·  {} WU",
+                    format_thousands(block.size),
+                    format_thousands(block.strippedsize),
+                    format_thousands(block.weight)
+                )
+            ),
+            field("Fees", fees),
+            field("Difficulty", format!("{:.0}", h.difficulty)),
+            field("Version", format!("{:#010x}", h.version)),
+            field("Merkle root", h.merkleroot.clone()),
+            nav,
+        ]
+        .spacing(4);
+
+        let mut txs = column![
+            section_label("TRANSACTIONS"),
+            tx_row_header(),
+            horizontal_rule()
+        ]
+        .spacing(3);
+        for tx in block.tx.iter().take(self.tx_limit) {
+            txs = txs.push(tx_row(tx));
+        }
+        if block.tx.len() > self.tx_limit {
+            txs = txs.push(
+                row![
+                    styled_button("Show More", ButtonStyle::Secondary)
+                        .on_press(Message::Explorer(Msg::ShowMoreTxs)),
+                    text(format!(
+                        "{} more transactions",
+                        format_thousands((block.tx.len() - self.tx_limit) as u64)
+                    ))
+                    .size(11)
+                    .color(TEXT_TER),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center),
+            );
+        }
+
+        column![details, horizontal_rule(), txs].spacing(10).into()
+    }
+}
+
+async fn fetch(auth: &RpcAuth, target: Target) -> anyhow::Result<Page> {
+    let block = |hash: String| async move {
+        rpc::get_block(auth, &hash)
+            .await
+            .map(|b| Page::Block(Box::new(b)))
+    };
+    match target {
+        Target::Height(height) => block(rpc::get_block_hash(auth, height).await?).await,
+        Target::Block(hash) => block(hash).await,
+        Target::Tx(txid) => Ok(Page::Tx(Box::new(rpc::get_transaction(auth, &txid).await?))),
+        Target::Hash(hash) => {
+            if rpc::get_block_header(auth, &hash).await.is_ok() {
+                block(hash).await
+            } else {
+                rpc::get_transaction(auth, &hash)
+                    .await
+                    .map(|tx| Page::Tx(Box::new(tx)))
+                    .map_err(|e| anyhow::anyhow!("No block or transaction {hash}: {e}"))
+            }
+        }
+    }
+}
+
+fn open(target: Target) -> Message {
+    Message::Explorer(Msg::Open(target))
+}
+
+fn confirmations(n: i64) -> String {
+    if n < 0 {
+        "stale (not on the active chain)".to_owned()
+    } else {
+        format_thousands(n.unsigned_abs())
+    }
+}
+
+fn tx_row_header<'a>() -> Element<'a, Message> {
+    row![
+        text("TXID")
+            .size(10)
+            .color(TEXT_TER)
+            .width(Length::FillPortion(6)),
+        text("VSIZE")
+            .size(10)
+            .color(TEXT_TER)
+            .width(Length::FillPortion(1)),
+        text("FEE RATE")
+            .size(10)
+            .color(TEXT_TER)
+            .width(Length::FillPortion(2)),
+        text("OUTPUT")
+            .size(10)
+            .color(TEXT_TER)
+            .width(Length::FillPortion(2)),
+    ]
+    .spacing(8)
+    .into()
+}
+
+fn tx_row(tx: &Transaction) -> Element<'_, Message> {
+    let rate = if tx.is_coinbase() {
+        "coinbase".to_owned()
+    } else {
+        tx.fee_rate()
+            .map_or_else(|| "—".to_owned(), |r| format!("{r:.1} sat/vB"))
+    };
+    row![
+        row![link_button(&tx.txid, open(Target::Tx(tx.txid.clone())))]
+            .width(Length::FillPortion(6)),
+        text(tx.vsize.to_string())
+            .size(11)
+            .color(TEXT_SEC)
+            .width(Length::FillPortion(1)),
+        text(rate)
+            .size(11)
+            .color(TEXT_SEC)
+            .width(Length::FillPortion(2)),
+        text(format_btc(tx.output_total()))
+            .size(11)
+            .color(TEXT_SEC)
+            .width(Length::FillPortion(2)),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center)
+    .into()
+}
+
+#[allow(clippy::too_many_lines)]
+fn view_tx(tx: &Transaction) -> Element<'_, Message> {
+    let block: Element<Message> = match &tx.blockhash {
+        Some(hash) => link_button(hash, open(Target::Block(hash.clone()))),
+        None => text("unconfirmed (in mempool)")
+            .size(11)
+            .color(TEXT_SEC)
+            .into(),
+    };
+    let fee = match (tx.is_coinbase(), tx.fee) {
+        (true, _) => "coinbase".to_owned(),
+        (false, Some(fee)) => format!(
+            "{}  ({:.1} sat/vB)",
+            format_btc(fee),
+            tx.fee_rate().unwrap_or(0.0)
+        ),
+        (false, None) => "unknown (previous outputs unavailable)".to_owned(),
+    };
+
+    let details = column![
+        text("Transaction").size(18).color(Color::BLACK),
+        field("Txid", tx.txid.clone()),
+        field("Wtxid", tx.hash.clone()),
+        row![text("Block").size(11).color(TEXT_TER).width(110), block].spacing(8),
+        field(
+            "Confirmations",
+            tx.confirmations
+                .map_or_else(|| "0".to_owned(), format_thousands)
+        ),
+        field(
+            "Time",
+            tx.blocktime
+                .map_or_else(|| "—".to_owned(), format_unix_time)
+        ),
+        field(
+            "Size",
+            format!("{} bytes  ·  {} vB  ·  {} WU", tx.size, tx.vsize, tx.weight)
+        ),
+        field("Fee", fee),
+        field(
+            "Version / locktime",
+            format!("{} / {}", tx.version, tx.locktime)
+        ),
+        field(
+            "Replaceable",
+            if tx.signals_rbf() {
+                "yes (BIP 125 signalled)"
+            } else {
+                "no"
+            }
+            .to_owned()
+        ),
+    ]
+    .spacing(4);
+
+    let mut inputs = column![section_label("INPUTS")].spacing(3);
+    for (i, input) in tx.vin.iter().enumerate() {
+        let source: Element<Message> = match (&input.txid, input.vout, &input.coinbase) {
+            (Some(txid), Some(n), _) => {
+                link_button(&format!("{txid}:{n}"), open(Target::Tx(txid.clone())))
+            }
+            (_, _, Some(script)) => text(format!("coinbase {script}"))
+                .size(11)
+                .color(TEXT_SEC)
+                .into(),
+            _ => text("—").size(11).color(TEXT_SEC).into(),
+        };
+        let (value, address) = input.prevout.as_ref().map_or_else(
+            || (String::new(), String::new()),
+            |p| (format_btc(p.value), p.script_pub_key.describe()),
+        );
+        inputs = inputs.push(
+            row![
+                text(format!("#{i}")).size(11).color(TEXT_TER).width(30),
+                row![source].width(Length::FillPortion(5)),
+                text(address)
+                    .size(11)
+                    .color(TEXT_SEC)
+                    .width(Length::FillPortion(4)),
+                text(value)
+                    .size(11)
+                    .color(TEXT_SEC)
+                    .width(Length::FillPortion(2)),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    let mut outputs = column![section_label("OUTPUTS")].spacing(3);
+    for out in &tx.vout {
+        outputs = outputs.push(
+            row![
+                text(format!("#{}", out.n))
+                    .size(11)
+                    .color(TEXT_TER)
+                    .width(30),
+                text(out.script_pub_key.describe())
+                    .size(11)
+                    .color(TEXT_SEC)
+                    .width(Length::FillPortion(9)),
+                text(format_btc(out.value))
+                    .size(11)
+                    .color(TEXT_SEC)
+                    .width(Length::FillPortion(2)),
+            ]
+            .spacing(8),
+        );
+    }
+
+    column![
+        details,
+        horizontal_rule(),
+        inputs,
+        horizontal_rule(),
+        outputs
+    ]
+    .spacing(10)
+    .into()
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_queries() {
+        assert_eq!(Target::parse(" 840,000 "), Ok(Target::Height(840_000)));
+        let hash = "00000000000000000002A7C4C1E48D76C5A37902165A270156B7A8D72728A054";
+        assert_eq!(
+            Target::parse(hash),
+            Ok(Target::Hash(hash.to_ascii_lowercase()))
+        );
+        assert!(Target::parse("").is_err());
+        assert!(Target::parse("bc1qxyz").is_err());
+        assert!(Target::parse(&hash[1..]).is_err());
+    }
+}
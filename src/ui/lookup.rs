@@ -0,0 +1,366 @@
+//! Lookup tab — private address / descriptor history via the local electrs.
+//!
+//! Addresses and descriptors are turned into output scripts by the local
+//! `bitcoind` (`validateaddress`, `deriveaddresses`), hashed into Electrum
+//! script hashes and queried with `blockchain.scripthash.*`.  Nothing leaves
+//! this machine.
+
+use iced::{
+    widget::{column, row, scrollable, text, text_input, Space},
+    Alignment, Color, Element, Length, Task,
+};
+
+use super::{
+    format_thousands, horizontal_rule, link_button, page, section_label, styled_button,
+    ButtonStyle, Message, GREEN, MAC_ORG, MAC_RED, TEXT_SEC, TEXT_TER,
+};
+use crate::{
+    electrum::{self, Balance, HistoryItem, Utxo},
+    process_manager::ELECTRUM_RPC_ADDR,
+    rpc::{self, RpcAuth},
+};
+
+/// Addresses derived from a ranged descriptor (`…/*`): indexes `0..=19`,
+/// the usual wallet gap limit.
+const DESCRIPTOR_RANGE: (u32, u32) = (0, 19);
+
+/// What the user typed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    Address(String),
+    Descriptor(String),
+    /// A raw Electrum script hash (64 hex digits).
+    ScriptHash(String),
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let q = input.trim();
+        if q.is_empty() {
+            return Err("Enter an address, output descriptor or script hash.".to_owned());
+        }
+        if q.contains('(') {
+            return Ok(Self::Descriptor(q.to_owned()));
+        }
+        if q.len() == 64 && q.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Ok(Self::ScriptHash(q.to_ascii_lowercase()));
+        }
+        Ok(Self::Address(q.to_owned()))
+    }
+}
+
+/// Results for one output script.
+#[derive(Debug, Clone)]
+pub struct ScriptReport {
+    /// Address, or the script hash when looked up directly.
+    label: String,
+    balance: Balance,
+    history: Vec<HistoryItem>,
+    utxos: Vec<Utxo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    tip: u64,
+    scripts: Vec<ScriptReport>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Msg {
+    QueryChanged(String),
+    Lookup,
+    Loaded(Result<Report, String>),
+}
+
+#[derive(Default)]
+pub struct State {
+    query: String,
+    report: Option<Report>,
+    loading: bool,
+    error: Option<String>,
+}
+
+impl State {
+    pub fn update(&mut self, msg: Msg, auth: RpcAuth) -> Task<Message> {
+        match msg {
+            Msg::QueryChanged(q) => {
+                self.query = q;
+                Task::none()
+            }
+            Msg::Lookup => {
+                let query = match Query::parse(&self.query) {
+                    Ok(q) => q,
+                    Err(e) => {
+                        self.error = Some(e);
+                        return Task::none();
+                    }
+                };
+                self.loading = true;
+                self.error = None;
+                Task::perform(
+                    async move { lookup(&auth, query).await.map_err(|e| format!("{e:#}")) },
+                    |r| Message::Lookup(Msg::Loaded(r)),
+                )
+            }
+            Msg::Loaded(result) => {
+                self.loading = false;
+                match result {
+                    Ok(report) => self.report = Some(report),
+                    Err(e) => self.error = Some(e),
+                }
+                Task::none()
+            }
+        }
+    }
+
+    pub fn view(&self, electrs_running: bool) -> Element<'_, Message> {
+        let input = text_input(
+            "Address, descriptor (wpkh(xpub…/0/*)) or script hash",
+            &self.query,
+        )
+        .on_input(|q| Message::Lookup(Msg::QueryChanged(q)))
+        .on_submit(Message::Lookup(Msg::Lookup))
+        .size(13)
+        .padding(6);
+
+        let header = row![
+            section_label("ADDRESS LOOKUP"),
+            Space::with_width(12),
+            input,
+            styled_button(
+                if self.loading {
+                    "Looking up…"
+                } else {
+                    "Look Up"
+                },
+                ButtonStyle::Primary
+            )
+            .on_press_maybe(
+                (electrs_running && !self.loading).then_some(Message::Lookup(Msg::Lookup))
+            ),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center);
+
+        let mut body = column![header].spacing(10);
+        if !electrs_running {
+            body = body.push(
+                text("Electrs is not running — lookups are answered by the local electrs.")
+                    .size(11)
+                    .color(TEXT_TER),
+            );
+        }
+        if let Some(e) = &self.error {
+            body = body.push(text(e.as_str()).size(11).color(MAC_RED));
+        }
+        if let Some(report) = &self.report {
+            body = body.push(view_report(report));
+        }
+
+        page(scrollable(body).height(Length::Fill))
+    }
+}
+
+async fn lookup(auth: &RpcAuth, query: Query) -> anyhow::Result<Report> {
+    let scripts = resolve_scripts(auth, query).await?;
+
+    let mut client = electrum::Client::connect(ELECTRUM_RPC_ADDR).await?;
+    let tip = client.tip_height().await?;
+    let mut reports = Vec::with_capacity(scripts.len());
+    for (label, hash) in scripts {
+        let mut history = client.history(&hash).await?;
+        // Newest first; unconfirmed (height ≤ 0) on top.
+        history.sort_by_key(|h| if h.height <= 0 { i64::MIN } else { -h.height });
+        reports.push(ScriptReport {
+            label,
+            balance: client.balance(&hash).await?,
+            history,
+            utxos: client.list_unspent(&hash).await?,
+        });
+    }
+    Ok(Report {
+        tip,
+        scripts: reports,
+    })
+}
+
+/// Turn the query into `(label, script hash)` pairs.
+async fn resolve_scripts(auth: &RpcAuth, query: Query) -> anyhow::Result<Vec<(String, String)>> {
+    let addresses = match query {
+        Query::ScriptHash(hash) => return Ok(vec![(hash.clone(), hash)]),
+        Query::Address(address) => vec![address],
+        Query::Descriptor(desc) => {
+            // `getdescriptorinfo` adds the checksum `deriveaddresses` requires.
+            let info = rpc::get_descriptor_info(auth, &desc).await?;
+            let range = info.isrange.then_some(DESCRIPTOR_RANGE);
+            rpc::derive_addresses(auth, &info.descriptor, range).await?
+        }
+    };
+
+    let mut scripts = Vec::with_capacity(addresses.len());
+    for address in addresses {
+        let info = rpc::validate_address(auth, &address).await?;
+        let script = hex::decode(&info.script_pub_key)
+            .map_err(|e| anyhow::anyhow!("bad scriptPubKey for {}: {e}", info.address))?;
+        scripts.push((info.address, electrum::script_hash(&script)));
+    }
+    Ok(scripts)
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn format_sats(sats: i64) -> String {
+    format!("{:.8} BTC", sats as f64 / 100_000_000.0)
+}
+
+fn view_report(report: &Report) -> Element<'_, Message> {
+    let total = report
+        .scripts
+        .iter()
+        .fold(Balance::default(), |acc, s| Balance {
+            confirmed: acc.confirmed + s.balance.confirmed,
+            unconfirmed: acc.unconfirmed + s.balance.unconfirmed,
+        });
+    let used = report
+        .scripts
+        .iter()
+        .filter(|s| !s.history.is_empty())
+        .count();
+
+    let mut col = column![
+        text(format!(
+            "{} confirmed  ·  {} unconfirmed",
+            format_sats(total.confirmed),
+            format_sats(total.unconfirmed)
+        ))
+        .size(16)
+        .color(Color::BLACK),
+        text(format!(
+            "{} of {} scripts used  ·  electrs tip {}",
+            used,
+            report.scripts.len(),
+            format_thousands(report.tip)
+        ))
+        .size(11)
+        .color(TEXT_TER),
+    ]
+    .spacing(6);
+
+    for script in &report.scripts {
+        // Unused derived addresses only add noise.
+        if script.history.is_empty() && report.scripts.len() > 1 {
+            continue;
+        }
+        col = col
+            .push(horizontal_rule())
+            .push(view_script(script, report.tip));
+    }
+    col.into()
+}
+
+fn view_script(script: &ScriptReport, tip: u64) -> Element<'_, Message> {
+    let mut col = column![
+        row![
+            text(script.label.as_str()).size(13).color(Color::BLACK),
+            Space::with_width(Length::Fill),
+            text(format!(
+                "{}  ({} unconfirmed)",
+                format_sats(script.balance.confirmed),
+                format_sats(script.balance.unconfirmed)
+            ))
+            .size(11)
+            .color(TEXT_SEC),
+        ]
+        .align_y(Alignment::Center),
+        section_label("HISTORY"),
+    ]
+    .spacing(4);
+
+    if script.history.is_empty() {
+        col = col.push(text("No transactions.").size(11).color(TEXT_TER));
+    }
+    for item in &script.history {
+        let confs = item.confirmations(tip);
+        let fee = item
+            .fee
+            .map(|f| format!(" · fee {} sat", format_thousands(f)))
+            .unwrap_or_default();
+        let (status, color) = match item.height {
+            h if h < 0 => (format!("unconfirmed (unconfirmed parents){fee}"), MAC_ORG),
+            0 => (format!("unconfirmed{fee}"), MAC_ORG),
+            _ => (
+                format!(
+                    "{} confirmation{} · block {}",
+                    format_thousands(confs),
+                    if confs == 1 { "" } else { "s" },
+                    format_thousands(item.height.unsigned_abs())
+                ),
+                if confs >= 6 { GREEN } else { TEXT_SEC },
+            ),
+        };
+        col = col.push(
+            row![
+                row![link_button(
+                    &item.tx_hash,
+                    Message::ExploreTx(item.tx_hash.clone())
+                )]
+                .width(Length::FillPortion(3)),
+                text(status)
+                    .size(11)
+                    .color(color)
+                    .width(Length::FillPortion(2)),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    if !script.utxos.is_empty() {
+        col = col.push(section_label("UNSPENT OUTPUTS"));
+        for utxo in &script.utxos {
+            col = col.push(
+                row![
+                    text(format!("{}:{}", utxo.tx_hash, utxo.tx_pos))
+                        .size(11)
+                        .color(TEXT_SEC)
+                        .width(Length::FillPortion(3)),
+                    text(format_sats(i64::try_from(utxo.value).unwrap_or(i64::MAX)))
+                        .size(11)
+                        .color(TEXT_SEC)
+                        .width(Length::FillPortion(2)),
+                    text(if utxo.height > 0 { "" } else { "unconfirmed" })
+                        .size(11)
+                        .color(MAC_ORG)
+                        .width(Length::FillPortion(1)),
+                ]
+                .spacing(8),
+            );
+        }
+    }
+    col.into()
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_queries() {
+        assert_eq!(
+            Query::parse(" bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq "),
+            Ok(Query::Address(
+                "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq".to_owned()
+            ))
+        );
+        assert!(matches!(
+            Query::parse("wpkh([d34db33f/84h/0h/0h]xpub6C…/0/*)"),
+            Ok(Query::Descriptor(_))
+        ));
+        assert_eq!(
+            Query::parse(&"AB".repeat(32)),
+            Ok(Query::ScriptHash("ab".repeat(32)))
+        );
+        assert!(Query::parse("  ").is_err());
+    }
+}
@@ -0,0 +1,380 @@
+//! Mempool tab — `getmempoolinfo` summary, `estimatesmartfee` targets and a
+//! fee-rate histogram with the projected next block from `getrawmempool`.
+//!
+//! With ZMQ enabled, transactions entering and leaving the mempool are
+//! also counted live as they happen.
+
+use std::{collections::VecDeque, fmt::Write as _};
+
+use iced::{
+    widget::{column, container, row, scrollable, text, Space},
+    Alignment, Element, Length, Task,
+};
+
+use super::{
+    format_bytes, format_thousands, horizontal_rule, link_button, meter, page, section_label,
+    styled_button, ButtonStyle, Message, BTC_ACC, MAC_BLUE, MAC_RED, TEXT_SEC, TEXT_TER,
+};
+use crate::rpc::{
+    self, btc_per_kvb_to_sat_per_vb, FeeEstimate, MempoolInfo, MempoolStats, RpcAuth,
+};
+
+/// Confirmation targets shown in the estimates row, with a rough wall-clock
+/// label.
+const FEE_TARGETS: [(u16, &str); 6] = [
+    (1, "next block"),
+    (3, "~30 min"),
+    (6, "~1 h"),
+    (12, "~2 h"),
+    (24, "~4 h"),
+    (144, "~1 day"),
+];
+
+/// Newest live txids listed.
+const RECENT_SHOWN: usize = 8;
+
+/// Everything fetched in one refresh.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    info: MempoolInfo,
+    estimates: Vec<FeeEstimate>,
+    stats: MempoolStats,
+}
+
+#[derive(Debug, Clone)]
+pub enum Msg {
+    Refresh,
+    Loaded(Result<Snapshot, String>),
+    /// ZMQ `sequence`: a transaction entered the mempool.
+    TxAdded(String),
+    /// ZMQ `sequence`: a transaction was evicted, replaced or expired.
+    TxRemoved,
+    /// ZMQ `rawtx`: size of a transaction seen.
+    RawTx(usize),
+}
+
+/// Mempool changes pushed over ZMQ since bitcoind was launched.
+#[derive(Default)]
+struct Live {
+    added: u64,
+    removed: u64,
+    raw_bytes: u64,
+    /// Newest first.
+    recent: VecDeque<String>,
+}
+
+#[derive(Default)]
+pub struct State {
+    snapshot: Option<Snapshot>,
+    error: Option<String>,
+    loading: bool,
+    live: Live,
+}
+
+impl State {
+    /// Forget the live counters (bitcoind was relaunched).
+    pub fn reset_live(&mut self) {
+        self.live = Live::default();
+    }
+
+    pub fn update(&mut self, msg: Msg, auth: RpcAuth) -> Task<Message> {
+        match msg {
+            Msg::Refresh => {
+                // A full verbose mempool can take longer than one RpcTick.
+                if self.loading {
+                    return Task::none();
+                }
+                self.loading = true;
+                Task::perform(
+                    async move { fetch(&auth).await.map_err(|e| e.to_string()) },
+                    |r| Message::Mempool(Msg::Loaded(r)),
+                )
+            }
+            Msg::Loaded(result) => {
+                self.loading = false;
+                match result {
+                    Ok(snap) => {
+                        self.snapshot = Some(snap);
+                        self.error = None;
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+                Task::none()
+            }
+            Msg::TxAdded(txid) => {
+                self.live.added += 1;
+                self.live.recent.push_front(txid);
+                self.live.recent.truncate(RECENT_SHOWN);
+                Task::none()
+            }
+            Msg::TxRemoved => {
+                self.live.removed += 1;
+                Task::none()
+            }
+            Msg::RawTx(size) => {
+                self.live.raw_bytes += size as u64;
+                Task::none()
+            }
+        }
+    }
+
+    pub fn view(&self, bitcoin_running: bool) -> Element<'_, Message> {
+        let refresh = styled_button(
+            if self.loading {
+                "Refreshing…"
+            } else {
+                "Refresh"
+            },
+            ButtonStyle::Secondary,
+        )
+        .on_press_maybe(bitcoin_running.then_some(Message::Mempool(Msg::Refresh)));
+
+        let header = row![
+            section_label("MEMPOOL"),
+            Space::with_width(Length::Fill),
+            refresh
+        ]
+        .align_y(Alignment::Center);
+
+        let mut body = column![header].spacing(10);
+        if let Some(e) = &self.error {
+            body = body.push(text(e.as_str()).size(11).color(MAC_RED));
+        }
+        if !bitcoin_running {
+            body = body.push(text("Bitcoin is not running.").size(11).color(TEXT_TER));
+        }
+        if let Some(snap) = &self.snapshot {
+            body = body
+                .push(view_info(&snap.info))
+                .push(self.view_live())
+                .push(horizontal_rule())
+                .push(view_estimates(&snap.estimates))
+                .push(horizontal_rule())
+                .push(view_next_block(&snap.stats))
+                .push(view_histogram(&snap.stats));
+        }
+
+        page(scrollable(body).height(Length::Fill))
+    }
+
+    fn view_live(&self) -> Element<'_, Message> {
+        let live = &self.live;
+        if live.added == 0 && live.removed == 0 && live.raw_bytes == 0 {
+            return Space::with_height(0).into();
+        }
+        let mut summary = format!(
+            "+{} added  ·  −{} removed",
+            format_thousands(live.added),
+            format_thousands(live.removed)
+        );
+        if live.raw_bytes > 0 {
+            let _ = write!(
+                summary,
+                "  ·  {} of raw transactions",
+                format_bytes(live.raw_bytes)
+            );
+        }
+        let mut col = column![
+            section_label("LIVE FEED (ZMQ, SINCE LAUNCH)"),
+            text(summary).size(12).color(TEXT_SEC),
+        ]
+        .spacing(4);
+        for txid in &live.recent {
+            col = col.push(link_button(txid, Message::ExploreTx(txid.clone())));
+        }
+        col.into()
+    }
+}
+
+async fn fetch(auth: &RpcAuth) -> anyhow::Result<Snapshot> {
+    let estimates = async {
+        let mut out = Vec::with_capacity(FEE_TARGETS.len());
+        for (target, _) in FEE_TARGETS {
+            out.push(rpc::estimate_smart_fee(auth, target).await?);
+        }
+        anyhow::Ok(out)
+    };
+    let (info, estimates, stats) = tokio::try_join!(
+        rpc::get_mempool_info(auth),
+        estimates,
+        rpc::get_mempool_stats(auth),
+    )?;
+    Ok(Snapshot {
+        info,
+        estimates,
+        stats,
+    })
+}
+
+fn stat(label: &str, value: String) -> Element<'_, Message> {
+    column![
+        text(label).size(9).color(TEXT_TER),
+        text(value).size(14).color(iced::Color::BLACK),
+    ]
+    .spacing(2)
+    .width(Length::FillPortion(1))
+    .into()
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn view_info(info: &MempoolInfo) -> Element<'_, Message> {
+    let usage = if info.maxmempool > 0 {
+        info.usage as f32 / info.maxmempool as f32
+    } else {
+        0.0
+    };
+    let mut stats = row![
+        stat("TRANSACTIONS", format_thousands(info.size)),
+        stat(
+            "VIRTUAL SIZE",
+            format!("{:.2} MvB", info.bytes as f64 / 1e6)
+        ),
+        stat(
+            "MIN MEMPOOL FEE",
+            format!(
+                "{:.2} sat/vB",
+                btc_per_kvb_to_sat_per_vb(info.mempoolminfee)
+            )
+        ),
+        stat(
+            "MIN RELAY FEE",
+            format!(
+                "{:.2} sat/vB",
+                btc_per_kvb_to_sat_per_vb(info.minrelaytxfee)
+            )
+        ),
+    ]
+    .spacing(16);
+    if let Some(fee) = info.total_fee {
+        stats = stats.push(stat("TOTAL FEES", format!("{fee:.8} BTC")));
+    }
+
+    let mut col = column![
+        stats,
+        row![
+            text("MEMORY").size(9).color(TEXT_TER).width(60),
+            meter(usage, if usage > 0.9 { MAC_RED } else { MAC_BLUE }),
+            text(format!(
+                "{} / {} ({:.0}%)",
+                format_bytes(info.usage),
+                format_bytes(info.maxmempool),
+                usage * 100.0
+            ))
+            .size(11)
+            .color(TEXT_SEC)
+            .width(220),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+    ]
+    .spacing(10);
+    if !info.loaded {
+        col = col.push(
+            text("Still loading mempool.dat — figures are incomplete.")
+                .size(11)
+                .color(TEXT_TER),
+        );
+    }
+    col.into()
+}
+
+fn view_estimates(estimates: &[FeeEstimate]) -> Element<'_, Message> {
+    let cells = FEE_TARGETS.iter().map(|&(target, label)| {
+        let estimate = estimates.iter().find(|e| e.target == target);
+        let value = estimate.and_then(|e| e.feerate).map_or_else(
+            || "—".to_owned(),
+            |r| format!("{:.1} sat/vB", btc_per_kvb_to_sat_per_vb(r)),
+        );
+        // Core falls back to the nearest target it has data for, or reports
+        // why it has none.
+        let note = match estimate {
+            Some(e) if e.feerate.is_none() && !e.errors.is_empty() => e.errors.join("; "),
+            Some(e) if e.blocks != 0 && e.blocks != target => {
+                format!("{label} (for {} blocks)", e.blocks)
+            }
+            _ => label.to_owned(),
+        };
+        column![
+            text(format!(
+                "{target} BLOCK{}",
+                if target == 1 { "" } else { "S" }
+            ))
+            .size(9)
+            .color(TEXT_TER),
+            text(value).size(14).color(iced::Color::BLACK),
+            text(note).size(10).color(TEXT_TER),
+        ]
+        .spacing(2)
+        .width(Length::FillPortion(1))
+        .into()
+    });
+    column![
+        section_label("FEE ESTIMATES (estimatesmartfee)"),
+        row(cells.collect::<Vec<Element<Message>>>()).spacing(16),
+    ]
+    .spacing(6)
+    .into()
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn view_next_block(stats: &MempoolStats) -> Element<'_, Message> {
+    let line = stats.next_block.as_ref().map_or_else(
+        || "Mempool is empty.".to_owned(),
+        |nb| {
+            format!(
+                "min {:.1}  ·  median {:.1}  ·  max {:.1} sat/vB  ·  {} txs, {:.2} MvB",
+                nb.min_rate,
+                nb.median_rate,
+                nb.max_rate,
+                format_thousands(nb.tx_count),
+                nb.vsize as f64 / 1e6
+            )
+        },
+    );
+    column![
+        section_label("PROJECTED NEXT BLOCK"),
+        text(line).size(12).color(TEXT_SEC),
+    ]
+    .spacing(4)
+    .into()
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn view_histogram(stats: &MempoolStats) -> Element<'_, Message> {
+    let largest = stats
+        .histogram
+        .iter()
+        .map(|b| b.vsize)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    let rows = stats.histogram.iter().rev().map(|b| {
+        let label = match b.max_rate {
+            Some(max) => format!("{}–{max}", b.min_rate),
+            None => format!("≥ {}", b.min_rate),
+        };
+        row![
+            text(label).size(11).color(TEXT_SEC).width(70),
+            meter(b.vsize as f32 / largest as f32, BTC_ACC),
+            text(format!(
+                "{} txs  ·  {:.2} MvB",
+                format_thousands(b.count),
+                b.vsize as f64 / 1e6
+            ))
+            .size(11)
+            .color(TEXT_SEC)
+            .width(180),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center)
+        .into()
+    });
+
+    column![
+        section_label("FEE-RATE HISTOGRAM (sat/vB, by virtual size)"),
+        container(column(rows.collect::<Vec<Element<Message>>>()).spacing(3)),
+    ]
+    .spacing(6)
+    .into()
+}
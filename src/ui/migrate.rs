@@ -0,0 +1,372 @@
+//! Migration wizard in the paths panel: copy the bitcoin data directory or
+//! the electrs DB to a new location (a new SSD, say), verify the copy, and
+//! only then point the config at it.
+//!
+//! The copy runs on a blocking thread; its counters are shared with the
+//! view, which the 100 ms output tick redraws.  The old directory is left
+//! in place for the user to remove once the node runs from the new one.
+
+use std::{
+    fmt::{self, Write as _},
+    path::PathBuf,
+    sync::{atomic::Ordering, Arc},
+};
+
+use iced::{
+    widget::{column, pick_list, row, text, text_input, Space},
+    Alignment, Element, Font, Length, Padding, Task,
+};
+
+use super::{
+    format_bytes, meter, section_label, styled_button, ButtonStyle, Message, GREEN, MAC_BLUE,
+    MAC_RED, TEXT_SEC, TEXT_TER,
+};
+use crate::migrate::{self, Plan, Progress};
+
+/// Which directory is being moved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Which {
+    BitcoinData,
+    ElectrsDb,
+}
+
+impl Which {
+    const ALL: [Which; 2] = [Which::BitcoinData, Which::ElectrsDb];
+
+    /// The process that must be stopped while its directory is copied.
+    pub fn process(self) -> &'static str {
+        match self {
+            Self::BitcoinData => "Bitcoin",
+            Self::ElectrsDb => "Electrs",
+        }
+    }
+}
+
+impl fmt::Display for Which {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::BitcoinData => "Bitcoin data directory",
+            Self::ElectrsDb => "Electrs DB directory",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Msg {
+    Toggle,
+    WhichSelected(Which),
+    DestChanged(String),
+    Browse,
+    Picked(Option<String>),
+    /// Size up the copy and check free space.
+    Check,
+    Checked(Result<Box<Plan>, String>),
+    Start,
+    Cancel,
+    /// Copy and verification finished (or failed, or were cancelled).
+    Finished(Result<(), String>),
+}
+
+/// Current directories and whether their processes run.
+pub struct Context {
+    pub bitcoin_data: PathBuf,
+    pub electrs_data: PathBuf,
+    pub bitcoin_running: bool,
+    pub electrs_running: bool,
+}
+
+impl Context {
+    fn source(&self, which: Which) -> PathBuf {
+        match which {
+            Which::BitcoinData => self.bitcoin_data.clone(),
+            Which::ElectrsDb => self.electrs_data.clone(),
+        }
+    }
+
+    fn running(&self, which: Which) -> bool {
+        match which {
+            Which::BitcoinData => self.bitcoin_running,
+            Which::ElectrsDb => self.electrs_running,
+        }
+    }
+}
+
+pub struct State {
+    open: bool,
+    which: Which,
+    dest: String,
+    plan: Option<Plan>,
+    /// Set while a copy runs.
+    progress: Option<Arc<Progress>>,
+    status: Option<Result<String, String>>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            open: false,
+            which: Which::BitcoinData,
+            dest: String::new(),
+            plan: None,
+            progress: None,
+            status: None,
+        }
+    }
+}
+
+impl State {
+    /// The directory being copied, while a copy runs.
+    pub fn copying(&self) -> Option<Which> {
+        self.progress.as_ref().map(|_| self.which)
+    }
+
+    #[allow(clippy::too_many_lines)]
+    pub fn update(&mut self, msg: Msg, ctx: &Context) -> Task<Message> {
+        match msg {
+            Msg::Toggle => {
+                self.open = !self.open;
+                Task::none()
+            }
+            Msg::WhichSelected(which) => {
+                if self.progress.is_none() {
+                    self.which = which;
+                    self.plan = None;
+                    self.status = None;
+                }
+                Task::none()
+            }
+            Msg::DestChanged(s) => {
+                self.dest = s;
+                self.plan = None;
+                Task::none()
+            }
+            Msg::Browse => Task::perform(
+                async {
+                    rfd::AsyncFileDialog::new()
+                        .set_title("Choose the New Location")
+                        .pick_folder()
+                        .await
+                        .map(|f| f.path().to_string_lossy().into_owned())
+                },
+                |p| Message::Migrate(Msg::Picked(p)),
+            ),
+            Msg::Picked(None) => Task::none(),
+            Msg::Picked(Some(path)) => {
+                self.dest = path;
+                self.plan = None;
+                Task::none()
+            }
+            Msg::Check => {
+                if let Err(e) = self.ready(ctx) {
+                    self.status = Some(Err(e));
+                    return Task::none();
+                }
+                let (src, dst) = (ctx.source(self.which), PathBuf::from(self.dest.trim()));
+                self.status = Some(Ok("Measuring…".into()));
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || migrate::plan(&src, &dst))
+                            .await
+                            .map_err(|e| e.to_string())?
+                            .map(Box::new)
+                            .map_err(|e| format!("{e:#}"))
+                    },
+                    |r| Message::Migrate(Msg::Checked(r)),
+                )
+            }
+            Msg::Checked(result) => {
+                match result {
+                    Ok(plan) => {
+                        self.status = None;
+                        self.plan = Some(*plan);
+                    }
+                    Err(e) => {
+                        self.plan = None;
+                        self.status = Some(Err(e));
+                    }
+                }
+                Task::none()
+            }
+            Msg::Start => {
+                if let Err(e) = self.ready(ctx) {
+                    self.status = Some(Err(e));
+                    return Task::none();
+                }
+                let progress = Arc::new(Progress::default());
+                self.progress = Some(Arc::clone(&progress));
+                self.status = None;
+                let (src, dst) = (ctx.source(self.which), PathBuf::from(self.dest.trim()));
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+                            // Plan again: an earlier run may have copied more.
+                            let plan = migrate::plan(&src, &dst)?;
+                            if !plan.fits() {
+                                anyhow::bail!("not enough free space at {}", dst.display());
+                            }
+                            migrate::copy(&plan, &progress)?;
+                            migrate::verify(&plan)
+                        })
+                        .await
+                        .map_err(|e| e.to_string())?
+                        .map_err(|e| format!("{e:#}"))
+                    },
+                    |r| Message::Migrate(Msg::Finished(r)),
+                )
+            }
+            Msg::Cancel => {
+                if let Some(progress) = &self.progress {
+                    progress.cancel.store(true, Ordering::Relaxed);
+                }
+                Task::none()
+            }
+            Msg::Finished(result) => {
+                self.progress = None;
+                self.plan = None;
+                match result {
+                    Ok(()) => {
+                        let dst = PathBuf::from(self.dest.trim());
+                        self.status = Some(Ok(format!(
+                            "Copied and verified. {} now uses {}; the old directory \
+                             is untouched.",
+                            self.which.process(),
+                            dst.display()
+                        )));
+                        Task::done(Message::DataMigrated(self.which, dst))
+                    }
+                    Err(e) => {
+                        self.status = Some(Err(e));
+                        Task::none()
+                    }
+                }
+            }
+        }
+    }
+
+    /// Refuse to copy a directory its process is using.
+    fn ready(&self, ctx: &Context) -> Result<(), String> {
+        if self.progress.is_some() {
+            return Err("A copy is already running.".into());
+        }
+        if self.dest.trim().is_empty() {
+            return Err("Choose where to copy to.".into());
+        }
+        if ctx.running(self.which) {
+            return Err(format!(
+                "Stop {} before copying its directory.",
+                self.which.process()
+            ));
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_lines)]
+    pub fn view(&self) -> Element<'_, Message> {
+        if !self.open {
+            return Space::with_height(0).into();
+        }
+        let idle = self.progress.is_none();
+        let mut col = column![
+            section_label("MIGRATE DATA"),
+            row![
+                pick_list(Which::ALL, Some(self.which), |w| {
+                    Message::Migrate(Msg::WhichSelected(w))
+                })
+                .text_size(12),
+                Space::with_width(8),
+                text("to").size(11).color(TEXT_SEC),
+                Space::with_width(8),
+                text_input("New location, e.g. on another SSD", &self.dest)
+                    .on_input(|s| Message::Migrate(Msg::DestChanged(s)))
+                    .padding(Padding::from([4, 6]))
+                    .font(Font::MONOSPACE)
+                    .size(11),
+                Space::with_width(6),
+                styled_button("Browse…", ButtonStyle::Secondary)
+                    .on_press(Message::Migrate(Msg::Browse)),
+                Space::with_width(6),
+                styled_button("Check", ButtonStyle::Secondary)
+                    .on_press_maybe(idle.then_some(Message::Migrate(Msg::Check))),
+            ]
+            .align_y(Alignment::Center),
+        ]
+        .spacing(6);
+
+        if let Some(plan) = &self.plan {
+            let free = plan
+                .free_bytes
+                .map_or_else(|| "unknown".to_owned(), format_bytes);
+            let mut summary = format!(
+                "{} files, {} in total; {} to copy, {} free at the destination.",
+                plan.files.len(),
+                format_bytes(plan.total_bytes),
+                format_bytes(plan.needed_bytes()),
+                free
+            );
+            if plan.present_bytes > 0 {
+                let _ = write!(
+                    summary,
+                    " Resumes an earlier copy ({} already there).",
+                    format_bytes(plan.present_bytes)
+                );
+            }
+            col = col.push(
+                row![
+                    text(summary)
+                        .size(11)
+                        .color(if plan.fits() { TEXT_SEC } else { MAC_RED }),
+                    Space::with_width(Length::Fill),
+                    styled_button("Copy & Switch", ButtonStyle::Warning).on_press_maybe(
+                        (idle && plan.fits()).then_some(Message::Migrate(Msg::Start))
+                    ),
+                ]
+                .align_y(Alignment::Center),
+            );
+        }
+
+        if let Some(progress) = &self.progress {
+            let copied = progress.copied_bytes.load(Ordering::Relaxed);
+            let files = progress.files_done.load(Ordering::Relaxed);
+            let total = self.plan.as_ref().map_or(0, |p| p.total_bytes);
+            #[allow(clippy::cast_precision_loss)]
+            let fraction = if total > 0 {
+                copied as f32 / total as f32
+            } else {
+                0.0
+            };
+            col = col.push(meter(fraction, MAC_BLUE)).push(
+                row![
+                    text(format!(
+                        "{} of {} copied, {files} files done. Cancelling keeps what is \
+                         copied; Copy & Switch again resumes.",
+                        format_bytes(copied),
+                        format_bytes(total)
+                    ))
+                    .size(11)
+                    .color(TEXT_SEC),
+                    Space::with_width(Length::Fill),
+                    styled_button("Cancel", ButtonStyle::Secondary)
+                        .on_press(Message::Migrate(Msg::Cancel)),
+                ]
+                .align_y(Alignment::Center),
+            );
+        }
+
+        if let Some(status) = &self.status {
+            col = col.push(match status {
+                Ok(s) => text(s.as_str()).size(11).color(GREEN),
+                Err(e) => text(e.as_str()).size(11).color(MAC_RED),
+            });
+        }
+        col.push(
+            text(
+                "Files are checked by size and modification time after copying. The \
+                 config is only changed once every file matches.",
+            )
+            .size(10)
+            .color(TEXT_TER),
+        )
+        .padding(Padding::from([6, 0]))
+        .into()
+    }
+}
@@ -0,0 +1,433 @@
+//! Peers tab — `getpeerinfo` table with sorting and disconnect/ban actions,
+//! plus a `getnetworkinfo` / `getnettotals` summary.
+
+use std::{
+    cmp::Ordering,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use iced::{
+    widget::{button, column, container, row, scrollable, text, Space},
+    Alignment, Element, Length, Padding, Task,
+};
+
+use super::{
+    format_bytes, format_duration, horizontal_rule, page, section_label, styled_button,
+    ButtonStyle, Message, MAC_RED, TEXT_SEC, TEXT_TER,
+};
+use crate::rpc::{self, NetTotals, NetworkInfo, PeerInfo, RpcAuth};
+
+/// How long the "Ban" action bans a peer for.
+const BAN_SECONDS: u64 = 24 * 60 * 60;
+
+/// Column the peer table is sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortColumn {
+    Id,
+    Address,
+    Network,
+    Direction,
+    ConnectionType,
+    Version,
+    Ping,
+    Sent,
+    Received,
+    Synced,
+    Connected,
+}
+
+/// Everything fetched in one refresh.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    peers: Vec<PeerInfo>,
+    network: NetworkInfo,
+    totals: NetTotals,
+}
+
+#[derive(Debug, Clone)]
+pub enum Msg {
+    Refresh,
+    Loaded(Result<Snapshot, String>),
+    SortBy(SortColumn),
+    Disconnect(u64),
+    /// Ask before banning this host.
+    Ban(String),
+    ConfirmBan,
+    CancelBan,
+    /// Result of a disconnect/ban: status line text.
+    ActionDone(Result<String, String>),
+}
+
+pub struct State {
+    peers: Vec<PeerInfo>,
+    network: Option<NetworkInfo>,
+    totals: Option<NetTotals>,
+    sort: SortColumn,
+    descending: bool,
+    /// Last refresh or action outcome shown under the summary.
+    status: Option<Result<String, String>>,
+    loading: bool,
+    /// Host whose ban awaits confirmation.
+    pending_ban: Option<String>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            peers: Vec::new(),
+            network: None,
+            totals: None,
+            sort: SortColumn::Id,
+            descending: false,
+            status: None,
+            loading: false,
+            pending_ban: None,
+        }
+    }
+}
+
+impl State {
+    pub fn update(&mut self, msg: Msg, auth: RpcAuth) -> Task<Message> {
+        match msg {
+            Msg::Refresh => {
+                if self.loading {
+                    return Task::none();
+                }
+                self.loading = true;
+                Task::perform(
+                    async move {
+                        let (peers, network, totals) = tokio::try_join!(
+                            rpc::get_peer_info(&auth),
+                            rpc::get_network_info(&auth),
+                            rpc::get_net_totals(&auth),
+                        )
+                        .map_err(|e| e.to_string())?;
+                        Ok(Snapshot {
+                            peers,
+                            network,
+                            totals,
+                        })
+                    },
+                    |r| Message::Peers(Msg::Loaded(r)),
+                )
+            }
+            Msg::Loaded(result) => {
+                self.loading = false;
+                match result {
+                    Ok(snap) => {
+                        self.peers = snap.peers;
+                        self.network = Some(snap.network);
+                        self.totals = Some(snap.totals);
+                        if matches!(self.status, Some(Err(_))) {
+                            self.status = None;
+                        }
+                    }
+                    Err(e) => self.status = Some(Err(e)),
+                }
+                Task::none()
+            }
+            Msg::SortBy(column) => {
+                if self.sort == column {
+                    self.descending = !self.descending;
+                } else {
+                    self.sort = column;
+                    self.descending = false;
+                }
+                Task::none()
+            }
+            Msg::Disconnect(id) => Task::perform(
+                async move {
+                    rpc::disconnect_node(&auth, id)
+                        .await
+                        .map(|()| format!("Disconnected peer {id}."))
+                        .map_err(|e| e.to_string())
+                },
+                |r| Message::Peers(Msg::ActionDone(r)),
+            ),
+            Msg::Ban(host) => {
+                self.pending_ban = Some(host);
+                Task::none()
+            }
+            Msg::CancelBan => {
+                self.pending_ban = None;
+                Task::none()
+            }
+            Msg::ConfirmBan => match self.pending_ban.take() {
+                Some(host) => Task::perform(
+                    async move {
+                        rpc::ban_peer(&auth, &host, BAN_SECONDS)
+                            .await
+                            .map(|()| format!("Banned {host} for 24 h."))
+                            .map_err(|e| e.to_string())
+                    },
+                    |r| Message::Peers(Msg::ActionDone(r)),
+                ),
+                None => Task::none(),
+            },
+            Msg::ActionDone(result) => {
+                self.status = Some(result);
+                Task::done(Message::Peers(Msg::Refresh))
+            }
+        }
+    }
+
+    /// Peers in the current sort order.
+    fn sorted_peers(&self) -> Vec<&PeerInfo> {
+        let mut peers: Vec<&PeerInfo> = self.peers.iter().collect();
+        peers.sort_by(|a, b| {
+            let ord = compare(a, b, self.sort);
+            if self.descending {
+                ord.reverse()
+            } else {
+                ord
+            }
+        });
+        peers
+    }
+
+    pub fn view(&self, bitcoin_running: bool) -> Element<'_, Message> {
+        let refresh = styled_button(
+            if self.loading {
+                "Refreshing…"
+            } else {
+                "Refresh"
+            },
+            ButtonStyle::Secondary,
+        )
+        .on_press_maybe(bitcoin_running.then_some(Message::Peers(Msg::Refresh)));
+
+        let header = row![
+            section_label("PEERS"),
+            Space::with_width(Length::Fill),
+            refresh
+        ]
+        .align_y(Alignment::Center);
+
+        let mut body = column![header, self.view_summary()].spacing(8);
+        if let Some(status) = &self.status {
+            body = body.push(match status {
+                Ok(s) => text(s.as_str()).size(11).color(TEXT_SEC),
+                Err(e) => text(e.as_str()).size(11).color(MAC_RED),
+            });
+        }
+        if !bitcoin_running {
+            body = body.push(text("Bitcoin is not running.").size(11).color(TEXT_TER));
+        }
+        if let Some(host) = &self.pending_ban {
+            body = body.push(
+                row![
+                    text(format!(
+                        "Ban {host} for 24 h? Every connection from it is dropped and refused \
+                         until the ban expires or is removed with `setban … remove`."
+                    ))
+                    .size(12)
+                    .color(MAC_RED),
+                    Space::with_width(Length::Fill),
+                    styled_button("Cancel", ButtonStyle::Secondary)
+                        .on_press(Message::Peers(Msg::CancelBan)),
+                    styled_button("Ban", ButtonStyle::Destructive)
+                        .on_press_maybe(bitcoin_running.then_some(Message::Peers(Msg::ConfirmBan))),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center),
+            );
+        }
+        body = body.push(self.view_table());
+
+        page(body)
+    }
+
+    fn view_summary(&self) -> Element<'_, Message> {
+        let Some(net) = &self.network else {
+            return Space::with_height(0).into();
+        };
+        let reachable: Vec<String> = net
+            .networks
+            .iter()
+            .filter(|n| n.reachable)
+            .map(|n| {
+                if n.proxy.is_empty() {
+                    n.name.clone()
+                } else {
+                    format!("{} (via {})", n.name, n.proxy)
+                }
+            })
+            .collect();
+        let mut lines = vec![
+            format!(
+                "{}  (protocol {})  ·  {} connections ({} in / {} out){}",
+                net.subversion,
+                net.version,
+                net.connections,
+                net.connections_in,
+                net.connections_out,
+                if net.networkactive {
+                    ""
+                } else {
+                    "  ·  NETWORK DISABLED"
+                }
+            ),
+            format!(
+                "Reachable: {}  ·  Min relay fee: {:.2} sat/vB",
+                if reachable.is_empty() {
+                    "none".to_owned()
+                } else {
+                    reachable.join(", ")
+                },
+                rpc::btc_per_kvb_to_sat_per_vb(net.relayfee)
+            ),
+        ];
+        if !net.localaddresses.is_empty() {
+            let addrs: Vec<String> = net
+                .localaddresses
+                .iter()
+                .map(|a| format!("{}:{}", a.address, a.port))
+                .collect();
+            lines.push(format!("Advertised: {}", addrs.join(", ")));
+        }
+        if let Some(t) = &self.totals {
+            lines.push(format!(
+                "Total received {}  ·  sent {}",
+                format_bytes(t.totalbytesrecv),
+                format_bytes(t.totalbytessent)
+            ));
+        }
+        let mut col = column(
+            lines
+                .into_iter()
+                .map(|l| text(l).size(11).color(TEXT_SEC).into())
+                .collect::<Vec<Element<Message>>>(),
+        )
+        .spacing(2);
+        for w in net.warnings() {
+            col = col.push(text(w).size(11).color(MAC_RED));
+        }
+        col.into()
+    }
+
+    fn view_table(&self) -> Element<'_, Message> {
+        let header = row![
+            self.header_cell("ID", SortColumn::Id, 1),
+            self.header_cell("Address", SortColumn::Address, 5),
+            self.header_cell("Network", SortColumn::Network, 2),
+            self.header_cell("Dir", SortColumn::Direction, 1),
+            self.header_cell("Type", SortColumn::ConnectionType, 3),
+            self.header_cell("Version", SortColumn::Version, 3),
+            self.header_cell("Ping", SortColumn::Ping, 2),
+            self.header_cell("Sent", SortColumn::Sent, 2),
+            self.header_cell("Recv", SortColumn::Received, 2),
+            self.header_cell("Headers / Blocks", SortColumn::Synced, 3),
+            self.header_cell("Connected", SortColumn::Connected, 2),
+            Space::with_width(Length::FillPortion(4)),
+        ]
+        .spacing(4)
+        .align_y(Alignment::Center);
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let rows: Vec<Element<Message>> = self
+            .sorted_peers()
+            .into_iter()
+            .map(|p| {
+                let network = p.network();
+                let ping = p
+                    .pingtime
+                    .map_or_else(|| "—".to_owned(), |s| format!("{:.0} ms", s * 1000.0));
+                let synced = if p.synced_headers < 0 {
+                    "—".to_owned()
+                } else {
+                    format!("{} / {}", p.synced_headers, p.synced_blocks)
+                };
+                let ban = styled_button("Ban", ButtonStyle::Destructive).on_press_maybe(
+                    network
+                        .is_bannable()
+                        .then(|| Message::Peers(Msg::Ban(p.host().to_owned()))),
+                );
+                row![
+                    cell(p.id.to_string(), 1),
+                    cell(p.addr.clone(), 5),
+                    cell(network.to_string(), 2),
+                    cell(if p.inbound { "in" } else { "out" }.to_owned(), 1),
+                    cell(p.connection_type.clone(), 3),
+                    cell(p.subver.clone(), 3),
+                    cell(ping, 2),
+                    cell(format_bytes(p.bytessent), 2),
+                    cell(format_bytes(p.bytesrecv), 2),
+                    cell(synced, 3),
+                    cell(format_duration(now.saturating_sub(p.conntime)), 2),
+                    row![
+                        styled_button("Disconnect", ButtonStyle::Warning)
+                            .on_press(Message::Peers(Msg::Disconnect(p.id))),
+                        ban,
+                    ]
+                    .spacing(4)
+                    .width(Length::FillPortion(4)),
+                ]
+                .spacing(4)
+                .align_y(Alignment::Center)
+                .into()
+            })
+            .collect();
+
+        column![
+            header,
+            horizontal_rule(),
+            scrollable(column(rows).spacing(2).padding(Padding {
+                top: 4.0,
+                right: 12.0,
+                bottom: 4.0,
+                left: 0.0,
+            }))
+            .height(Length::Fill),
+        ]
+        .spacing(4)
+        .height(Length::Fill)
+        .into()
+    }
+
+    fn header_cell(&self, label: &str, column: SortColumn, portion: u16) -> Element<'_, Message> {
+        let arrow = match (self.sort == column, self.descending) {
+            (true, false) => " ▲",
+            (true, true) => " ▼",
+            _ => "",
+        };
+        button(text(format!("{label}{arrow}")).size(10).color(TEXT_TER))
+            .padding(0)
+            .style(|_, _| button::Style::default())
+            .on_press(Message::Peers(Msg::SortBy(column)))
+            .width(Length::FillPortion(portion))
+            .into()
+    }
+}
+
+fn cell<'a>(value: String, portion: u16) -> Element<'a, Message> {
+    container(text(value).size(11).color(TEXT_SEC))
+        .width(Length::FillPortion(portion))
+        .into()
+}
+
+fn compare(a: &PeerInfo, b: &PeerInfo, column: SortColumn) -> Ordering {
+    match column {
+        SortColumn::Id => a.id.cmp(&b.id),
+        SortColumn::Address => a.addr.cmp(&b.addr),
+        SortColumn::Network => a.network().cmp(&b.network()),
+        SortColumn::Direction => a.inbound.cmp(&b.inbound),
+        SortColumn::ConnectionType => a.connection_type.cmp(&b.connection_type),
+        SortColumn::Version => a.version.cmp(&b.version).then(a.subver.cmp(&b.subver)),
+        // Peers without a ping yet sort last.
+        SortColumn::Ping => a
+            .pingtime
+            .unwrap_or(f64::INFINITY)
+            .total_cmp(&b.pingtime.unwrap_or(f64::INFINITY)),
+        SortColumn::Sent => a.bytessent.cmp(&b.bytessent),
+        SortColumn::Received => a.bytesrecv.cmp(&b.bytesrecv),
+        SortColumn::Synced => a
+            .synced_headers
+            .cmp(&b.synced_headers)
+            .then(a.synced_blocks.cmp(&b.synced_blocks)),
+        // Longest-connected first when ascending.
+        SortColumn::Connected => a.conntime.cmp(&b.conntime),
+    }
+    .then(a.id.cmp(&b.id))
+}
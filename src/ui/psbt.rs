@@ -0,0 +1,514 @@
+//! PSBT tab — load partially signed transactions, inspect them with
+//! `decodepsbt` / `analyzepsbt`, merge copies with `combinepsbt` and
+//! finalise them with `finalizepsbt`, handing the result to Broadcast.
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use iced::{
+    clipboard,
+    widget::{button, column, row, scrollable, text, text_input, Space},
+    Alignment, Color, Element, Length, Task,
+};
+
+use super::{
+    field, format_btc, horizontal_rule, link_button, page, section_label, styled_button,
+    ButtonStyle, Message, GREEN, MAC_BLUE, MAC_ORG, MAC_RED, TEXT_SEC, TEXT_TER,
+};
+use crate::rpc::{self, DecodedPsbt, FinalizedPsbt, PsbtAnalysis, RpcAuth};
+
+/// Every serialised PSBT starts with these bytes (BIP 174).
+const PSBT_MAGIC: &[u8] = b"psbt\xff";
+
+/// Base64 PSBT from pasted text: base64 or hex.
+fn psbt_from_text(text: &str) -> Result<String, String> {
+    let t: String = text.split_whitespace().collect();
+    if let Ok(bytes) = STANDARD.decode(&t) {
+        if bytes.starts_with(PSBT_MAGIC) {
+            return Ok(t);
+        }
+    }
+    if let Ok(bytes) = hex::decode(&t) {
+        if bytes.starts_with(PSBT_MAGIC) {
+            return Ok(STANDARD.encode(bytes));
+        }
+    }
+    Err("Not a PSBT (expected base64, hex or a binary .psbt file).".to_owned())
+}
+
+/// Base64 PSBT from a file: binary, or base64/hex text.
+fn psbt_from_file(bytes: &[u8]) -> Result<String, String> {
+    if bytes.starts_with(PSBT_MAGIC) {
+        return Ok(STANDARD.encode(bytes));
+    }
+    std::str::from_utf8(bytes)
+        .map_err(|_| "Not a PSBT file.".to_owned())
+        .and_then(psbt_from_text)
+}
+
+/// Ask for PSBT files and read them; `(file name, base64 or error)` each.
+async fn pick_psbt_files() -> Vec<(String, Result<String, String>)> {
+    let Some(files) = rfd::AsyncFileDialog::new()
+        .set_title("Open PSBT Files")
+        .add_filter("PSBT", &["psbt", "txt"])
+        .pick_files()
+        .await
+    else {
+        return Vec::new();
+    };
+    let mut loaded = Vec::with_capacity(files.len());
+    for f in files {
+        let parsed = tokio::fs::read(f.path())
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| psbt_from_file(&bytes));
+        loaded.push((f.file_name(), parsed));
+    }
+    loaded
+}
+
+/// A PSBT in the workbench.
+#[derive(Debug, Clone)]
+pub struct Loaded {
+    label: String,
+    base64: String,
+}
+
+/// Decoded and analysed form of one PSBT.
+#[derive(Debug, Clone)]
+pub struct Inspection {
+    base64: String,
+    decoded: DecodedPsbt,
+    analysis: PsbtAnalysis,
+}
+
+#[derive(Debug, Clone)]
+pub enum Msg {
+    PasteChanged(String),
+    AddPasted,
+    OpenFiles,
+    /// `(file name, base64 or error)` per picked file.
+    FilesLoaded(Vec<(String, Result<String, String>)>),
+    Select(usize),
+    Remove(usize),
+    Inspected(Result<Box<Inspection>, String>),
+    Combine,
+    Combined(Result<String, String>),
+    Finalize,
+    Finalized(Result<FinalizedPsbt, String>),
+    Copy,
+}
+
+#[derive(Default)]
+pub struct State {
+    paste: String,
+    psbts: Vec<Loaded>,
+    selected: Option<usize>,
+    inspection: Option<Inspection>,
+    finalized: Option<FinalizedPsbt>,
+    busy: bool,
+    error: Option<String>,
+}
+
+impl State {
+    fn selected_psbt(&self) -> Option<&Loaded> {
+        self.selected.and_then(|i| self.psbts.get(i))
+    }
+
+    #[allow(clippy::too_many_lines)]
+    pub fn update(&mut self, msg: Msg, auth: RpcAuth) -> Task<Message> {
+        match msg {
+            Msg::PasteChanged(s) => {
+                self.paste = s;
+                Task::none()
+            }
+            Msg::AddPasted => match psbt_from_text(&self.paste) {
+                Ok(base64) => {
+                    self.paste.clear();
+                    let label = format!("Pasted #{}", self.psbts.len() + 1);
+                    self.add(label, base64, auth)
+                }
+                Err(e) => {
+                    self.error = Some(e);
+                    Task::none()
+                }
+            },
+            Msg::OpenFiles => {
+                Task::perform(pick_psbt_files(), |r| Message::Psbt(Msg::FilesLoaded(r)))
+            }
+            Msg::FilesLoaded(files) => {
+                let mut task = Task::none();
+                let mut errors = Vec::new();
+                for (name, parsed) in files {
+                    match parsed {
+                        Ok(base64) => task = self.add(name, base64, auth.clone()),
+                        Err(e) => errors.push(format!("{name}: {e}")),
+                    }
+                }
+                if !errors.is_empty() {
+                    self.error = Some(errors.join("\n"));
+                }
+                task
+            }
+            Msg::Select(i) => {
+                self.selected = Some(i);
+                self.inspect(auth)
+            }
+            Msg::Remove(i) => {
+                if i < self.psbts.len() {
+                    self.psbts.remove(i);
+                }
+                self.selected = match self.selected {
+                    Some(s) if s == i => None,
+                    Some(s) if s > i => Some(s - 1),
+                    s => s,
+                };
+                if self.selected.is_none() {
+                    self.inspection = None;
+                    self.finalized = None;
+                }
+                Task::none()
+            }
+            Msg::Inspected(result) => {
+                self.busy = false;
+                match result {
+                    // Ignore a reply for a PSBT that is no longer selected.
+                    Ok(i) if self.selected_psbt().is_some_and(|p| p.base64 == i.base64) => {
+                        self.inspection = Some(*i);
+                    }
+                    Ok(_) => {}
+                    Err(e) => self.error = Some(e),
+                }
+                Task::none()
+            }
+            Msg::Combine => {
+                let all: Vec<String> = self.psbts.iter().map(|p| p.base64.clone()).collect();
+                self.busy = true;
+                self.error = None;
+                Task::perform(
+                    async move {
+                        rpc::combine_psbt(&auth, &all)
+                            .await
+                            .map_err(|e| e.to_string())
+                    },
+                    |r| Message::Psbt(Msg::Combined(r)),
+                )
+            }
+            Msg::Combined(result) => {
+                self.busy = false;
+                match result {
+                    Ok(base64) => {
+                        let label = format!("Combined ({} PSBTs)", self.psbts.len());
+                        self.add(label, base64, auth)
+                    }
+                    Err(e) => {
+                        self.error = Some(e);
+                        Task::none()
+                    }
+                }
+            }
+            Msg::Finalize => {
+                let Some(base64) = self.selected_psbt().map(|p| p.base64.clone()) else {
+                    return Task::none();
+                };
+                self.busy = true;
+                self.error = None;
+                Task::perform(
+                    async move {
+                        rpc::finalize_psbt(&auth, &base64)
+                            .await
+                            .map_err(|e| e.to_string())
+                    },
+                    |r| Message::Psbt(Msg::Finalized(r)),
+                )
+            }
+            Msg::Finalized(result) => {
+                self.busy = false;
+                match result {
+                    Ok(f) => self.finalized = Some(f),
+                    Err(e) => self.error = Some(e),
+                }
+                Task::none()
+            }
+            Msg::Copy => match self.selected_psbt() {
+                Some(p) => clipboard::write(p.base64.clone()),
+                None => Task::none(),
+            },
+        }
+    }
+
+    /// Add a PSBT (unless already loaded) and select it.
+    fn add(&mut self, label: String, base64: String, auth: RpcAuth) -> Task<Message> {
+        let index = if let Some(i) = self.psbts.iter().position(|p| p.base64 == base64) {
+            i
+        } else {
+            self.psbts.push(Loaded { label, base64 });
+            self.psbts.len() - 1
+        };
+        self.error = None;
+        self.update(Msg::Select(index), auth)
+    }
+
+    /// Decode and analyse the selected PSBT.
+    fn inspect(&mut self, auth: RpcAuth) -> Task<Message> {
+        let Some(base64) = self.selected_psbt().map(|p| p.base64.clone()) else {
+            return Task::none();
+        };
+        self.inspection = None;
+        self.finalized = None;
+        self.busy = true;
+        Task::perform(
+            async move {
+                let (decoded, analysis) = tokio::try_join!(
+                    rpc::decode_psbt(&auth, &base64),
+                    rpc::analyze_psbt(&auth, &base64),
+                )
+                .map_err(|e| e.to_string())?;
+                Ok(Box::new(Inspection {
+                    base64,
+                    decoded,
+                    analysis,
+                }))
+            },
+            |r| Message::Psbt(Msg::Inspected(r)),
+        )
+    }
+
+    pub fn view(&self, bitcoin_running: bool) -> Element<'_, Message> {
+        let enabled = bitcoin_running && !self.busy;
+        let paste = text_input("Paste a base64 or hex PSBT", &self.paste)
+            .on_input(|s| Message::Psbt(Msg::PasteChanged(s)))
+            .on_submit(Message::Psbt(Msg::AddPasted))
+            .size(12)
+            .padding(6);
+        let header = row![
+            section_label("PSBT WORKBENCH"),
+            Space::with_width(12),
+            paste,
+            styled_button("Add", ButtonStyle::Secondary)
+                .on_press_maybe(enabled.then_some(Message::Psbt(Msg::AddPasted))),
+            styled_button("Open Files…", ButtonStyle::Secondary)
+                .on_press_maybe(enabled.then_some(Message::Psbt(Msg::OpenFiles))),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center);
+
+        let mut body = column![header].spacing(10);
+        if !bitcoin_running {
+            body = body.push(text("Bitcoin is not running.").size(11).color(TEXT_TER));
+        }
+        if let Some(e) = &self.error {
+            body = body.push(text(e.as_str()).size(11).color(MAC_RED));
+        }
+        if !self.psbts.is_empty() {
+            body = body.push(self.view_list(enabled));
+        }
+        if let Some(f) = &self.finalized {
+            body = body.push(view_finalized(f));
+        }
+        if let Some(i) = &self.inspection {
+            body = body.push(horizontal_rule()).push(view_inspection(i));
+        }
+
+        page(scrollable(body).height(Length::Fill))
+    }
+
+    fn view_list(&self, enabled: bool) -> Element<'_, Message> {
+        let mut col = column![].spacing(4);
+        for (i, p) in self.psbts.iter().enumerate() {
+            let selected = self.selected == Some(i);
+            col = col.push(
+                row![
+                    button(text(p.label.as_str()).size(12).color(if selected {
+                        MAC_BLUE
+                    } else {
+                        Color::BLACK
+                    }))
+                    .padding(0)
+                    .style(|_, _| button::Style::default())
+                    .on_press_maybe(enabled.then_some(Message::Psbt(Msg::Select(i)))),
+                    Space::with_width(Length::Fill),
+                    styled_button("Remove", ButtonStyle::Secondary)
+                        .on_press(Message::Psbt(Msg::Remove(i))),
+                ]
+                .align_y(Alignment::Center),
+            );
+        }
+        let has_selection = self.selected_psbt().is_some();
+        col.push(
+            row![
+                Space::with_width(Length::Fill),
+                styled_button("Copy Base64", ButtonStyle::Secondary)
+                    .on_press_maybe(has_selection.then_some(Message::Psbt(Msg::Copy))),
+                styled_button("Combine All", ButtonStyle::Secondary).on_press_maybe(
+                    (enabled && self.psbts.len() > 1).then_some(Message::Psbt(Msg::Combine))
+                ),
+                styled_button("Finalize", ButtonStyle::Primary).on_press_maybe(
+                    (enabled && has_selection).then_some(Message::Psbt(Msg::Finalize))
+                ),
+            ]
+            .spacing(8),
+        )
+        .into()
+    }
+}
+
+fn view_finalized(f: &FinalizedPsbt) -> Element<'_, Message> {
+    match (&f.hex, f.complete) {
+        (Some(hex), true) => row![
+            text(format!(
+                "Finalized — {} byte transaction ready to broadcast.",
+                hex.len() / 2
+            ))
+            .size(12)
+            .color(GREEN),
+            Space::with_width(Length::Fill),
+            styled_button("Send to Broadcast", ButtonStyle::Confirm)
+                .on_press(Message::BroadcastHex(hex.clone())),
+        ]
+        .align_y(Alignment::Center)
+        .into(),
+        _ => text("Not complete — some inputs are still missing signatures or scripts.")
+            .size(12)
+            .color(MAC_ORG)
+            .into(),
+    }
+}
+
+fn view_inspection(i: &Inspection) -> Element<'_, Message> {
+    let a = &i.analysis;
+    let tx = &i.decoded.tx;
+    let fee = a.fee.or(i.decoded.fee);
+
+    let mut col = column![
+        text(format!("Next role: {}", a.next))
+            .size(16)
+            .color(Color::BLACK),
+        field("Txid", tx.txid.clone()),
+        field(
+            "Fee",
+            fee.map_or_else(|| "unknown (missing UTXOs)".to_owned(), format_btc)
+        ),
+        field(
+            "Estimated size",
+            a.estimated_vsize
+                .map_or_else(|| "—".to_owned(), |v| format!("{v} vB"))
+        ),
+        field(
+            "Estimated fee rate",
+            a.estimated_feerate.map_or_else(
+                || "—".to_owned(),
+                |r| format!("{:.1} sat/vB", rpc::btc_per_kvb_to_sat_per_vb(r))
+            )
+        ),
+        field("Outputs total", format_btc(tx.output_total())),
+    ]
+    .spacing(4);
+    if let Some(e) = &a.error {
+        col = col.push(text(e.as_str()).size(11).color(MAC_RED));
+    }
+
+    col = col.push(section_label("INPUTS"));
+    for (n, vin) in tx.vin.iter().enumerate() {
+        let psbt_in = i.decoded.inputs.get(n);
+        let analysis = a.inputs.get(n);
+        let source: Element<Message> = match (&vin.txid, vin.vout) {
+            (Some(txid), Some(v)) => {
+                link_button(&format!("{txid}:{v}"), Message::ExploreTx(txid.clone()))
+            }
+            _ => text("—").size(11).color(TEXT_SEC).into(),
+        };
+        let spent = psbt_in
+            .and_then(|p| p.witness_utxo.as_ref())
+            .map_or_else(String::new, |u| {
+                format!("{}  {}", u.script_pub_key.describe(), format_btc(u.amount))
+            });
+        let sigs = psbt_in.map_or(0, |p| p.partial_signatures.len());
+        let (status, color) = input_status(
+            analysis,
+            psbt_in.is_some_and(rpc::PsbtInput::is_finalized),
+            sigs,
+        );
+        col = col.push(
+            row![
+                text(format!("#{n}")).size(11).color(TEXT_TER).width(30),
+                row![source].width(Length::FillPortion(5)),
+                text(spent)
+                    .size(11)
+                    .color(TEXT_SEC)
+                    .width(Length::FillPortion(5)),
+                text(status)
+                    .size(11)
+                    .color(color)
+                    .width(Length::FillPortion(3)),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    col = col.push(section_label("OUTPUTS"));
+    for out in &tx.vout {
+        col = col.push(
+            row![
+                text(format!("#{}", out.n))
+                    .size(11)
+                    .color(TEXT_TER)
+                    .width(30),
+                text(out.script_pub_key.describe())
+                    .size(11)
+                    .color(TEXT_SEC)
+                    .width(Length::FillPortion(5)),
+                text(format_btc(out.value))
+                    .size(11)
+                    .color(TEXT_SEC)
+                    .width(Length::FillPortion(2)),
+            ]
+            .spacing(8),
+        );
+    }
+    col.into()
+}
+
+/// Status column for one input.
+fn input_status(
+    analysis: Option<&rpc::InputAnalysis>,
+    finalized: bool,
+    sigs: usize,
+) -> (String, Color) {
+    if finalized || analysis.is_some_and(|a| a.is_final) {
+        return ("final".to_owned(), GREEN);
+    }
+    let Some(a) = analysis else {
+        return ("—".to_owned(), TEXT_SEC);
+    };
+    if !a.has_utxo {
+        return ("missing UTXO".to_owned(), MAC_RED);
+    }
+    let next = a.next.as_deref().unwrap_or("?");
+    match a.missing.as_ref().map(rpc::MissingData::describe) {
+        Some(missing) if !missing.is_empty() => (
+            format!("{sigs} signed, missing {missing} · next: {next}"),
+            MAC_ORG,
+        ),
+        _ => (format!("{sigs} signed · next: {next}"), TEXT_SEC),
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_base64_hex_and_binary() {
+        let raw = b"psbt\xff\x01\x00";
+        let b64 = STANDARD.encode(raw);
+        assert_eq!(psbt_from_text(&format!(" {b64}\n")), Ok(b64.clone()));
+        assert_eq!(psbt_from_text(&hex::encode(raw)), Ok(b64.clone()));
+        assert_eq!(psbt_from_file(raw), Ok(b64.clone()));
+        assert_eq!(psbt_from_file(b64.as_bytes()), Ok(b64));
+
+        assert!(psbt_from_text("aGVsbG8=").is_err());
+        assert!(psbt_from_file(&[0x02, 0x00, 0xff]).is_err());
+    }
+}
@@ -0,0 +1,491 @@
+//! Snapshots tab — `AssumeUTXO`: bootstrap from a UTXO snapshot file with
+//! `loadtxoutset`, write one with `dumptxoutset`, and follow background
+//! validation of a snapshot chainstate through `getchainstates`.
+//!
+//! Each dump gets a `<file>.json` sidecar with the `dumptxoutset` result.
+//! Loading a file that has one checks the loaded base block against it, so
+//! a snapshot from our own node can be told apart from one of unknown
+//! origin.
+
+use std::path::{Path, PathBuf};
+
+use iced::{
+    widget::{column, pick_list, row, scrollable, text, text_input, Space},
+    Alignment, Element, Font, Length, Padding, Task,
+};
+
+use super::{
+    format_thousands, horizontal_rule, meter, page, section_label, styled_button, ButtonStyle,
+    Message, GREEN, MAC_BLUE, MAC_RED, TEXT_SEC, TEXT_TER,
+};
+use crate::rpc::{self, Chainstates, DumpType, DumpedSnapshot, RpcAuth};
+
+/// `getchainstates` plus the height of the snapshot's base block.
+#[derive(Debug, Clone)]
+pub struct Progress {
+    states: Chainstates,
+    base_height: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Msg {
+    Refresh,
+    Refreshed(Result<Box<Progress>, String>),
+    LoadPathChanged(String),
+    BrowseLoad,
+    DumpPathChanged(String),
+    BrowseDump,
+    /// A file dialog closed: `(for_dump, path)`.
+    Picked(bool, Option<String>),
+    DumpTypeSelected(DumpType),
+    Load,
+    Dump,
+    /// Go ahead with a rollback dump after the warning.
+    ConfirmDump,
+    CancelDump,
+    /// Result of a load or dump: status line text.
+    Done(Result<String, String>),
+}
+
+pub struct State {
+    progress: Option<Progress>,
+    load_path: String,
+    dump_path: String,
+    dump_type: DumpType,
+    /// A load or dump is running; both can take many minutes.
+    busy: Option<&'static str>,
+    status: Option<Result<String, String>>,
+    loading: bool,
+    /// A rollback dump awaiting confirmation.
+    confirm_dump: bool,
+}
+
+impl State {
+    /// `snapshot_dir` is where new dumps go by default.
+    pub fn new(snapshot_dir: &Path) -> Self {
+        Self {
+            progress: None,
+            load_path: String::new(),
+            dump_path: snapshot_dir
+                .join("utxo-snapshot.dat")
+                .to_string_lossy()
+                .into_owned(),
+            dump_type: DumpType::Rollback,
+            busy: None,
+            status: None,
+            loading: false,
+            confirm_dump: false,
+        }
+    }
+
+    #[allow(clippy::too_many_lines)]
+    /// `pruned`: bitcoind reports its blocks as pruned.
+    pub fn update(&mut self, msg: Msg, auth: RpcAuth, pruned: bool) -> Task<Message> {
+        match msg {
+            Msg::Refresh => {
+                if self.loading {
+                    return Task::none();
+                }
+                self.loading = true;
+                Task::perform(
+                    async move {
+                        let states = rpc::get_chainstates(&auth)
+                            .await
+                            .map_err(|e| e.to_string())?;
+                        let base = states.snapshot().and_then(|c| c.snapshot_blockhash.clone());
+                        let base_height = match base {
+                            Some(hash) => rpc::get_block_header(&auth, &hash)
+                                .await
+                                .ok()
+                                .map(|h| h.height),
+                            None => None,
+                        };
+                        Ok(Box::new(Progress {
+                            states,
+                            base_height,
+                        }))
+                    },
+                    |r| Message::Snapshots(Msg::Refreshed(r)),
+                )
+            }
+            Msg::Refreshed(result) => {
+                self.loading = false;
+                match result {
+                    Ok(progress) => self.progress = Some(*progress),
+                    Err(e) => {
+                        self.progress = None;
+                        if self.busy.is_none() {
+                            self.status = Some(Err(e));
+                        }
+                    }
+                }
+                Task::none()
+            }
+            Msg::LoadPathChanged(s) => {
+                self.load_path = s;
+                Task::none()
+            }
+            Msg::DumpPathChanged(s) => {
+                self.dump_path = s;
+                Task::none()
+            }
+            Msg::BrowseLoad => Task::perform(
+                async {
+                    rfd::AsyncFileDialog::new()
+                        .set_title("Open UTXO Snapshot")
+                        .pick_file()
+                        .await
+                        .map(|f| f.path().to_string_lossy().into_owned())
+                },
+                |p| Message::Snapshots(Msg::Picked(false, p)),
+            ),
+            Msg::BrowseDump => Task::perform(
+                async {
+                    rfd::AsyncFileDialog::new()
+                        .set_title("Save UTXO Snapshot")
+                        .set_file_name("utxo-snapshot.dat")
+                        .save_file()
+                        .await
+                        .map(|f| f.path().to_string_lossy().into_owned())
+                },
+                |p| Message::Snapshots(Msg::Picked(true, p)),
+            ),
+            Msg::Picked(_, None) => Task::none(),
+            Msg::Picked(for_dump, Some(path)) => {
+                if for_dump {
+                    self.dump_path = path;
+                } else {
+                    self.load_path = path;
+                }
+                Task::none()
+            }
+            Msg::DumpTypeSelected(kind) => {
+                self.dump_type = kind;
+                self.confirm_dump = false;
+                Task::none()
+            }
+            Msg::Load => {
+                let path = PathBuf::from(self.load_path.trim());
+                self.busy = Some("Loading snapshot…");
+                self.status = None;
+                Task::perform(load(auth, path), |r| Message::Snapshots(Msg::Done(r)))
+            }
+            Msg::Dump => match self.dump_type {
+                DumpType::Latest => self.start_dump(auth),
+                // Rolling back needs the blocks above the snapshot height
+                // to roll forward again, and a pruned node has deleted them.
+                DumpType::Rollback if pruned => {
+                    self.status = Some(Err(ROLLBACK_PRUNED.to_owned()));
+                    Task::none()
+                }
+                DumpType::Rollback => {
+                    self.confirm_dump = true;
+                    Task::none()
+                }
+            },
+            Msg::ConfirmDump => {
+                self.confirm_dump = false;
+                if pruned {
+                    self.status = Some(Err(ROLLBACK_PRUNED.to_owned()));
+                    return Task::none();
+                }
+                self.start_dump(auth)
+            }
+            Msg::CancelDump => {
+                self.confirm_dump = false;
+                Task::none()
+            }
+            Msg::Done(result) => {
+                self.busy = None;
+                self.status = Some(result);
+                Task::done(Message::Snapshots(Msg::Refresh))
+            }
+        }
+    }
+
+    fn start_dump(&mut self, auth: RpcAuth) -> Task<Message> {
+        let path = PathBuf::from(self.dump_path.trim());
+        self.busy = Some("Writing snapshot…");
+        self.status = None;
+        Task::perform(dump(auth, path, self.dump_type), |r| {
+            Message::Snapshots(Msg::Done(r))
+        })
+    }
+
+    pub fn view(&self, bitcoin_running: bool, pruned: bool) -> Element<'_, Message> {
+        let refresh = styled_button(
+            if self.loading {
+                "Refreshing…"
+            } else {
+                "Refresh"
+            },
+            ButtonStyle::Secondary,
+        )
+        .on_press_maybe(bitcoin_running.then_some(Message::Snapshots(Msg::Refresh)));
+
+        let header = row![
+            section_label("CHAINSTATES"),
+            Space::with_width(Length::Fill),
+            refresh
+        ]
+        .align_y(Alignment::Center);
+
+        let mut body = column![header].spacing(8);
+        if let Some(busy) = self.busy {
+            body = body.push(text(busy).size(11).color(MAC_BLUE));
+        }
+        if let Some(status) = &self.status {
+            body = body.push(match status {
+                Ok(s) => text(s.as_str()).size(11).color(TEXT_SEC),
+                Err(e) => text(e.as_str()).size(11).color(MAC_RED),
+            });
+        }
+        if !bitcoin_running {
+            body = body.push(text("Bitcoin is not running.").size(11).color(TEXT_TER));
+        }
+        body = body
+            .push(self.view_progress())
+            .push(horizontal_rule())
+            .push(self.view_load(bitcoin_running))
+            .push(horizontal_rule())
+            .push(self.view_dump(bitcoin_running, pruned));
+
+        page(scrollable(body).height(Length::Fill))
+    }
+
+    fn view_progress(&self) -> Element<'_, Message> {
+        let Some(progress) = &self.progress else {
+            return Space::with_height(0).into();
+        };
+        let states = &progress.states;
+        let Some(snapshot) = states.snapshot() else {
+            let tip = states.chainstates.first().map_or(0, |c| c.blocks);
+            return text(format!(
+                "One fully validated chainstate at height {}.",
+                format_thousands(tip)
+            ))
+            .size(12)
+            .into();
+        };
+        let base = progress
+            .base_height
+            .map_or_else(|| "?".to_owned(), format_thousands);
+        let mut col = column![text(format!(
+            "Snapshot chainstate: tip {} of {} headers, loaded at block {base}",
+            format_thousands(snapshot.blocks),
+            format_thousands(states.headers)
+        ))
+        .size(12)]
+        .spacing(6);
+        match (states.background(), progress.base_height) {
+            (Some(bg), Some(base_height)) if base_height > 0 => {
+                #[allow(clippy::cast_precision_loss)]
+                let fraction = bg.blocks as f32 / base_height as f32;
+                col = col
+                    .push(
+                        text(format!(
+                            "Background validation: block {} of {} ({:.1}%)",
+                            format_thousands(bg.blocks),
+                            format_thousands(base_height),
+                            fraction * 100.0
+                        ))
+                        .size(11)
+                        .color(TEXT_SEC),
+                    )
+                    .push(meter(fraction, GREEN));
+            }
+            _ => {
+                col = col.push(
+                    text("Background validation has not started.")
+                        .size(11)
+                        .color(TEXT_TER),
+                );
+            }
+        }
+        col.push(
+            text(
+                "Until background validation reaches the snapshot block, the \
+                 snapshot's UTXO set is trusted rather than verified.",
+            )
+            .size(10)
+            .color(TEXT_TER),
+        )
+        .into()
+    }
+
+    fn view_load(&self, bitcoin_running: bool) -> Element<'_, Message> {
+        let ready = bitcoin_running && self.busy.is_none() && !self.load_path.trim().is_empty();
+        column![
+            section_label("LOAD SNAPSHOT"),
+            text(
+                "loadtxoutset accepts only snapshots at a height built into bitcoind, \
+                 and needs the headers past it. Load into a fresh node."
+            )
+            .size(10)
+            .color(TEXT_TER),
+            row![
+                text_input("Path to a UTXO snapshot file", &self.load_path)
+                    .on_input(|s| Message::Snapshots(Msg::LoadPathChanged(s)))
+                    .padding(Padding::from([4, 6]))
+                    .font(Font::MONOSPACE)
+                    .size(11),
+                Space::with_width(6),
+                styled_button("Browse…", ButtonStyle::Secondary)
+                    .on_press(Message::Snapshots(Msg::BrowseLoad)),
+                Space::with_width(6),
+                styled_button("Load", ButtonStyle::Primary)
+                    .on_press_maybe(ready.then_some(Message::Snapshots(Msg::Load))),
+            ]
+            .align_y(Alignment::Center),
+        ]
+        .spacing(6)
+        .into()
+    }
+
+    fn view_dump(&self, bitcoin_running: bool, pruned: bool) -> Element<'_, Message> {
+        let refused = pruned && self.dump_type == DumpType::Rollback;
+        let ready =
+            bitcoin_running && self.busy.is_none() && !refused && !self.dump_path.trim().is_empty();
+        let mut col = column![
+            section_label("DUMP SNAPSHOT"),
+            text(
+                "Rollback rewinds to the latest loadable height, writes the set and rolls \
+                 forward again; the node is busy meanwhile. Current tip snapshots can only \
+                 be loaded by builds that list that height."
+            )
+            .size(10)
+            .color(TEXT_TER),
+            row![
+                text_input("Where to write the snapshot", &self.dump_path)
+                    .on_input(|s| Message::Snapshots(Msg::DumpPathChanged(s)))
+                    .padding(Padding::from([4, 6]))
+                    .font(Font::MONOSPACE)
+                    .size(11),
+                Space::with_width(6),
+                styled_button("Browse…", ButtonStyle::Secondary)
+                    .on_press(Message::Snapshots(Msg::BrowseDump)),
+            ]
+            .align_y(Alignment::Center),
+            row![
+                pick_list(DumpType::ALL, Some(self.dump_type), |k| {
+                    Message::Snapshots(Msg::DumpTypeSelected(k))
+                })
+                .text_size(12),
+                Space::with_width(Length::Fill),
+                styled_button("Dump", ButtonStyle::Primary)
+                    .on_press_maybe(ready.then_some(Message::Snapshots(Msg::Dump))),
+            ]
+            .align_y(Alignment::Center),
+        ]
+        .spacing(6);
+        if refused {
+            col = col.push(text(ROLLBACK_PRUNED).size(11).color(MAC_RED));
+        } else if self.confirm_dump {
+            col = col.push(
+                row![
+                    text(
+                        "Roll back for the dump? bitcoind invalidates every block above the \
+                         snapshot height, turns networking off, writes the set and then \
+                         reconnects those blocks. On mainnet this takes hours; the node \
+                         neither relays nor follows the tip meanwhile, and stopping it \
+                         midway leaves it rolled back until it catches up again."
+                    )
+                    .size(12)
+                    .color(MAC_RED),
+                    Space::with_width(Length::Fill),
+                    styled_button("Cancel", ButtonStyle::Secondary)
+                        .on_press(Message::Snapshots(Msg::CancelDump)),
+                    styled_button("Roll Back and Dump", ButtonStyle::Destructive).on_press_maybe(
+                        (bitcoin_running && self.busy.is_none())
+                            .then_some(Message::Snapshots(Msg::ConfirmDump))
+                    ),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center),
+            );
+        }
+        col.into()
+    }
+}
+
+const ROLLBACK_PRUNED: &str = "A pruned node cannot roll back: the blocks it would \
+                               reconnect afterwards are deleted. Dump the current tip instead.";
+
+/// `<file>.json` next to a snapshot.
+fn sidecar_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".json");
+    PathBuf::from(name)
+}
+
+async fn load(auth: RpcAuth, path: PathBuf) -> Result<String, String> {
+    if !path.is_file() {
+        return Err(format!("{} is not a file", path.display()));
+    }
+    let recorded: Option<DumpedSnapshot> = tokio::fs::read(sidecar_path(&path))
+        .await
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok());
+    let loaded = rpc::load_txoutset(&auth, &path)
+        .await
+        .map_err(|e| format!("{e:#}"))?;
+    let mut msg = format!(
+        "Loaded {} coins at block {}; background validation starts from genesis.",
+        format_thousands(loaded.coins_loaded),
+        format_thousands(loaded.base_height)
+    );
+    match recorded {
+        Some(r) if r.base_hash == loaded.tip_hash => {
+            msg.push_str(" Matches the snapshot this manager dumped.");
+        }
+        Some(r) => {
+            return Err(format!(
+                "{msg} Warning: the loaded base block {} differs from the dump record ({}).",
+                loaded.tip_hash, r.base_hash
+            ));
+        }
+        None => msg.push_str(" No dump record found next to the file."),
+    }
+    Ok(msg)
+}
+
+async fn dump(auth: RpcAuth, path: PathBuf, kind: DumpType) -> Result<String, String> {
+    if path.exists() {
+        return Err(format!("{} already exists", path.display()));
+    }
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|e| format!("create {}: {e}", dir.display()))?;
+    }
+    let dumped = rpc::dump_txoutset(&auth, &path, kind)
+        .await
+        .map_err(|e| format!("{e:#}"))?;
+    let record = serde_json::to_vec_pretty(&dumped).map_err(|e| e.to_string())?;
+    let sidecar = sidecar_path(&path);
+    tokio::fs::write(&sidecar, record)
+        .await
+        .map_err(|e| format!("write {}: {e}", sidecar.display()))?;
+    Ok(format!(
+        "Wrote {} coins at block {} to {}. UTXO set hash {}.",
+        format_thousands(dumped.coins_written),
+        format_thousands(dumped.base_height),
+        path.display(),
+        dumped.txoutset_hash
+    ))
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sidecar_sits_next_to_the_snapshot() {
+        assert_eq!(
+            sidecar_path(Path::new("/Volumes/SSD/Snapshots/utxo-880000.dat")),
+            Path::new("/Volumes/SSD/Snapshots/utxo-880000.dat.json")
+        );
+    }
+}
@@ -0,0 +1,559 @@
+//! Wallets tab — list, create, load/unload and back up the node's wallets,
+//! with `getwalletinfo` / `getbalances` for the selected one.
+
+use std::{
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use iced::{
+    widget::{button, column, row, scrollable, text, text_input, Space},
+    Alignment, Color, Element, Length, Task,
+};
+
+use super::{
+    browse_folder, format_thousands, format_unix_time, horizontal_rule, page, section_label,
+    styled_button, styled_checkbox, ButtonStyle, Message, GREEN, MAC_BLUE, MAC_ORG, MAC_RED,
+    TEXT_SEC, TEXT_TER,
+};
+use crate::rpc::{self, Balances, CreateWallet, RpcAuth, WalletInfo};
+
+/// A wallet found in the wallets directory or loaded by the node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletEntry {
+    /// `""` is the default wallet.
+    name: String,
+    loaded: bool,
+}
+
+impl WalletEntry {
+    fn display_name(&self) -> &str {
+        display_name(&self.name)
+    }
+}
+
+fn display_name(name: &str) -> &str {
+    if name.is_empty() {
+        "(default)"
+    } else {
+        name
+    }
+}
+
+/// Merge `listwalletdir` with `listwallets`: wallets loaded from outside
+/// the wallets directory only appear in the latter.
+fn merge_wallets(on_disk: Vec<String>, loaded: &[String]) -> Vec<WalletEntry> {
+    let mut wallets: Vec<WalletEntry> = on_disk
+        .into_iter()
+        .map(|name| WalletEntry {
+            loaded: loaded.contains(&name),
+            name,
+        })
+        .collect();
+    for name in loaded {
+        if !wallets.iter().any(|w| &w.name == name) {
+            wallets.push(WalletEntry {
+                name: name.clone(),
+                loaded: true,
+            });
+        }
+    }
+    wallets.sort_by(|a, b| a.name.cmp(&b.name));
+    wallets
+}
+
+/// Backup file name: `<wallet>-YYYY-MM-DD_HHMMSS.bak`.
+fn backup_file_name(wallet: &str, now: u64) -> String {
+    let stamp: String = format_unix_time(now)
+        .trim_end_matches(" UTC")
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('_'),
+            ':' => None,
+            c => Some(c),
+        })
+        .collect();
+    let name = if wallet.is_empty() { "default" } else { wallet };
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{name}-{stamp}.bak")
+}
+
+/// `getwalletinfo` and `getbalances` for one wallet.
+#[derive(Debug, Clone)]
+pub struct Details {
+    name: String,
+    info: WalletInfo,
+    balances: Balances,
+}
+
+#[derive(Debug, Clone)]
+pub enum Msg {
+    Refresh,
+    Loaded(Result<Vec<WalletEntry>, String>),
+    Select(String),
+    DetailsLoaded(Result<Details, String>),
+    Load(String),
+    Unload(String),
+    Backup(String),
+    BackupFolderPicked(String, Option<String>),
+    // Create form
+    NameChanged(String),
+    DescriptorsToggled(bool),
+    BlankToggled(bool),
+    WatchOnlyToggled(bool),
+    PassphraseChanged(String),
+    Create,
+    /// Result of a create/load/unload/backup: status line text.
+    ActionDone(Result<String, String>),
+}
+
+#[derive(Default)]
+pub struct State {
+    wallets: Vec<WalletEntry>,
+    selected: Option<String>,
+    details: Option<Details>,
+    form: CreateWallet,
+    /// A create/load/unload/backup is in flight.
+    busy: bool,
+    /// The operation in flight is a create; the form clears once it succeeds.
+    creating: bool,
+    loading: bool,
+    status: Option<Result<String, String>>,
+}
+
+impl State {
+    #[allow(clippy::too_many_lines)]
+    pub fn update(&mut self, msg: Msg, auth: RpcAuth) -> Task<Message> {
+        match msg {
+            Msg::Refresh => {
+                if self.loading {
+                    return Task::none();
+                }
+                self.loading = true;
+                Task::perform(
+                    async move {
+                        let (on_disk, loaded) = tokio::try_join!(
+                            rpc::list_wallet_dir(&auth),
+                            rpc::list_wallets(&auth),
+                        )
+                        .map_err(|e| e.to_string())?;
+                        Ok(merge_wallets(on_disk, &loaded))
+                    },
+                    |r| Message::Wallets(Msg::Loaded(r)),
+                )
+            }
+            Msg::Loaded(result) => {
+                self.loading = false;
+                match result {
+                    Ok(wallets) => self.set_wallets(wallets, auth),
+                    Err(e) => {
+                        self.status = Some(Err(e));
+                        Task::none()
+                    }
+                }
+            }
+            Msg::Select(name) => {
+                if self.selected.as_ref() != Some(&name) {
+                    self.details = None;
+                }
+                self.selected = Some(name.clone());
+                fetch_details(name, auth)
+            }
+            Msg::DetailsLoaded(result) => {
+                match result {
+                    // Ignore replies for a wallet no longer selected.
+                    Ok(d) if self.selected.as_ref() == Some(&d.name) => self.details = Some(d),
+                    Ok(_) => {}
+                    Err(e) => self.status = Some(Err(e)),
+                }
+                Task::none()
+            }
+            Msg::Load(name) => self.action(async move {
+                let warnings = rpc::load_wallet(&auth, &name).await?;
+                Ok(with_warnings(
+                    format!("Loaded {}.", display_name(&name)),
+                    &warnings,
+                ))
+            }),
+            Msg::Unload(name) => {
+                if self.selected.as_ref() == Some(&name) {
+                    self.selected = None;
+                    self.details = None;
+                }
+                self.action(async move {
+                    rpc::unload_wallet(&auth, &name).await?;
+                    Ok(format!("Unloaded {}.", display_name(&name)))
+                })
+            }
+            Msg::Backup(name) => Task::perform(
+                async move {
+                    let folder = browse_folder("Select Backup Folder").await;
+                    (name, folder)
+                },
+                |(name, folder)| Message::Wallets(Msg::BackupFolderPicked(name, folder)),
+            ),
+            Msg::BackupFolderPicked(_, None) => Task::none(),
+            Msg::BackupFolderPicked(name, Some(folder)) => {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |d| d.as_secs());
+                let dest = PathBuf::from(folder).join(backup_file_name(&name, now));
+                self.action(async move {
+                    rpc::backup_wallet(&auth, &name, &dest).await?;
+                    Ok(format!(
+                        "Backed up {} to {}.",
+                        display_name(&name),
+                        dest.display()
+                    ))
+                })
+            }
+            Msg::NameChanged(s) => {
+                self.form.name = s;
+                Task::none()
+            }
+            Msg::DescriptorsToggled(b) => {
+                self.form.descriptors = b;
+                Task::none()
+            }
+            Msg::BlankToggled(b) => {
+                self.form.blank = b;
+                Task::none()
+            }
+            Msg::WatchOnlyToggled(b) => {
+                self.form.disable_private_keys = b;
+                Task::none()
+            }
+            Msg::PassphraseChanged(s) => {
+                self.form.passphrase = s;
+                Task::none()
+            }
+            Msg::Create => {
+                // Kept until the wallet exists, so a failure can be retried.
+                let opts = self.form.clone();
+                self.creating = true;
+                self.selected = Some(opts.name.clone());
+                self.details = None;
+                self.action(async move {
+                    let warnings = rpc::create_wallet(&auth, &opts).await?;
+                    Ok(with_warnings(
+                        format!("Created {}.", display_name(&opts.name)),
+                        &warnings,
+                    ))
+                })
+            }
+            Msg::ActionDone(result) => {
+                self.busy = false;
+                if std::mem::take(&mut self.creating) && result.is_ok() {
+                    self.form = CreateWallet {
+                        descriptors: self.form.descriptors,
+                        ..CreateWallet::default()
+                    };
+                }
+                self.status = Some(result);
+                Task::done(Message::Wallets(Msg::Refresh))
+            }
+        }
+    }
+
+    /// Store a fresh wallet list and fetch details for the selection.
+    fn set_wallets(&mut self, wallets: Vec<WalletEntry>, auth: RpcAuth) -> Task<Message> {
+        self.wallets = wallets;
+        // Default to the only loaded wallet; drop a selection that was
+        // unloaded elsewhere.
+        let loaded: Vec<&WalletEntry> = self.wallets.iter().filter(|w| w.loaded).collect();
+        if self
+            .selected
+            .as_ref()
+            .is_none_or(|s| !loaded.iter().any(|w| &w.name == s))
+        {
+            self.selected = match loaded.as_slice() {
+                [only] => Some(only.name.clone()),
+                _ => None,
+            };
+        }
+        if let Some(name) = self.selected.clone() {
+            fetch_details(name, auth)
+        } else {
+            self.details = None;
+            Task::none()
+        }
+    }
+
+    /// Run a wallet operation, reporting its outcome in the status line.
+    fn action(
+        &mut self,
+        op: impl std::future::Future<Output = anyhow::Result<String>> + Send + 'static,
+    ) -> Task<Message> {
+        self.busy = true;
+        self.status = None;
+        Task::perform(async move { op.await.map_err(|e| format!("{e:#}")) }, |r| {
+            Message::Wallets(Msg::ActionDone(r))
+        })
+    }
+
+    pub fn view(&self, bitcoin_running: bool) -> Element<'_, Message> {
+        let refresh = styled_button(
+            if self.loading {
+                "Refreshing…"
+            } else {
+                "Refresh"
+            },
+            ButtonStyle::Secondary,
+        )
+        .on_press_maybe(bitcoin_running.then_some(Message::Wallets(Msg::Refresh)));
+
+        let header = row![
+            section_label("WALLETS"),
+            Space::with_width(Length::Fill),
+            refresh
+        ]
+        .align_y(Alignment::Center);
+
+        let mut body = column![header].spacing(8);
+        if let Some(status) = &self.status {
+            body = body.push(match status {
+                Ok(s) => text(s.as_str()).size(11).color(TEXT_SEC),
+                Err(e) => text(e.as_str()).size(11).color(MAC_RED),
+            });
+        }
+        if !bitcoin_running {
+            body = body.push(text("Bitcoin is not running.").size(11).color(TEXT_TER));
+        }
+
+        body = body.push(self.view_list(bitcoin_running));
+        if let Some(details) = &self.details {
+            body = body
+                .push(horizontal_rule())
+                .push(self.view_details(details, bitcoin_running));
+        }
+        body = body
+            .push(horizontal_rule())
+            .push(self.view_create(bitcoin_running));
+
+        page(scrollable(body).height(Length::Fill))
+    }
+
+    fn view_list(&self, bitcoin_running: bool) -> Element<'_, Message> {
+        if self.wallets.is_empty() {
+            return text("No wallets.").size(11).color(TEXT_TER).into();
+        }
+        let enabled = bitcoin_running && !self.busy;
+        let rows: Vec<Element<Message>> = self
+            .wallets
+            .iter()
+            .map(|w| {
+                let selected = self.selected.as_ref() == Some(&w.name);
+                let name = text(w.display_name()).size(12).color(if selected {
+                    MAC_BLUE
+                } else {
+                    Color::BLACK
+                });
+                let name: Element<Message> = if w.loaded {
+                    button(name)
+                        .padding(0)
+                        .style(|_, _| button::Style::default())
+                        .on_press(Message::Wallets(Msg::Select(w.name.clone())))
+                        .into()
+                } else {
+                    name.into()
+                };
+                let action = if w.loaded {
+                    styled_button("Unload", ButtonStyle::Secondary).on_press_maybe(
+                        enabled.then(|| Message::Wallets(Msg::Unload(w.name.clone()))),
+                    )
+                } else {
+                    styled_button("Load", ButtonStyle::Primary).on_press_maybe(
+                        enabled.then(|| Message::Wallets(Msg::Load(w.name.clone()))),
+                    )
+                };
+                row![
+                    row![name].width(Length::FillPortion(4)),
+                    text(if w.loaded { "loaded" } else { "not loaded" })
+                        .size(11)
+                        .color(if w.loaded { GREEN } else { TEXT_TER })
+                        .width(Length::FillPortion(2)),
+                    action,
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center)
+                .into()
+            })
+            .collect();
+        column(rows).spacing(4).into()
+    }
+
+    fn view_details<'a>(&'a self, d: &'a Details, bitcoin_running: bool) -> Element<'a, Message> {
+        let info = &d.info;
+        let mine = d.balances.mine;
+        let mut col = column![
+            row![
+                section_label("SELECTED WALLET"),
+                text(format!("  {}", display_name(&info.walletname)))
+                    .size(13)
+                    .color(Color::BLACK),
+                Space::with_width(Length::Fill),
+                styled_button("Back Up…", ButtonStyle::Secondary).on_press_maybe(
+                    (bitcoin_running && !self.busy)
+                        .then(|| Message::Wallets(Msg::Backup(d.name.clone())))
+                ),
+            ]
+            .align_y(Alignment::Center),
+            text(format!(
+                "{:.8} BTC trusted  ·  {:.8} pending  ·  {:.8} immature",
+                mine.trusted, mine.untrusted_pending, mine.immature
+            ))
+            .size(14)
+            .color(Color::BLACK),
+        ]
+        .spacing(6);
+        if let Some(w) = d.balances.watchonly {
+            col = col.push(
+                text(format!(
+                    "Watch-only: {:.8} BTC trusted  ·  {:.8} pending  ·  {:.8} immature",
+                    w.trusted, w.untrusted_pending, w.immature
+                ))
+                .size(11)
+                .color(TEXT_SEC),
+            );
+        }
+
+        let kind = match (info.descriptors, info.private_keys_enabled) {
+            (true, true) => "descriptor",
+            (true, false) => "descriptor, watch-only",
+            (false, true) => "legacy",
+            (false, false) => "legacy, watch-only",
+        };
+        let encryption = match info.unlocked_until {
+            None => "not encrypted",
+            Some(0) => "encrypted, locked",
+            Some(_) => "encrypted, unlocked",
+        };
+        col = col.push(
+            text(format!(
+                "{kind}  ·  {}  ·  {encryption}  ·  {} transactions  ·  keypool {}",
+                if info.format.is_empty() {
+                    "bdb"
+                } else {
+                    &info.format
+                },
+                format_thousands(info.txcount),
+                info.keypoolsize
+            ))
+            .size(11)
+            .color(TEXT_SEC),
+        );
+        if let Some(p) = info.scan_progress() {
+            col = col.push(
+                text(format!("Rescanning… {:.1}%", p * 100.0))
+                    .size(11)
+                    .color(MAC_ORG),
+            );
+        }
+        col.into()
+    }
+
+    fn view_create(&self, bitcoin_running: bool) -> Element<'_, Message> {
+        let f = &self.form;
+        let name = text_input("Wallet name", &f.name)
+            .on_input(|s| Message::Wallets(Msg::NameChanged(s)))
+            .size(12)
+            .padding(6);
+        let passphrase = text_input("Passphrase (optional)", &f.passphrase)
+            .on_input(|s| Message::Wallets(Msg::PassphraseChanged(s)))
+            .secure(true)
+            .size(12)
+            .padding(6);
+        // A watch-only wallet has no private keys to encrypt.
+        let can_create = bitcoin_running
+            && !self.busy
+            && !self.wallets.iter().any(|w| w.name == f.name)
+            && (!f.disable_private_keys || f.passphrase.is_empty());
+
+        column![
+            section_label("CREATE WALLET"),
+            row![name, passphrase].spacing(8),
+            row![
+                styled_checkbox("Descriptor wallet", f.descriptors)
+                    .on_toggle(|b| Message::Wallets(Msg::DescriptorsToggled(b))),
+                styled_checkbox("Blank", f.blank)
+                    .on_toggle(|b| Message::Wallets(Msg::BlankToggled(b))),
+                styled_checkbox("Watch-only (no private keys)", f.disable_private_keys)
+                    .on_toggle(|b| Message::Wallets(Msg::WatchOnlyToggled(b))),
+                Space::with_width(Length::Fill),
+                styled_button("Create", ButtonStyle::Confirm)
+                    .on_press_maybe(can_create.then_some(Message::Wallets(Msg::Create))),
+            ]
+            .spacing(16)
+            .align_y(Alignment::Center),
+        ]
+        .spacing(8)
+        .into()
+    }
+}
+
+fn fetch_details(name: String, auth: RpcAuth) -> Task<Message> {
+    Task::perform(
+        async move {
+            let (info, balances) = tokio::try_join!(
+                rpc::get_wallet_info(&auth, &name),
+                rpc::get_balances(&auth, &name),
+            )
+            .map_err(|e| e.to_string())?;
+            Ok(Details {
+                name,
+                info,
+                balances,
+            })
+        },
+        |r| Message::Wallets(Msg::DetailsLoaded(r)),
+    )
+}
+
+fn with_warnings(msg: String, warnings: &[String]) -> String {
+    if warnings.is_empty() {
+        msg
+    } else {
+        format!("{msg} Warning: {}", warnings.join(" "))
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_wallet_lists() {
+        let wallets = merge_wallets(
+            vec!["cold".to_owned(), String::new()],
+            &["hot".to_owned(), "cold".to_owned()],
+        );
+        let summary: Vec<(&str, bool)> = wallets
+            .iter()
+            .map(|w| (w.display_name(), w.loaded))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("(default)", false), ("cold", true), ("hot", true)]
+        );
+    }
+
+    #[test]
+    fn backup_names_are_safe_and_timestamped() {
+        assert_eq!(
+            backup_file_name("my wallet/1", 1_700_000_000),
+            "my_wallet_1-2023-11-14_221320.bak"
+        );
+        assert_eq!(backup_file_name("", 0), "default-1970-01-01_000000.bak");
+    }
+}
@@ -0,0 +1,405 @@
+//! Where the updater looks for new binaries.
+//!
+//! Every source exposes the same folder-per-build layout the updater has
+//! always used (`bitcoin-27.0/bitcoind`, `electrs-0.10.5/electrs`, …):
+//!
+//!   * [`LocalDirSource`]   — a directory on this machine (e.g. the `BitForge`
+//!     output in `~/Downloads`, or a folder on the SSD itself).
+//!   * [`HttpMirrorSource`] — a plain HTTP directory listing (nginx/Apache
+//!     autoindex, `python3 -m http.server`) serving that same layout.
+//!
+//! Every binary downloaded from a mirror is checked against a `SHA256SUMS`
+//! file in its build folder, or failing that a `<binary>.sha256` next to it.
+//! A binary without a checksum, or with one that does not match, fails that
+//! build.  The checksums come from the same mirror, so this only catches
+//! corrupted or truncated transfers: it is no proof of authenticity against
+//! a tampered mirror or a man-in-the-middle on plain HTTP.  Only add mirrors
+//! you control.
+//!
+//! Sources are configured in [`Config::update_sources`](crate::config::Config)
+//! as [`UpdateSourceConfig`] values and turned into trait objects with
+//! [`from_config`].
+
+use std::{
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Result};
+use ring::digest::{Context as Digest, SHA256};
+
+use crate::config::UpdateSourceConfig;
+
+/// Directory (inside the binaries folder) where remote builds are staged
+/// before being installed.
+pub const STAGING_DIR: &str = ".update-staging";
+
+/// Checksum list a mirror serves in each build folder.
+const SHA256SUMS: &str = "SHA256SUMS";
+
+/// A place that offers versioned build folders.
+pub trait UpdateSource {
+    /// Short human-readable description, used in plans and error messages.
+    fn describe(&self) -> String;
+
+    /// Names of all build folders offered (`bitcoin-27.0`, `electrs-0.10.5`, …).
+    fn list_folders(&self) -> Result<Vec<String>>;
+
+    /// Which of `names` are present inside `folder`.
+    fn available_binaries(&self, folder: &str, names: &[&str]) -> Result<Vec<String>>;
+
+    /// Make `binaries` from `folder` available on the local filesystem and
+    /// return the directory containing them.  `staging` is a scratch
+    /// directory the source may download into.
+    fn fetch(&self, folder: &str, binaries: &[String], staging: &Path) -> Result<PathBuf>;
+
+    /// Local directory of `folder` if it can be inspected without fetching.
+    fn local_path(&self, _folder: &str) -> Option<PathBuf> {
+        None
+    }
+}
+
+/// Build the trait object for a configured source.
+pub fn from_config(cfg: &UpdateSourceConfig) -> Box<dyn UpdateSource> {
+    match cfg {
+        UpdateSourceConfig::LocalDir { path } => Box::new(LocalDirSource { root: path.clone() }),
+        UpdateSourceConfig::HttpMirror { url } => Box::new(HttpMirrorSource::new(url)),
+    }
+}
+
+// ── Local directory ───────────────────────────────────────────────────────────
+
+/// Build folders inside a local directory.
+pub struct LocalDirSource {
+    pub root: PathBuf,
+}
+
+impl UpdateSource for LocalDirSource {
+    fn describe(&self) -> String {
+        self.root.display().to_string()
+    }
+
+    fn list_folders(&self) -> Result<Vec<String>> {
+        let entries = fs::read_dir(&self.root)
+            .with_context(|| format!("read update folder {}", self.root.display()))?;
+        Ok(entries
+            .flatten()
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .collect())
+    }
+
+    fn available_binaries(&self, folder: &str, names: &[&str]) -> Result<Vec<String>> {
+        let dir = self.root.join(folder);
+        Ok(names
+            .iter()
+            .filter(|n| dir.join(n).is_file())
+            .map(|&n| n.to_owned())
+            .collect())
+    }
+
+    fn fetch(&self, folder: &str, _binaries: &[String], _staging: &Path) -> Result<PathBuf> {
+        Ok(self.root.join(folder))
+    }
+
+    fn local_path(&self, folder: &str) -> Option<PathBuf> {
+        Some(self.root.join(folder))
+    }
+}
+
+// ── HTTP mirror ───────────────────────────────────────────────────────────────
+
+/// Build folders served from an HTTP directory listing.
+pub struct HttpMirrorSource {
+    /// Base URL, always ending in `/`.
+    base_url: String,
+}
+
+impl HttpMirrorSource {
+    pub fn new(url: &str) -> Self {
+        let mut base_url = url.trim().to_owned();
+        if !base_url.ends_with('/') {
+            base_url.push('/');
+        }
+        Self { base_url }
+    }
+
+    fn client(timeout: Duration) -> Result<reqwest::blocking::Client> {
+        reqwest::blocking::Client::builder()
+            .timeout(timeout)
+            .build()
+            .context("build HTTP client")
+    }
+
+    /// Body of `url`, or `None` when the mirror has no such file.
+    fn get_text(client: &reqwest::blocking::Client, url: &str) -> Result<Option<String>> {
+        let resp = client
+            .get(url)
+            .send()
+            .with_context(|| format!("GET {url}"))?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !resp.status().is_success() {
+            bail!("GET {url}: HTTP {}", resp.status());
+        }
+        resp.text().map(Some).with_context(|| format!("read {url}"))
+    }
+
+    /// The SHA-256 the mirror publishes for `folder/name`.
+    fn expected_sha256(
+        &self,
+        client: &reqwest::blocking::Client,
+        folder: &str,
+        sums: Option<&str>,
+        name: &str,
+    ) -> Result<String> {
+        if let Some(hash) = sums.and_then(|sums| sha256sums_entry(sums, name)) {
+            return Ok(hash);
+        }
+        let url = format!("{}{folder}/{name}.sha256", self.base_url);
+        Self::get_text(client, &url)?
+            .and_then(|text| text.split_whitespace().next().map(str::to_ascii_lowercase))
+            .with_context(|| {
+                format!("{folder}/{name} has no checksum in {SHA256SUMS} or {name}.sha256")
+            })
+    }
+
+    fn listing(url: &str) -> Result<Vec<String>> {
+        let resp = Self::client(Duration::from_secs(15))?
+            .get(url)
+            .send()
+            .with_context(|| format!("GET {url}"))?;
+        if !resp.status().is_success() {
+            bail!("GET {url}: HTTP {}", resp.status());
+        }
+        let body = resp.text().with_context(|| format!("read {url}"))?;
+        Ok(parse_directory_listing(&body))
+    }
+}
+
+impl UpdateSource for HttpMirrorSource {
+    fn describe(&self) -> String {
+        self.base_url.clone()
+    }
+
+    fn list_folders(&self) -> Result<Vec<String>> {
+        Ok(Self::listing(&self.base_url)?
+            .into_iter()
+            .filter_map(|entry| entry.strip_suffix('/').map(str::to_owned))
+            .collect())
+    }
+
+    fn available_binaries(&self, folder: &str, names: &[&str]) -> Result<Vec<String>> {
+        let entries = Self::listing(&format!("{}{folder}/", self.base_url))?;
+        Ok(names
+            .iter()
+            .filter(|n| entries.iter().any(|e| e == *n))
+            .map(|&n| n.to_owned())
+            .collect())
+    }
+
+    fn fetch(&self, folder: &str, binaries: &[String], staging: &Path) -> Result<PathBuf> {
+        let dir = staging.join(folder);
+        fs::create_dir_all(&dir).with_context(|| format!("create {}", dir.display()))?;
+        let client = Self::client(Duration::from_secs(600))?;
+        let sums = Self::get_text(&client, &format!("{}{folder}/{SHA256SUMS}", self.base_url))?;
+
+        for name in binaries {
+            let expected = self.expected_sha256(&client, folder, sums.as_deref(), name)?;
+            let url = format!("{}{folder}/{name}", self.base_url);
+            let mut resp = client
+                .get(&url)
+                .send()
+                .with_context(|| format!("GET {url}"))?;
+            if !resp.status().is_success() {
+                bail!("GET {url}: HTTP {}", resp.status());
+            }
+            let path = dir.join(name);
+            let mut file =
+                fs::File::create(&path).with_context(|| format!("create {}", path.display()))?;
+            resp.copy_to(&mut file)
+                .with_context(|| format!("download {url}"))?;
+            file.flush()
+                .with_context(|| format!("flush {}", path.display()))?;
+            drop(file);
+
+            let actual = sha256_file(&path)?;
+            if actual != expected {
+                let _ = fs::remove_file(&path);
+                bail!("{folder}/{name}: SHA-256 {actual} does not match the published {expected}");
+            }
+        }
+        Ok(dir)
+    }
+}
+
+/// Hash listed for `name` in a `sha256sum`-style file (`<hex>  [*]<path>`).
+/// Paths are matched on their last component.
+fn sha256sums_entry(sums: &str, name: &str) -> Option<String> {
+    sums.lines().find_map(|line| {
+        let (hash, file) = line.trim().split_once(char::is_whitespace)?;
+        let file = file.trim_start().trim_start_matches('*');
+        let base = file.rsplit('/').next()?;
+        (base == name && hash.len() == 64).then(|| hash.to_ascii_lowercase())
+    })
+}
+
+/// Lowercase hex SHA-256 of the file at `path`.
+fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = fs::File::open(path).with_context(|| format!("open {}", path.display()))?;
+    let mut ctx = Digest::new(&SHA256);
+    let mut buf = vec![0; 1 << 16];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .with_context(|| format!("read {}", path.display()))?;
+        if n == 0 {
+            break;
+        }
+        ctx.update(&buf[..n]);
+    }
+    Ok(hex::encode(ctx.finish()))
+}
+
+/// Extract entry names from an HTML directory listing.
+///
+/// Only relative links directly below the listed directory are returned
+/// (directories keep their trailing `/`); parent links, sort links and
+/// absolute URLs are dropped.
+pub fn parse_directory_listing(html: &str) -> Vec<String> {
+    let mut entries = Vec::new();
+    let mut rest = html;
+    while let Some(idx) = rest.find("href=") {
+        rest = &rest[idx + 5..];
+        let Some(quote) = rest.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            continue;
+        };
+        rest = &rest[1..];
+        let Some(end) = rest.find(quote) else {
+            break;
+        };
+        let href = percent_decode(&rest[..end]);
+        rest = &rest[end..];
+
+        let href = href.strip_prefix("./").unwrap_or(&href);
+        if href.is_empty()
+            || href.starts_with(['?', '#', '/', '.'])
+            || href.contains("://")
+            || href.trim_end_matches('/').contains('/')
+        {
+            continue;
+        }
+        if !entries.iter().any(|e| e == href) {
+            entries.push(href.to_owned());
+        }
+    }
+    entries
+}
+
+/// Minimal `%XX` decoding for hrefs in directory listings.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(b) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nginx_listing() {
+        let html = r#"<html><head><title>Index of /builds/</title></head><body>
+<h1>Index of /builds/</h1><hr><pre><a href="../">../</a>
+<a href="bitcoin-27.1/">bitcoin-27.1/</a>        01-Jun-2024 10:00       -
+<a href="bitcoin-28.0rc1/">bitcoin-28.0rc1/</a>     01-Sep-2024 10:00       -
+<a href="electrs-0.10.5/">electrs-0.10.5/</a>      01-Jun-2024 10:00       -
+<a href="README.txt">README.txt</a>          01-Jun-2024 10:00     120
+</pre><hr></body></html>"#;
+        assert_eq!(
+            parse_directory_listing(html),
+            vec![
+                "bitcoin-27.1/",
+                "bitcoin-28.0rc1/",
+                "electrs-0.10.5/",
+                "README.txt"
+            ]
+        );
+    }
+
+    #[test]
+    fn apache_and_python_listings() {
+        let apache = r#"<tr><td><a href="?C=N;O=D">Name</a></td></tr>
+<tr><td><a href="/pub/">Parent Directory</a></td></tr>
+<tr><td><a href="bitcoind">bitcoind</a></td></tr>
+<tr><td><a href='bitcoin-cli'>bitcoin-cli</a></td></tr>
+<tr><td><a href="https://example.com/">elsewhere</a></td></tr>"#;
+        assert_eq!(
+            parse_directory_listing(apache),
+            vec!["bitcoind", "bitcoin-cli"]
+        );
+
+        let python =
+            r#"<ul><li><a href="electrs-0.10.5%2Bbeta/">electrs-0.10.5+beta/</a></li></ul>"#;
+        assert_eq!(
+            parse_directory_listing(python),
+            vec!["electrs-0.10.5+beta/"]
+        );
+    }
+
+    #[test]
+    fn checksums_from_sha256sums() {
+        let a = "a".repeat(64);
+        let b = "B".repeat(64);
+        let sums = format!("{a}  bitcoind\n{b} *bin/bitcoin-cli\nshort  electrs\n");
+        assert_eq!(sha256sums_entry(&sums, "bitcoind"), Some(a));
+        assert_eq!(sha256sums_entry(&sums, "bitcoin-cli"), Some("b".repeat(64)));
+        assert_eq!(sha256sums_entry(&sums, "electrs"), None);
+        assert_eq!(sha256sums_entry(&sums, "bitcoin"), None);
+
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("bitcoind");
+        fs::write(&path, b"abc").unwrap();
+        assert_eq!(
+            sha256_file(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn local_source_lists_folders_and_binaries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let folder = tmp.path().join("bitcoin-27.1");
+        fs::create_dir(&folder).unwrap();
+        fs::write(folder.join("bitcoind"), b"").unwrap();
+        fs::write(tmp.path().join("notes.txt"), b"").unwrap();
+
+        let src = LocalDirSource {
+            root: tmp.path().to_path_buf(),
+        };
+        assert_eq!(src.list_folders().unwrap(), vec!["bitcoin-27.1"]);
+        assert_eq!(
+            src.available_binaries("bitcoin-27.1", &["bitcoind", "bitcoin-cli"])
+                .unwrap(),
+            vec!["bitcoind"]
+        );
+    }
+}
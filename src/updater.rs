@@ -1,12 +1,13 @@
 //! Binary update system.
 //!
-//! Scans `~/Downloads/bitcoin_builds/binaries/` for versioned folders,
-//! selects the highest semantic version, and copies the relevant binaries
-//! into the configured `Binaries/` directory on the SSD.
+//! Scans the configured update sources (see `update_source`) for versioned
+//! folders, selects the highest version across all of them, and copies the
+//! relevant binaries into the configured `Binaries/` directory on the SSD.
 //!
 //! Folder naming convention expected:
 //!   `bitcoin-27.0`          → contains bitcoind, bitcoin-cli, bitcoin-tx, bitcoin-util
 //!   `electrs-0.10.5`        → contains electrs
+//!   `bitcoin-28.0rc1`       → release candidate (only with pre-releases enabled)
 
 use std::{
     fs,
@@ -16,45 +17,196 @@ use std::{
 
 use anyhow::{Context, Result};
 
+use crate::{
+    binary_arch::{self, BinaryInfo, Compatibility},
+    config::UpdateSourceConfig,
+    update_source::{self, UpdateSource, STAGING_DIR},
+};
+
 // ── Version parsing ───────────────────────────────────────────────────────────
 
-/// Parse a semantic version string like "27.0.1" into a comparable tuple.
-fn parse_semver(s: &str) -> Option<(u64, u64, u64)> {
-    let parts: Vec<&str> = s.splitn(4, '.').collect();
-    let major = parts.first()?.parse().ok()?;
-    let minor = parts.get(1).and_then(|v| v.parse().ok()).unwrap_or(0);
-    let patch = parts.get(2).and_then(|v| v.parse().ok()).unwrap_or(0);
-    Some((major, minor, patch))
+/// Pre-release labels recognised after the numeric part of a version.
+///
+/// Anything else (e.g. `x86_64-linux-gnu`, `arm64-apple-darwin`) is treated as
+/// a platform suffix and ignored.
+const PRERELEASE_LABELS: &[&str] = &["alpha", "beta", "pre", "preview", "rc"];
+
+/// Pre-release tag such as `rc1`, `beta` or `beta.2`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PreRelease {
+    /// Lower-cased label (`alpha`, `beta`, `rc`, …).  Ordered alphabetically,
+    /// which happens to give the conventional alpha < beta < pre < rc order.
+    pub label: String,
+    /// Trailing number, `0` when absent (`beta` ≡ `beta0`).
+    pub number: u64,
 }
 
-/// Find the folder with the highest version for a given `prefix` (e.g. "bitcoin")
-/// inside `search_dir`.
+/// A release version as found in Bitcoin Core and electrs folder names.
 ///
-/// Returns the folder name (e.g. "bitcoin-27.0") or `None`.
-pub fn find_latest_version(search_dir: &Path, prefix: &str) -> Option<String> {
-    let mut best: Option<((u64, u64, u64), String)> = None;
-
-    let entries = fs::read_dir(search_dir).ok()?;
-    for entry in entries.flatten() {
-        let name = entry.file_name().to_string_lossy().into_owned();
-        // Must be a directory
-        if !entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
-            continue;
+/// Handles the naming schemes actually used upstream:
+///   * `27.0`, `0.21.0.1`   — two- to four-part numeric versions
+///   * `28.0rc1`            — Core release candidates (no separator)
+///   * `0.10.5-beta`        — hyphenated pre-release tags
+///   * `v0.10.5`            — leading `v` from git tags
+///   * `27.1+1a2b3c`        — build metadata (kept, but ignored for ordering)
+///
+/// Ordering follows semver precedence: numeric parts first (missing parts are
+/// zero), then a pre-release sorts *below* the corresponding final release.
+#[derive(Debug, Clone)]
+pub struct Version {
+    /// Numeric components, at most four.
+    pub parts: Vec<u64>,
+    pub pre: Option<PreRelease>,
+    pub build: Option<String>,
+}
+
+impl Version {
+    /// Parse a version string; returns `None` if it doesn't start with a number.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let s = s
+            .strip_prefix('v')
+            .or_else(|| s.strip_prefix('V'))
+            .unwrap_or(s);
+
+        let (s, build) = match s.split_once('+') {
+            Some((v, b)) if !b.is_empty() => (v, Some(b.to_owned())),
+            Some((v, _)) => (v, None),
+            None => (s, None),
+        };
+
+        // Numeric core: digits and dots up to the first other character.
+        let core_len = s
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(s.len());
+        let core = s[..core_len].trim_end_matches('.');
+        if core.is_empty() {
+            return None;
+        }
+        let parts = core
+            .split('.')
+            .map(str::parse)
+            .collect::<Result<Vec<u64>, _>>()
+            .ok()?;
+        if parts.len() > 4 {
+            return None;
         }
+
+        let rest = s[core_len..].trim_start_matches(['-', '.', '_']);
+        let pre = rest.split('-').next().and_then(parse_prerelease);
+
+        Some(Self { parts, pre, build })
+    }
+
+    /// `true` for release candidates, betas, etc.
+    pub const fn is_prerelease(&self) -> bool {
+        self.pre.is_some()
+    }
+
+    fn part(&self, i: usize) -> u64 {
+        self.parts.get(i).copied().unwrap_or(0)
+    }
+}
+
+/// Parse `rc1` / `beta` / `beta.2` / `RC-3` into a [`PreRelease`].
+fn parse_prerelease(segment: &str) -> Option<PreRelease> {
+    let label_len = segment
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(segment.len());
+    let label = segment[..label_len].to_ascii_lowercase();
+    if !PRERELEASE_LABELS.contains(&label.as_str()) {
+        return None;
+    }
+    // Only the leading digits: `rc1_arm64` is still `rc1`.
+    let rest = segment[label_len..].trim_start_matches(['.', '-', '_']);
+    let digits = &rest[..rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len())];
+    let number = if digits.is_empty() {
+        0
+    } else {
+        digits.parse().ok()?
+    };
+    Some(PreRelease { label, number })
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (0..4)
+            .map(|i| self.part(i).cmp(&other.part(i)))
+            .find(|o| o.is_ne())
+            .unwrap_or_else(|| match (&self.pre, &other.pre) {
+                (None, None) => std::cmp::Ordering::Equal,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (Some(a), Some(b)) => a.cmp(b),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Version {}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let core: Vec<String> = self.parts.iter().map(u64::to_string).collect();
+        write!(f, "{}", core.join("."))?;
+        // Core's style (`28.0rc1`, `28.0beta`) for every tag, so the output
+        // parses back to the same pre-release.
+        if let Some(pre) = &self.pre {
+            write!(f, "{}", pre.label)?;
+            if pre.number != 0 {
+                write!(f, "{}", pre.number)?;
+            }
+        }
+        if let Some(build) = &self.build {
+            write!(f, "+{build}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Pick the highest version among folder `names` of the form `<prefix>-<version>`.
+///
+/// Pre-release folders (`bitcoin-28.0rc1`, `electrs-0.10.5-beta`) are only
+/// considered when `include_prereleases` is set.
+pub fn select_latest<I>(
+    names: I,
+    prefix: &str,
+    include_prereleases: bool,
+) -> Option<(Version, String)>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut best: Option<(Version, String)> = None;
+    let wanted = format!("{prefix}-");
+
+    for name in names {
         // Must match `<prefix>-<version>`
-        let Some(version_str) = name.strip_prefix(&format!("{prefix}-")) else {
+        let Some(ver) = name.strip_prefix(&wanted).and_then(Version::parse) else {
             continue;
         };
-        if let Some(ver) = parse_semver(version_str) {
-            match &best {
-                None => best = Some((ver, name)),
-                Some((best_ver, _)) if ver > *best_ver => best = Some((ver, name)),
-                _ => {}
-            }
+        if ver.is_prerelease() && !include_prereleases {
+            continue;
+        }
+        match &best {
+            Some((best_ver, _)) if ver <= *best_ver => {}
+            _ => best = Some((ver, name)),
         }
     }
 
-    best.map(|(_, name)| name)
+    best
 }
 
 // ── Copy helpers ──────────────────────────────────────────────────────────────
@@ -102,85 +254,401 @@ pub fn copy_binaries(src_dir: &Path, dst_dir: &Path, names: &[&str]) -> Result<V
     Ok(copied)
 }
 
-// ── Update entry point ────────────────────────────────────────────────────────
+// ── Installed version detection ──────────────────────────────────────────────
 
-/// Outcome of an update attempt.
-#[derive(Debug)]
-pub enum UpdateResult {
-    /// At least one binary was updated.  Message lists what changed.
-    Updated(String),
-    /// `bitcoin_builds` not found but BitForge.app exists at the given path.
-    BitForgeFound(PathBuf),
-    /// `bitcoin_builds` not found and BitForge.app is absent.
-    BitForgeNotFound,
-    /// `bitcoin_builds` found but the `binaries/` sub-folder is missing.
-    BinariesSubfolderMissing,
-    /// `bitcoin_builds` and `binaries/` both found but no versioned folders inside.
-    NothingToUpdate,
+/// Extract a version from `--version` output.
+///
+/// Handles `Bitcoin Core daemon version v27.0.0`, `Bitcoin Core version
+/// v28.0.0rc1` and electrs' `v0.10.5` / `electrs 0.10.5`.
+pub fn parse_version_output(output: &str) -> Option<Version> {
+    let first_line = output.lines().next()?;
+    first_line
+        .split_whitespace()
+        .filter(|tok| {
+            tok.trim_start_matches(['v', 'V'])
+                .starts_with(|c: char| c.is_ascii_digit())
+        })
+        .find_map(Version::parse)
 }
 
-/// Run the full update check.
-pub fn run_update(binaries_dst: &Path) -> UpdateResult {
-    let downloads = home_dir().join("Downloads").join("bitcoin_builds");
+/// Run `<binary> --version` and parse the reported version.
+///
+/// Returns `None` if the binary is missing, fails to run, or prints
+/// something unrecognisable.
+pub fn installed_version(binary: &Path) -> Option<Version> {
+    if !binary.exists() {
+        return None;
+    }
+    let out = std::process::Command::new(binary)
+        .arg("--version")
+        .stdin(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    let text = if out.stdout.is_empty() {
+        String::from_utf8_lossy(&out.stderr).into_owned()
+    } else {
+        String::from_utf8_lossy(&out.stdout).into_owned()
+    };
+    parse_version_output(&text)
+}
 
-    if !downloads.exists() {
-        let bitforge = PathBuf::from("/Applications/BitForge.app");
-        return if bitforge.exists() {
-            UpdateResult::BitForgeFound(bitforge)
-        } else {
-            UpdateResult::BitForgeNotFound
-        };
+// ── Update plan ───────────────────────────────────────────────────────────────
+
+/// A group of binaries that is versioned and updated together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Component {
+    Bitcoin,
+    Electrs,
+}
+
+impl Component {
+    pub const ALL: [Self; 2] = [Self::Bitcoin, Self::Electrs];
+
+    /// Folder prefix used by versioned build folders (`bitcoin-27.0`).
+    pub const fn prefix(self) -> &'static str {
+        match self {
+            Self::Bitcoin => "bitcoin",
+            Self::Electrs => "electrs",
+        }
     }
 
-    let binaries_src = downloads.join("binaries");
-    if !binaries_src.exists() {
-        return UpdateResult::BinariesSubfolderMissing;
+    pub const fn display_name(self) -> &'static str {
+        match self {
+            Self::Bitcoin => "Bitcoin",
+            Self::Electrs => "Electrs",
+        }
     }
 
-    let btc_folder = find_latest_version(&binaries_src, "bitcoin");
-    let etr_folder = find_latest_version(&binaries_src, "electrs");
+    /// Binaries copied for this component.
+    pub const fn binaries(self) -> &'static [&'static str] {
+        match self {
+            Self::Bitcoin => &["bitcoind", "bitcoin-cli", "bitcoin-tx", "bitcoin-util"],
+            Self::Electrs => &["electrs"],
+        }
+    }
 
-    if btc_folder.is_none() && etr_folder.is_none() {
-        return UpdateResult::NothingToUpdate;
+    /// The binary whose `--version` represents the installed component.
+    pub const fn primary_binary(self) -> &'static str {
+        match self {
+            Self::Bitcoin => "bitcoind",
+            Self::Electrs => "electrs",
+        }
+    }
+}
+
+/// What applying the plan would do for one component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanAction {
+    /// Nothing installed yet; the candidate will be copied in.
+    Install,
+    /// A binary is installed but its version could not be read (e.g. built
+    /// for another platform); the candidate replaces it.
+    Replace,
+    /// Candidate is newer than the installed binary.
+    Upgrade,
+    /// Installed binary is newer than the candidate — left untouched.
+    SkipNewerInstalled,
+    /// Installed binary already matches the candidate.
+    UpToDate,
+    /// No candidate folder was found for this component.
+    NoCandidate,
+    /// Candidate is built for another CPU architecture or OS.
+    Incompatible,
+}
+
+impl PlanAction {
+    /// `true` if applying the plan replaces binaries for this component.
+    pub const fn changes(self) -> bool {
+        matches!(self, Self::Install | Self::Replace | Self::Upgrade)
     }
+}
 
-    let mut messages: Vec<String> = Vec::new();
+/// A candidate build folder selected for a component.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub folder: String,
+    pub version: Version,
+    /// The source offering `folder`.
+    pub source: UpdateSourceConfig,
+    /// Binaries from [`Component::binaries`] actually present in `folder`.
+    pub binaries: Vec<String>,
+    /// Header of the primary binary, when it could be inspected without
+    /// fetching (local sources only).
+    pub binary_info: Option<BinaryInfo>,
+}
+
+/// Plan entry for a single component.
+#[derive(Debug, Clone)]
+pub struct ComponentPlan {
+    pub component: Component,
+    pub installed: Option<Version>,
+    /// The primary binary exists, whether or not its version was readable.
+    pub present: bool,
+    pub candidate: Option<Candidate>,
+    pub action: PlanAction,
+}
+
+/// Dry-run result: what is installed, what is available, what would change.
+#[derive(Debug, Clone)]
+pub struct UpdatePlan {
+    pub components: Vec<ComponentPlan>,
+    /// Sources that could not be read, with the reason.
+    pub source_errors: Vec<String>,
+}
 
-    if let Some(folder) = btc_folder {
-        let src = binaries_src.join(&folder);
-        match copy_binaries(
-            &src,
-            binaries_dst,
-            &["bitcoind", "bitcoin-cli", "bitcoin-tx", "bitcoin-util"],
-        ) {
-            Ok(copied) if !copied.is_empty() => {
-                messages.push(format!("Bitcoin ({folder}): {}", copied.join(", ")));
+impl UpdatePlan {
+    /// `true` if applying this plan replaces any binaries.
+    pub fn has_changes(&self) -> bool {
+        self.components.iter().any(|c| c.action.changes())
+    }
+
+    /// `true` if applying this plan replaces binaries of `component`.
+    pub fn changes(&self, component: Component) -> bool {
+        self.components
+            .iter()
+            .any(|c| c.component == component && c.action.changes())
+    }
+
+    /// Human-readable description for the confirmation dialog.
+    pub fn summary(&self) -> String {
+        let mut lines = Vec::new();
+        for c in &self.components {
+            let installed = match (&c.installed, c.present) {
+                (Some(version), _) => version.to_string(),
+                (None, true) => "installed, version unknown".to_owned(),
+                (None, false) => "not installed".to_owned(),
+            };
+            let candidate = c
+                .candidate
+                .as_ref()
+                .map_or_else(|| "none".to_owned(), |cand| cand.version.to_string());
+            let verdict = match c.action {
+                PlanAction::Install => "install",
+                PlanAction::Replace => "replace",
+                PlanAction::Upgrade => "upgrade",
+                PlanAction::SkipNewerInstalled => "skip (installed is newer)",
+                PlanAction::UpToDate => "up to date",
+                PlanAction::NoCandidate => "no candidate found",
+                PlanAction::Incompatible => "skip (wrong platform)",
+            };
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.push(format!(
+                "{}: {installed} → {candidate} — {verdict}",
+                c.component.display_name()
+            ));
+            if let (true, Some(cand)) = (c.action.changes(), &c.candidate) {
+                lines.push(format!(
+                    "  {}/{}: {}",
+                    cand.source,
+                    cand.folder,
+                    cand.binaries.join(", ")
+                ));
             }
-            Ok(_) => {}
-            Err(e) => messages.push(format!("Bitcoin update error: {e}")),
+            if let Some(info) = c.candidate.as_ref().and_then(|c| c.binary_info.as_ref()) {
+                match info.compatibility() {
+                    Compatibility::Native => {}
+                    Compatibility::Rosetta => {
+                        lines.push(format!("  {info} — runs under Rosetta"));
+                    }
+                    Compatibility::Incompatible(why) => lines.push(format!("  {why}")),
+                }
+            }
+        }
+        if !self.source_errors.is_empty() {
+            lines.push(String::new());
+            lines.push("Unavailable sources:".to_owned());
+            lines.extend(self.source_errors.iter().map(|e| format!("  {e}")));
         }
+        lines.join("\n")
+    }
+}
+
+/// Decide the action for one component from its installed and candidate versions.
+fn decide(installed: Option<&Version>, candidate: Option<&Version>) -> PlanAction {
+    match (installed, candidate) {
+        (_, None) => PlanAction::NoCandidate,
+        (None, Some(_)) => PlanAction::Install,
+        (Some(i), Some(c)) if c > i => PlanAction::Upgrade,
+        (Some(i), Some(c)) if c < i => PlanAction::SkipNewerInstalled,
+        (Some(_), Some(_)) => PlanAction::UpToDate,
     }
+}
 
-    if let Some(folder) = etr_folder {
-        let src = binaries_src.join(&folder);
-        match copy_binaries(&src, binaries_dst, &["electrs"]) {
-            Ok(copied) if !copied.is_empty() => {
-                messages.push(format!("Electrs ({folder}): {}", copied.join(", ")));
+/// Build the dry-run plan for `sources` against what is installed in
+/// `binaries_dst`.  Nothing is copied.
+///
+/// When several sources offer the same version, the earlier source wins.
+pub fn build_plan(
+    sources: &[UpdateSourceConfig],
+    binaries_dst: &Path,
+    include_prereleases: bool,
+) -> UpdatePlan {
+    let mut source_errors = Vec::new();
+    let listed: Vec<(&UpdateSourceConfig, Box<dyn UpdateSource>, Vec<String>)> = sources
+        .iter()
+        .filter_map(|cfg| {
+            let source = update_source::from_config(cfg);
+            match source.list_folders() {
+                Ok(folders) => Some((cfg, source, folders)),
+                Err(e) => {
+                    source_errors.push(format!("{}: {e:#}", source.describe()));
+                    None
+                }
             }
-            Ok(_) => {}
-            Err(e) => messages.push(format!("Electrs update error: {e}")),
-        }
+        })
+        .collect();
+
+    let components = Component::ALL
+        .iter()
+        .map(|&component| {
+            let binary = binaries_dst.join(component.primary_binary());
+            let present = binary.exists();
+            let installed = installed_version(&binary);
+
+            // Best folder per source, highest version first (stable sort keeps
+            // source priority for equal versions).
+            let mut offers: Vec<_> = listed
+                .iter()
+                .filter_map(|(cfg, source, folders)| {
+                    select_latest(
+                        folders.iter().cloned(),
+                        component.prefix(),
+                        include_prereleases,
+                    )
+                    .map(|(version, folder)| (version, folder, *cfg, source))
+                })
+                .collect();
+            offers.sort_by(|a, b| b.0.cmp(&a.0));
+
+            let candidate = offers
+                .into_iter()
+                .find_map(|(version, folder, cfg, source)| {
+                    let binaries = source
+                        .available_binaries(&folder, component.binaries())
+                        .unwrap_or_default();
+                    let binary_info = source.local_path(&folder).and_then(|dir| {
+                        binary_arch::inspect(&dir.join(component.primary_binary())).ok()
+                    });
+                    (!binaries.is_empty()).then(|| Candidate {
+                        folder,
+                        version,
+                        source: cfg.clone(),
+                        binaries,
+                        binary_info,
+                    })
+                });
+            let mut action = decide(installed.as_ref(), candidate.as_ref().map(|c| &c.version));
+            if action == PlanAction::Install && present {
+                action = PlanAction::Replace;
+            }
+            let incompatible = candidate
+                .as_ref()
+                .and_then(|c| c.binary_info.as_ref())
+                .is_some_and(|info| matches!(info.compatibility(), Compatibility::Incompatible(_)));
+            if action.changes() && incompatible {
+                action = PlanAction::Incompatible;
+            }
+            ComponentPlan {
+                component,
+                installed,
+                present,
+                candidate,
+                action,
+            }
+        })
+        .collect();
+
+    UpdatePlan {
+        components,
+        source_errors,
     }
+}
 
-    if messages.is_empty() {
-        UpdateResult::NothingToUpdate
+// ── Update entry point ────────────────────────────────────────────────────────
+
+/// Outcome of an update check or an applied plan.
+#[derive(Debug, Clone)]
+pub enum UpdateResult {
+    /// Candidates were found; the plan must be confirmed before anything changes.
+    Planned(UpdatePlan),
+    /// No source could be read, but BitForge.app exists at the given path.
+    BitForgeFound(PathBuf),
+    /// No source could be read and BitForge.app is absent.  Lists each
+    /// source with the reason it was unavailable.
+    SourcesUnavailable(Vec<String>),
+    /// Sources were readable but contained no usable versioned folders.
+    NothingToUpdate,
+}
+
+/// Locate update candidates and build a dry-run plan.  Nothing is copied.
+///
+/// Pre-release builds are skipped unless `include_prereleases` is set.
+pub fn plan_update(
+    sources: &[UpdateSourceConfig],
+    bitforge_app: &Path,
+    binaries_dst: &Path,
+    include_prereleases: bool,
+) -> UpdateResult {
+    let plan = build_plan(sources, binaries_dst, include_prereleases);
+
+    if plan.components.iter().any(|c| c.candidate.is_some()) {
+        return UpdateResult::Planned(plan);
+    }
+    if plan.source_errors.len() < sources.len() {
+        return UpdateResult::NothingToUpdate;
+    }
+    if bitforge_app.exists() {
+        UpdateResult::BitForgeFound(bitforge_app.to_path_buf())
     } else {
-        UpdateResult::Updated(messages.join("\n"))
+        UpdateResult::SourcesUnavailable(plan.source_errors)
     }
 }
 
-fn home_dir() -> PathBuf {
-    std::env::var("HOME").map_or_else(|_| PathBuf::from("/tmp"), PathBuf::from)
+/// Apply a confirmed plan, copying only components whose action changes them.
+///
+/// Remote builds are downloaded into a staging folder inside `binaries_dst`
+/// first.  The caller is responsible for stopping any process whose binary
+/// is replaced.
+///
+/// Returns one entry per component attempted: what was copied
+/// (`"bitcoin-27.1: bitcoind, bitcoin-cli"`), or why it failed.  Components
+/// with nothing to copy are left out.
+pub fn apply_plan(plan: &UpdatePlan, binaries_dst: &Path) -> Vec<(Component, Result<String>)> {
+    let staging = binaries_dst.join(STAGING_DIR);
+    let mut results = Vec::new();
+
+    for c in plan.components.iter().filter(|c| c.action.changes()) {
+        let Some(cand) = &c.candidate else {
+            continue;
+        };
+        let source = update_source::from_config(&cand.source);
+        let names: Vec<&str> = cand.binaries.iter().map(String::as_str).collect();
+        let result = source
+            .fetch(&cand.folder, &cand.binaries, &staging)
+            .and_then(|dir| {
+                // Remote builds can only be inspected once fetched.  A binary
+                // that cannot be read as an executable is not installed.
+                for name in &names {
+                    let info = binary_arch::inspect(&dir.join(name))?;
+                    if let Compatibility::Incompatible(why) = info.compatibility() {
+                        anyhow::bail!("{name} {why}");
+                    }
+                }
+                copy_binaries(&dir, binaries_dst, &names)
+            });
+        match result {
+            Ok(copied) if copied.is_empty() => {}
+            Ok(copied) => results.push((
+                c.component,
+                Ok(format!("{}: {}", cand.folder, copied.join(", "))),
+            )),
+            Err(e) => results.push((c.component, Err(e))),
+        }
+    }
+    let _ = fs::remove_dir_all(&staging);
+    results
 }
 
 // ── Tests ─────────────────────────────────────────────────────────────────────
@@ -189,12 +657,216 @@ fn home_dir() -> PathBuf {
 mod tests {
     use super::*;
 
+    fn v(s: &str) -> Version {
+        Version::parse(s).unwrap()
+    }
+
+    #[test]
+    fn version_parsing() {
+        assert_eq!(v("27.0").parts, vec![27, 0]);
+        assert_eq!(v("0.10.5").parts, vec![0, 10, 5]);
+        assert_eq!(v("1").parts, vec![1]);
+        assert_eq!(v("0.21.0.1").parts, vec![0, 21, 0, 1]);
+        assert_eq!(v("v0.10.5").parts, vec![0, 10, 5]);
+        assert!(Version::parse("").is_none());
+        assert!(Version::parse("rc1").is_none());
+        assert!(Version::parse("1.2.3.4.5").is_none());
+    }
+
+    #[test]
+    fn prerelease_and_build_parsing() {
+        let rc = v("28.0rc1");
+        assert_eq!(rc.parts, vec![28, 0]);
+        assert_eq!(
+            rc.pre,
+            Some(PreRelease {
+                label: "rc".into(),
+                number: 1
+            })
+        );
+
+        let beta = v("0.10.5-beta");
+        assert_eq!(beta.pre.as_ref().map(|p| p.label.as_str()), Some("beta"));
+
+        let built = v("27.1+1a2b3c");
+        assert!(!built.is_prerelease());
+        assert_eq!(built.build.as_deref(), Some("1a2b3c"));
+        assert_eq!(built.to_string(), "27.1+1a2b3c");
+
+        // Platform triples from release tarballs are not pre-release tags.
+        assert!(!v("28.0-x86_64-linux-gnu").is_prerelease());
+        assert!(!v("28.0-arm64-apple-darwin").is_prerelease());
+        assert!(v("28.0rc2-arm64-apple-darwin").is_prerelease());
+        assert_eq!(v("28.0rc1_arm64").to_string(), "28.0rc1");
+        assert_eq!(v("28.0rc1.x86_64").to_string(), "28.0rc1");
+        assert_eq!(v("28.0beta2_x86_64").to_string(), "28.0beta2");
+
+        // Display round-trips through parse for every pre-release form.
+        for s in ["28.0rc1", "28.0beta2", "0.10.5alpha", "27.0pre3+abc"] {
+            assert_eq!(v(s).to_string(), s);
+        }
+        for s in ["0.10.5-beta", "0.10.5-beta.2", "28.0-RC-3", "28.0rc0"] {
+            let parsed = v(s);
+            let again = v(&parsed.to_string());
+            assert_eq!((again.parts, again.pre), (parsed.parts, parsed.pre), "{s}");
+        }
+    }
+
+    #[test]
+    fn version_ordering() {
+        assert!(v("28.0rc1") < v("28.0"));
+        assert!(v("28.0rc1") < v("28.0rc2"));
+        assert!(v("28.0") < v("28.1rc1"));
+        assert!(v("27.2") < v("28.0rc1"));
+        assert!(v("0.10.5-beta") < v("0.10.5"));
+        assert!(v("0.10.5-alpha") < v("0.10.5-beta"));
+        assert!(v("0.10.4") < v("0.10.5-beta"));
+        assert!(v("0.21.0") < v("0.21.0.1"));
+        assert!(v("0.21.0.1") < v("0.21.1"));
+        assert_eq!(v("27.0"), v("27.0.0"));
+        assert_eq!(v("27.1+abc"), v("27.1"));
+    }
+
+    #[test]
+    fn version_output_parsing() {
+        let core = "Bitcoin Core daemon version v27.0.0\nCopyright (C) 2009-2024";
+        assert_eq!(parse_version_output(core), Some(v("27.0.0")));
+        let rc = parse_version_output("Bitcoin Core version v28.0.0rc1").unwrap();
+        assert!(rc.is_prerelease());
+        assert_eq!(parse_version_output("v0.10.5\n"), Some(v("0.10.5")));
+        assert_eq!(parse_version_output("electrs 0.10.5"), Some(v("0.10.5")));
+        assert_eq!(parse_version_output("command not found"), None);
+    }
+
+    #[test]
+    fn plan_actions() {
+        assert_eq!(decide(None, Some(&v("27.0"))), PlanAction::Install);
+        assert_eq!(
+            decide(Some(&v("27.0")), Some(&v("27.1"))),
+            PlanAction::Upgrade
+        );
+        assert_eq!(
+            decide(Some(&v("27.1")), Some(&v("27.1"))),
+            PlanAction::UpToDate
+        );
+        assert_eq!(
+            decide(Some(&v("28.0")), Some(&v("28.0rc2"))),
+            PlanAction::SkipNewerInstalled
+        );
+        assert_eq!(decide(Some(&v("27.0")), None), PlanAction::NoCandidate);
+    }
+
+    /// The start of this test binary: an executable header for the host.
+    fn native_header() -> Vec<u8> {
+        let mut header = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+        header.truncate(4096);
+        header
+    }
+
+    #[test]
+    fn plan_and_apply_only_copy_changes() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        let btc = src.path().join("bitcoin-27.1");
+        std::fs::create_dir(&btc).unwrap();
+        std::fs::write(btc.join("bitcoind"), native_header()).unwrap();
+        std::fs::write(btc.join("bitcoin-cli"), b"truncated").unwrap();
+
+        let sources = [UpdateSourceConfig::LocalDir {
+            path: src.path().to_path_buf(),
+        }];
+        let plan = build_plan(&sources, dst.path(), false);
+        assert!(plan.changes(Component::Bitcoin));
+        assert!(!plan.changes(Component::Electrs));
+        let bitcoin = &plan.components[0];
+        assert_eq!(bitcoin.action, PlanAction::Install);
+        assert_eq!(
+            bitcoin.candidate.as_ref().unwrap().binaries,
+            vec!["bitcoind".to_owned(), "bitcoin-cli".to_owned()]
+        );
+
+        // Every binary is inspected, not only the primary one.
+        let results = apply_plan(&plan, dst.path());
+        assert_eq!(results.len(), 1);
+        let err = format!("{:#}", results[0].1.as_ref().unwrap_err());
+        assert!(err.contains("bitcoin-cli"), "{err}");
+        assert!(!dst.path().join("bitcoind").exists());
+
+        std::fs::write(btc.join("bitcoin-cli"), native_header()).unwrap();
+        let results = apply_plan(&plan, dst.path());
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, Component::Bitcoin);
+        assert_eq!(
+            results[0].1.as_ref().unwrap(),
+            "bitcoin-27.1: bitcoind, bitcoin-cli"
+        );
+        assert_eq!(
+            std::fs::read(dst.path().join("bitcoind")).unwrap(),
+            native_header()
+        );
+        assert!(!dst.path().join("electrs").exists());
+
+        // The copied stand-in has no readable `--version`.
+        let replan = build_plan(&sources, dst.path(), false);
+        assert_eq!(replan.components[0].action, PlanAction::Replace);
+        assert!(replan
+            .summary()
+            .contains("installed, version unknown → 27.1 — replace"));
+    }
+
+    #[test]
+    fn plan_prefers_highest_version_across_sources() {
+        let downloads = tempfile::tempdir().unwrap();
+        let ssd = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        for (root, folder) in [
+            (downloads.path(), "bitcoin-27.0"),
+            (ssd.path(), "bitcoin-27.1"),
+            (downloads.path(), "electrs-0.10.5"),
+            (ssd.path(), "electrs-0.10.5"),
+        ] {
+            let dir = root.join(folder);
+            std::fs::create_dir(&dir).unwrap();
+            std::fs::write(dir.join("bitcoind"), b"").unwrap();
+            std::fs::write(dir.join("electrs"), b"").unwrap();
+        }
+        let sources = [
+            UpdateSourceConfig::LocalDir {
+                path: downloads.path().to_path_buf(),
+            },
+            UpdateSourceConfig::LocalDir {
+                path: ssd.path().to_path_buf(),
+            },
+            UpdateSourceConfig::LocalDir {
+                path: downloads.path().join("missing"),
+            },
+        ];
+        let plan = build_plan(&sources, dst.path(), false);
+        let btc = plan.components[0].candidate.as_ref().unwrap();
+        assert_eq!(btc.folder, "bitcoin-27.1");
+        assert_eq!(btc.source, sources[1]);
+        // Equal versions: the first source wins.
+        let els = plan.components[1].candidate.as_ref().unwrap();
+        assert_eq!(els.source, sources[0]);
+        assert_eq!(plan.source_errors.len(), 1);
+    }
+
     #[test]
-    fn semver_parsing() {
-        assert_eq!(parse_semver("27.0"), Some((27, 0, 0)));
-        assert_eq!(parse_semver("0.10.5"), Some((0, 10, 5)));
-        assert_eq!(parse_semver("1"), Some((1, 0, 0)));
-        assert_eq!(parse_semver(""), None);
+    fn unavailable_sources_are_reported() {
+        let dst = tempfile::tempdir().unwrap();
+        let sources = [UpdateSourceConfig::LocalDir {
+            path: dst.path().join("nope"),
+        }];
+        let missing_app = dst.path().join("BitForge.app");
+        assert!(matches!(
+            plan_update(&sources, &missing_app, dst.path(), false),
+            UpdateResult::SourcesUnavailable(errors) if errors.len() == 1
+        ));
+        std::fs::create_dir(&missing_app).unwrap();
+        assert!(matches!(
+            plan_update(&sources, &missing_app, dst.path(), false),
+            UpdateResult::BitForgeFound(_)
+        ));
     }
 
     #[test]
@@ -204,7 +876,46 @@ mod tests {
         std::fs::create_dir(dir.join("bitcoin-26.0")).unwrap();
         std::fs::create_dir(dir.join("bitcoin-27.1")).unwrap();
         std::fs::create_dir(dir.join("bitcoin-27.0")).unwrap();
-        let latest = find_latest_version(dir, "bitcoin");
-        assert_eq!(latest.as_deref(), Some("bitcoin-27.1"));
+        let source = update_source::LocalDirSource {
+            root: dir.to_path_buf(),
+        };
+        let latest = select_latest(source.list_folders().unwrap(), "bitcoin", false);
+        assert_eq!(
+            latest.map(|(_, name)| name).as_deref(),
+            Some("bitcoin-27.1")
+        );
+    }
+
+    #[test]
+    fn prerelease_selection_respects_setting() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        for name in [
+            "bitcoin-27.1",
+            "bitcoin-28.0rc1",
+            "bitcoin-28.0rc2",
+            "electrs-0.10.4",
+            "electrs-0.10.5-beta",
+        ] {
+            std::fs::create_dir(dir.join(name)).unwrap();
+        }
+        let source = update_source::LocalDirSource {
+            root: dir.to_path_buf(),
+        };
+        let latest = |prefix, include_prereleases| {
+            select_latest(source.list_folders().unwrap(), prefix, include_prereleases)
+                .map(|(_, name)| name)
+        };
+        assert_eq!(latest("bitcoin", false).as_deref(), Some("bitcoin-27.1"));
+        assert_eq!(latest("bitcoin", true).as_deref(), Some("bitcoin-28.0rc2"));
+        assert_eq!(latest("electrs", false).as_deref(), Some("electrs-0.10.4"));
+        assert_eq!(
+            latest("electrs", true).as_deref(),
+            Some("electrs-0.10.5-beta")
+        );
+
+        // Once the final release lands it wins over its candidates.
+        std::fs::create_dir(dir.join("bitcoin-28.0")).unwrap();
+        assert_eq!(latest("bitcoin", true).as_deref(), Some("bitcoin-28.0"));
     }
 }
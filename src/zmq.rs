@@ -0,0 +1,461 @@
+//! Minimal ZMQ subscriber for bitcoind's `zmqpub*` notifications.
+//!
+//! Speaks just enough ZMTP 3.0 (NULL mechanism, SUB socket) over a plain TCP
+//! socket to receive `hashblock`, `rawtx` and `sequence` messages; bitcoind
+//! only ever publishes on `tcp://` endpoints.  [`events`] wraps a subscriber
+//! in a reconnecting stream for the UI's subscription.
+//!
+//! The endpoints are written to bitcoin.conf as a managed block, which
+//! [`write_conf`] replaces on every launch.
+
+use std::{path::Path, time::Duration};
+
+use anyhow::{bail, Context, Result};
+use iced::futures::{stream, Stream};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+    time::timeout,
+};
+
+use crate::config::ZmqConfig;
+
+/// Connect and handshake timeout.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Wait between reconnection attempts.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Failed attempts before a never-connected endpoint is reported; bitcoind
+/// opens its ZMQ sockets a few seconds after it starts.
+const FAILURES_BEFORE_REPORT: u32 = 3;
+
+/// Largest frame accepted (a raw transaction is under 4 MB).
+const MAX_FRAME: u64 = 8 * 1024 * 1024;
+
+const FLAG_MORE: u8 = 0x01;
+const FLAG_LONG: u8 = 0x02;
+const FLAG_COMMAND: u8 = 0x04;
+
+const CONF_BEGIN: &str = "# BEGIN zmq (managed by Bitcoin Node Manager)";
+const CONF_END: &str = "# END zmq";
+
+/// A bitcoind notification topic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Topic {
+    HashBlock,
+    RawTx,
+    Sequence,
+}
+
+impl Topic {
+    /// Topic name on the wire and suffix of the `zmqpub*` option.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::HashBlock => "hashblock",
+            Self::RawTx => "rawtx",
+            Self::Sequence => "sequence",
+        }
+    }
+}
+
+/// Configured `(topic, endpoint)` pairs, empty when ZMQ is off.
+fn topics(cfg: &ZmqConfig) -> Vec<(Topic, &str)> {
+    if !cfg.enabled {
+        return Vec::new();
+    }
+    [
+        (Topic::HashBlock, cfg.hashblock.trim()),
+        (Topic::RawTx, cfg.rawtx.trim()),
+        (Topic::Sequence, cfg.sequence.trim()),
+    ]
+    .into_iter()
+    .filter(|(_, endpoint)| !endpoint.is_empty())
+    .collect()
+}
+
+/// Endpoints to subscribe to, each with its topics.
+pub fn subscriptions(cfg: &ZmqConfig) -> Vec<(String, Vec<Topic>)> {
+    let mut out: Vec<(String, Vec<Topic>)> = Vec::new();
+    for (topic, endpoint) in topics(cfg) {
+        match out.iter_mut().find(|(e, _)| e == endpoint) {
+            Some((_, list)) => list.push(topic),
+            None => out.push((endpoint.to_owned(), vec![topic])),
+        }
+    }
+    out
+}
+
+/// `conf` with the managed ZMQ block replaced by the options for `cfg`.
+/// The block goes before the first `[section]` so it applies to every
+/// network.
+pub fn conf_with_endpoints(conf: &str, cfg: &ZmqConfig) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut in_block = false;
+    for line in conf.lines() {
+        match line.trim() {
+            CONF_BEGIN => in_block = true,
+            CONF_END if in_block => in_block = false,
+            _ if in_block => {}
+            _ => lines.push(line.to_owned()),
+        }
+    }
+    let options = topics(cfg);
+    if !options.is_empty() {
+        let at = lines
+            .iter()
+            .position(|l| l.trim_start().starts_with('['))
+            .unwrap_or(lines.len());
+        let mut block = vec![CONF_BEGIN.to_owned()];
+        block.extend(
+            options
+                .iter()
+                .map(|(topic, endpoint)| format!("zmqpub{}={endpoint}", topic.name())),
+        );
+        block.push(CONF_END.to_owned());
+        lines.splice(at..at, block);
+    }
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+/// Bring the managed ZMQ block in `<data_dir>/bitcoin.conf` up to date.
+pub fn write_conf(data_dir: &Path, cfg: &ZmqConfig) -> Result<()> {
+    let path = data_dir.join("bitcoin.conf");
+    let current = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("read {}", path.display())),
+    };
+    let updated = conf_with_endpoints(&current, cfg);
+    if updated != current {
+        std::fs::write(&path, updated).with_context(|| format!("write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// A notification, or a change in the connection to an endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    Connected(String),
+    Disconnected {
+        endpoint: String,
+        error: String,
+    },
+    BlockConnected(String),
+    BlockDisconnected(String),
+    /// Txid entering the mempool.
+    TxAdded(String),
+    /// Txid leaving the mempool other than by being mined.
+    TxRemoved(String),
+    /// Size in bytes of a transaction seen on `rawtx`.
+    RawTx(usize),
+}
+
+/// Decode a `[topic, body, sequence]` message.  Hashes arrive in display
+/// (RPC) byte order.
+pub fn parse(parts: &[Vec<u8>]) -> Option<Event> {
+    let (topic, body) = (parts.first()?, parts.get(1)?);
+    match topic.as_slice() {
+        b"hashblock" if body.len() == 32 => Some(Event::BlockConnected(hex::encode(body))),
+        b"rawtx" => Some(Event::RawTx(body.len())),
+        b"sequence" if body.len() >= 33 => {
+            let hash = hex::encode(&body[..32]);
+            match body[32] {
+                b'C' => Some(Event::BlockConnected(hash)),
+                b'D' => Some(Event::BlockDisconnected(hash)),
+                b'A' => Some(Event::TxAdded(hash)),
+                b'R' => Some(Event::TxRemoved(hash)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// A connected SUB socket.
+pub struct Subscriber {
+    stream: BufReader<TcpStream>,
+}
+
+impl Subscriber {
+    /// Connect to a `tcp://host:port` endpoint and subscribe to `topics`.
+    pub async fn connect(endpoint: &str, topics: &[Topic]) -> Result<Self> {
+        let addr = endpoint
+            .strip_prefix("tcp://")
+            .with_context(|| format!("unsupported ZMQ endpoint {endpoint} (tcp:// only)"))?;
+        let stream = timeout(CONNECT_TIMEOUT, TcpStream::connect(addr))
+            .await
+            .with_context(|| format!("connect to {endpoint}: timed out"))?
+            .with_context(|| format!("connect to {endpoint}"))?;
+        let mut sub = Self {
+            stream: BufReader::new(stream),
+        };
+        timeout(CONNECT_TIMEOUT, sub.handshake(topics))
+            .await
+            .with_context(|| format!("ZMQ handshake with {endpoint}: timed out"))??;
+        Ok(sub)
+    }
+
+    async fn handshake(&mut self, topics: &[Topic]) -> Result<()> {
+        // Signature, version 3.0, NULL mechanism, as-client, filler.
+        let mut greeting = [0u8; 64];
+        greeting[0] = 0xff;
+        greeting[9] = 0x7f;
+        greeting[10] = 3;
+        greeting[12..16].copy_from_slice(b"NULL");
+        self.stream.write_all(&greeting).await?;
+
+        let mut peer = [0u8; 64];
+        self.stream.read_exact(&mut peer).await?;
+        if peer[0] != 0xff || peer[9] & 0x01 == 0 {
+            bail!("not a ZMQ endpoint");
+        }
+        if peer[10] < 3 {
+            bail!("peer speaks ZMTP {}.x; 3.0 or later is needed", peer[10]);
+        }
+        if &peer[12..17] != b"NULL\0" {
+            bail!("unsupported ZMQ security mechanism");
+        }
+
+        self.write_frame(FLAG_COMMAND, &ready_command()).await?;
+        let (flags, body) = self.read_frame().await?;
+        let name_len = usize::from(body.first().copied().unwrap_or(0));
+        let name = body.get(1..=name_len).unwrap_or_default();
+        if flags & FLAG_COMMAND == 0 || name != b"READY" {
+            let reason = String::from_utf8_lossy(body.get(name_len + 2..).unwrap_or_default());
+            bail!("ZMQ handshake refused: {reason}");
+        }
+
+        // ZMTP 3.0 subscriptions are messages starting with 0x01.
+        for topic in topics {
+            let mut sub = vec![0x01];
+            sub.extend_from_slice(topic.name().as_bytes());
+            self.write_frame(0, &sub).await?;
+        }
+        self.stream.flush().await?;
+        Ok(())
+    }
+
+    async fn write_frame(&mut self, flags: u8, body: &[u8]) -> Result<()> {
+        if let Ok(len) = u8::try_from(body.len()) {
+            self.stream.write_all(&[flags, len]).await?;
+        } else {
+            self.stream.write_all(&[flags | FLAG_LONG]).await?;
+            self.stream
+                .write_all(&(body.len() as u64).to_be_bytes())
+                .await?;
+        }
+        self.stream.write_all(body).await?;
+        Ok(())
+    }
+
+    async fn read_frame(&mut self) -> Result<(u8, Vec<u8>)> {
+        let flags = self.stream.read_u8().await.context("read ZMQ frame")?;
+        let size = if flags & FLAG_LONG == 0 {
+            u64::from(self.stream.read_u8().await?)
+        } else {
+            self.stream.read_u64().await?
+        };
+        if size > MAX_FRAME {
+            bail!("ZMQ frame of {size} bytes is too large");
+        }
+        let mut body = vec![0; usize::try_from(size)?];
+        self.stream.read_exact(&mut body).await?;
+        Ok((flags, body))
+    }
+
+    /// Next multipart message, skipping commands.
+    pub async fn recv(&mut self) -> Result<Vec<Vec<u8>>> {
+        let mut parts = Vec::new();
+        loop {
+            let (flags, body) = self.read_frame().await?;
+            if flags & FLAG_COMMAND != 0 {
+                continue;
+            }
+            parts.push(body);
+            if flags & FLAG_MORE == 0 {
+                return Ok(parts);
+            }
+        }
+    }
+}
+
+/// `READY` command body announcing a SUB socket.
+fn ready_command() -> Vec<u8> {
+    let mut body = vec![5];
+    body.extend_from_slice(b"READY");
+    body.push(11);
+    body.extend_from_slice(b"Socket-Type");
+    body.extend_from_slice(&3u32.to_be_bytes());
+    body.extend_from_slice(b"SUB");
+    body
+}
+
+/// Reconnecting subscriber state behind [`events`].
+struct Listener {
+    endpoint: String,
+    topics: Vec<Topic>,
+    sub: Option<Subscriber>,
+    /// Consecutive failed connection attempts.
+    failures: u32,
+}
+
+impl Listener {
+    async fn next(&mut self) -> Event {
+        loop {
+            if let Some(sub) = &mut self.sub {
+                match sub.recv().await {
+                    Ok(parts) => {
+                        if let Some(event) = parse(&parts) {
+                            return event;
+                        }
+                    }
+                    Err(e) => {
+                        self.sub = None;
+                        self.failures = FAILURES_BEFORE_REPORT;
+                        return Event::Disconnected {
+                            endpoint: self.endpoint.clone(),
+                            error: format!("{e:#}"),
+                        };
+                    }
+                }
+                continue;
+            }
+            if self.failures > 0 {
+                tokio::time::sleep(RETRY_DELAY).await;
+            }
+            match Subscriber::connect(&self.endpoint, &self.topics).await {
+                Ok(sub) => {
+                    self.sub = Some(sub);
+                    self.failures = 0;
+                    return Event::Connected(self.endpoint.clone());
+                }
+                Err(e) => {
+                    self.failures += 1;
+                    if self.failures == FAILURES_BEFORE_REPORT {
+                        return Event::Disconnected {
+                            endpoint: self.endpoint.clone(),
+                            error: format!("{e:#}"),
+                        };
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Notifications from `endpoint`, reconnecting for as long as the stream is
+/// polled.  Connection failures are reported once until the next success.
+pub fn events(endpoint: String, topics: Vec<Topic>) -> impl Stream<Item = Event> {
+    let listener = Listener {
+        endpoint,
+        topics,
+        sub: None,
+        failures: 0,
+    };
+    stream::unfold(listener, |mut l| async move {
+        let event = l.next().await;
+        Some((event, l))
+    })
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled() -> ZmqConfig {
+        ZmqConfig {
+            enabled: true,
+            rawtx: String::new(),
+            sequence: "tcp://127.0.0.1:28332".into(),
+            ..ZmqConfig::default()
+        }
+    }
+
+    #[test]
+    fn groups_topics_by_endpoint() {
+        assert!(subscriptions(&ZmqConfig::default()).is_empty());
+        assert_eq!(
+            subscriptions(&enabled()),
+            vec![(
+                "tcp://127.0.0.1:28332".to_owned(),
+                vec![Topic::HashBlock, Topic::Sequence]
+            )]
+        );
+    }
+
+    #[test]
+    fn replaces_managed_conf_block_before_sections() {
+        let conf = "server=1\n[test]\nrpcport=18332\n";
+        let with = conf_with_endpoints(conf, &enabled());
+        assert_eq!(
+            with,
+            format!(
+                "server=1\n{CONF_BEGIN}\nzmqpubhashblock=tcp://127.0.0.1:28332\n\
+                 zmqpubsequence=tcp://127.0.0.1:28332\n{CONF_END}\n[test]\nrpcport=18332\n"
+            )
+        );
+        // Idempotent, and removed again when disabled.
+        assert_eq!(conf_with_endpoints(&with, &enabled()), with);
+        assert_eq!(conf_with_endpoints(&with, &ZmqConfig::default()), conf);
+    }
+
+    #[tokio::test]
+    async fn subscribes_and_decodes_notifications() {
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("tcp://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut s, _) = listener.accept().await.unwrap();
+            let mut greeting = [0u8; 64];
+            s.read_exact(&mut greeting).await.unwrap();
+            assert_eq!(&greeting[12..16], b"NULL");
+            let mut reply = [0u8; 64];
+            reply[0] = 0xff;
+            reply[9] = 0x7f;
+            reply[10] = 3;
+            reply[11] = 1;
+            reply[12..16].copy_from_slice(b"NULL");
+            s.write_all(&reply).await.unwrap();
+
+            // Client READY, then its subscription.
+            let mut ready = [0u8; 2 + 25];
+            s.read_exact(&mut ready).await.unwrap();
+            assert_eq!(&ready[..2], &[FLAG_COMMAND, 25]);
+            let mut ours = vec![5];
+            ours.extend_from_slice(b"READY");
+            ours.push(11);
+            ours.extend_from_slice(b"Socket-Type");
+            ours.extend_from_slice(&3u32.to_be_bytes());
+            ours.extend_from_slice(b"PUB");
+            s.write_all(&[FLAG_COMMAND, 25]).await.unwrap();
+            s.write_all(&ours).await.unwrap();
+            let mut subscribe = [0u8; 2 + 9];
+            s.read_exact(&mut subscribe).await.unwrap();
+            assert_eq!(&subscribe[2..], b"\x01sequence");
+
+            let mut body = vec![0xab; 32];
+            body.push(b'A');
+            body.extend_from_slice(&7u64.to_le_bytes());
+            s.write_all(&[FLAG_MORE, 8]).await.unwrap();
+            s.write_all(b"sequence").await.unwrap();
+            s.write_all(&[FLAG_MORE, 41]).await.unwrap();
+            s.write_all(&body).await.unwrap();
+            s.write_all(&[0, 4]).await.unwrap();
+            s.write_all(&1u32.to_le_bytes()).await.unwrap();
+        });
+
+        let mut sub = Subscriber::connect(&endpoint, &[Topic::Sequence])
+            .await
+            .unwrap();
+        let parts = sub.recv().await.unwrap();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parse(&parts), Some(Event::TxAdded("ab".repeat(32))));
+        server.await.unwrap();
+    }
+}